@@ -0,0 +1,299 @@
+use std::ops::Range;
+
+use num::{Float, Zero};
+
+use nalgebra::{DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use NalgebraLapackResult;
+
+/// The eigenvalues selected by `BandedMatrix::selected_eigensystem()`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EigenvalueRange<N> {
+    /// All the eigenvalues.
+    All,
+    /// The eigenvalues with these indices, counting from zero in ascending order. For example,
+    /// `Index(0..3)` selects the three smallest.
+    Index(Range<usize>),
+    /// The eigenvalues in the half-open interval `(lower, upper]`.
+    Value(N, N),
+}
+
+/// A real symmetric band matrix, stored in LAPACK's lower band storage.
+///
+/// An `n x n` matrix with `kd` subdiagonals (and, by symmetry, `kd` superdiagonals) is stored
+/// as a `(kd + 1) x n` matrix `ab` with `ab[(i - j, j)] = a[(i, j)]` for
+/// `j <= i <= min(n - 1, j + kd)`. This needs only `O(n kd)` memory, and the band routines
+/// exploit the structure, e.g. for finite element matrices.
+#[derive(Clone)]
+pub struct BandedMatrix<N> {
+    ab: DMatrix<N>,
+}
+
+impl<N: Copy + Zero> BandedMatrix<N> {
+    /// build a band matrix from the lower triangle of a dense symmetric matrix.
+    ///
+    /// Only the elements of the lower triangle within `kd` of the diagonal are read; the
+    /// others are assumed to be zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The dense matrix.
+    /// * `kd` - The number of subdiagonals.
+    ///
+    /// # Returns
+    ///
+    /// * `banded` - The band matrix, or a `MatrixNotSquare` error.
+    pub fn from_dense(a: &DMatrix<N>, kd: usize) -> NalgebraLapackResult<BandedMatrix<N>> {
+        let n = a.nrows();
+        if a.ncols() != n {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        let ab = DMatrix::from_fn(kd + 1, n, |d, j| {
+            if j + d < n { a[(j + d, j)] } else { N::zero() }
+        });
+        Ok(BandedMatrix { ab })
+    }
+
+    /// build a symmetric tridiagonal matrix, a band matrix with one subdiagonal.
+    ///
+    /// # Arguments
+    ///
+    /// * `d` - The diagonal, of length `n`.
+    /// * `e` - The subdiagonal, of length `n - 1`, e.g. as returned by
+    ///   `SymmetricTridiagonalize::symmetric_tridiagonalize()`.
+    ///
+    /// # Returns
+    ///
+    /// * `banded` - The band matrix, or a `DimensionMismatch` error.
+    pub fn from_tridiagonal(d: &DVector<N>, e: &DVector<N>)
+        -> NalgebraLapackResult<BandedMatrix<N>> {
+        let n = d.len();
+        if e.len() + 1 != n && !(n == 0 && e.len() == 0) {
+            return Err(Error::from(ErrorKind::DimensionMismatch));
+        }
+        let ab = DMatrix::from_fn(2, n, |r, j| {
+            match r {
+                0 => d[j],
+                _ => if j + 1 < n { e[j] } else { N::zero() },
+            }
+        });
+        Ok(BandedMatrix { ab })
+    }
+
+    /// build a band matrix from its lower band storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `ab` - The `(kd + 1) x n` band storage, with the diagonal in the first row and the
+    ///   `d`-th subdiagonal in row `d`. The last `d` elements of row `d` are not used.
+    ///
+    /// # Returns
+    ///
+    /// * `banded` - The band matrix, or a `DimensionMismatch` error if `ab` has no rows.
+    pub fn from_band_storage(ab: DMatrix<N>) -> NalgebraLapackResult<BandedMatrix<N>> {
+        if ab.nrows() == 0 {
+            return Err(Error::from(ErrorKind::DimensionMismatch));
+        }
+        Ok(BandedMatrix { ab })
+    }
+
+    /// return the lower band storage.
+    pub fn band_storage(&self) -> &DMatrix<N> {
+        &self.ab
+    }
+
+    /// return the dimension `n` of the square matrix.
+    pub fn dim(&self) -> usize {
+        self.ab.ncols()
+    }
+
+    /// return the number of subdiagonals `kd`.
+    pub fn bandwidth(&self) -> usize {
+        // The storage has at least one row, for the diagonal, as the constructors check.
+        self.ab.nrows() - 1
+    }
+
+    /// expand into a dense symmetric matrix.
+    pub fn to_dense(&self) -> DMatrix<N> {
+        let (n, kd) = (self.dim(), self.bandwidth());
+        DMatrix::from_fn(n, n, |i, j| {
+            let (i, j) = if i >= j { (i, j) } else { (j, i) };
+            if i - j <= kd { self.ab[(i - j, j)] } else { N::zero() }
+        })
+    }
+}
+
+macro_rules! banded_impl(
+    ($t: ty, $sbgv: path, $stebz: path, $stein: path) => (
+        impl BandedMatrix<$t> {
+            /// solve the generalized symmetric-definite eigenproblem `A x = lambda B x` for a
+            /// pair of band matrices.
+            ///
+            /// `A` is this matrix and `B` must be positive definite. The bandedness of both
+            /// is exploited, which makes this much cheaper than a dense solver for large
+            /// matrices with narrow bands, such as the stiffness and mass matrices of
+            /// structural problems. `?sbgv` needs `A` to have at least as many subdiagonals
+            /// as `B`, so a narrower `A` is padded with zero subdiagonals.
+            ///
+            /// Because the input matrices may be overwritten or destroyed, they are consumed.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The positive-definite band matrix, of the same dimension.
+            /// * `compute_vectors` - Whether to compute the eigenvectors.
+            ///
+            /// # Returns
+            ///
+            /// * `eigen_values` - The real eigenvalues, in ascending order.
+            /// * `eigen_vectors` - If requested, the eigenvectors as columns, in the same order
+            ///   as the eigenvalues and normalized so that `Z^T B Z = I`.
+            ///
+            /// A `DimensionMismatch` error is returned if the dimensions differ, and a
+            /// `MatrixNotPositiveDefinite` error if `B` is not positive definite.
+            pub fn generalized_eigensystem(self, b: BandedMatrix<$t>, compute_vectors: bool)
+                -> NalgebraLapackResult<(DVector<$t>, Option<DMatrix<$t>>)> {
+                let jobz = if compute_vectors { b'V' } else { b'N' };
+                let uplo = b'L';
+
+                let n = self.dim();
+                if b.dim() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let kb = b.bandwidth();
+                let ka = ::std::cmp::max(self.bandwidth(), kb);
+                let mut ab = if self.bandwidth() < ka {
+                    let a = &self.ab;
+                    DMatrix::from_fn(ka + 1, n, |d, j| if d < a.nrows() { a[(d, j)] } else { 0.0 })
+                } else {
+                    self.ab
+                };
+                let mut bb = b.ab;
+
+                let mut w: Vec<$t> = vec![0.0; n];
+                let ldz = if compute_vectors { ::std::cmp::max(1, n) } else { 1 };
+                let mut z: DMatrix<$t> =
+                    DMatrix::new_zeros(ldz, if compute_vectors { n } else { 1 });
+                let mut work: Vec<$t> = vec![0.0; ::std::cmp::max(1, 3 * n)];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($sbgv), n, n, &[jobz, uplo]);
+                $sbgv(jobz, uplo, n as i32, ka as i32, kb as i32, ab.as_mut_vector(),
+                    (ka + 1) as i32, bb.as_mut_vector(), (kb + 1) as i32, &mut w,
+                    z.as_mut_vector(), ldz as i32, &mut work, &mut info);
+                // A positive `info` above `n` means that the factorization of `B` failed.
+                if info > n as i32 {
+                    return Err(Error::from(ErrorKind::MatrixNotPositiveDefinite));
+                }
+                check_info!(info);
+
+                Ok((DVector { at: w }, if compute_vectors { Some(z) } else { None }))
+            }
+
+            /// compute selected eigenvalues, and optionally eigenvectors, of a symmetric
+            /// tridiagonal matrix by bisection.
+            ///
+            /// The eigenvalues are found with `?stebz`, which only costs `O(n)` per eigenvalue,
+            /// so this is much cheaper than a full eigensolve when a few eigenvalues of a large
+            /// matrix are needed. The eigenvectors are then found by inverse iteration with
+            /// `?stein`. The matrix must have at most one subdiagonal, e.g. built with
+            /// `from_tridiagonal()`.
+            ///
+            /// # Arguments
+            ///
+            /// * `range` - The eigenvalues to compute.
+            /// * `compute_vectors` - Whether to compute the eigenvectors.
+            ///
+            /// # Returns
+            ///
+            /// * `eigen_values` - The selected eigenvalues, in ascending order.
+            /// * `eigen_vectors` - If requested, the orthonormal eigenvectors as columns, in the
+            ///   same order as the eigenvalues.
+            ///
+            /// A `DimensionMismatch` error is returned if the matrix has more than one
+            /// subdiagonal or an index range extends past `n`.
+            pub fn selected_eigensystem(&self, range: EigenvalueRange<$t>, compute_vectors: bool)
+                -> NalgebraLapackResult<(DVector<$t>, Option<DMatrix<$t>>)> {
+                let order = b'B';
+
+                let n = self.dim();
+                if self.bandwidth() > 1 {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let (range_code, vl, vu, il, iu) = match range {
+                    EigenvalueRange::All => (b'A', 0.0, 0.0, 0, 0),
+                    EigenvalueRange::Index(ref r) => {
+                        if r.start > r.end || r.end > n {
+                            return Err(Error::from(ErrorKind::DimensionMismatch));
+                        }
+                        (b'I', 0.0, 0.0, r.start + 1, r.end)
+                    }
+                    EigenvalueRange::Value(lower, upper) => (b'V', lower, upper, 0, 0),
+                };
+                let empty = match range {
+                    EigenvalueRange::Index(ref r) => r.start == r.end,
+                    _ => n == 0,
+                };
+                if empty {
+                    let vectors = if compute_vectors {
+                        Some(DMatrix::new_zeros(n, 0))
+                    } else {
+                        None
+                    };
+                    return Ok((DVector { at: vec![] }, vectors));
+                }
+
+                let d: Vec<$t> = (0..n).map(|j| self.ab[(0, j)]).collect();
+                let e: Vec<$t> = (0..n).map(|j| {
+                    if self.bandwidth() == 1 { self.ab[(1, j)] } else { 0.0 }
+                }).collect();
+                // Twice the underflow threshold, as recommended for inverse iteration.
+                let abstol = 2.0 * <$t as Float>::min_positive_value();
+
+                let mut found = 0;
+                let mut nsplit = [0];
+                let mut w: Vec<$t> = vec![0.0; n];
+                let mut iblock = vec![0; n];
+                let mut isplit = vec![0; n];
+                let mut work: Vec<$t> = vec![0.0; 4 * n];
+                let mut iwork = vec![0; 3 * n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($stebz), n, n, &[range_code, order]);
+                $stebz(range_code, order, n as i32, vl, vu, il as i32, iu as i32, abstol, &d,
+                    &e, &mut found, &mut nsplit, &mut w, &mut iblock, &mut isplit, &mut work,
+                    &mut iwork, &mut info);
+                check_info!(info);
+                let m = found as usize;
+
+                // With `order = 'B'` the eigenvalues are only sorted within each block.
+                let mut perm: Vec<usize> = (0..m).collect();
+                perm.sort_by(|&i, &j| w[i].partial_cmp(&w[j]).unwrap());
+                let values = DVector { at: perm.iter().map(|&i| w[i]).collect() };
+                if !compute_vectors {
+                    return Ok((values, None));
+                }
+
+                let mut z: DMatrix<$t> = DMatrix::new_zeros(n, m);
+                let mut work: Vec<$t> = vec![0.0; 5 * n];
+                let mut iwork = vec![0; n];
+                let mut ifail = vec![0; ::std::cmp::max(1, m)];
+
+                // The binding declares `ifail` as a scalar, but LAPACK writes `m` elements to
+                // it, so the first element of a large enough buffer is passed.
+                ::diagnostics::record(stringify!($stein), n, m, &[]);
+                $stein(n as i32, &d, &e, found, &w, &iblock, &isplit, z.as_mut_vector(),
+                    n as i32, &mut work, &mut iwork, &mut ifail[0], &mut info);
+                check_info!(info);
+
+                let vectors = DMatrix::from_fn(n, m, |i, j| z[(i, perm[j])]);
+                Ok((values, Some(vectors)))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+banded_impl!(f32, interface::ssbgv, interface::sstebz, interface::sstein);
+banded_impl!(f64, interface::dsbgv, interface::dstebz, interface::dstein);
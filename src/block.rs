@@ -0,0 +1,133 @@
+use std::ops::Range;
+
+use num::complex::Complex;
+
+use nalgebra::{DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use {GeevInPlace, GesvdInPlace, NalgebraLapackResult, SVD};
+
+/// A rectangular block of a larger matrix, to be decomposed in place.
+///
+/// LAPACK addresses a column-major matrix through its leading dimension `lda`, the distance
+/// between the starts of consecutive columns, so any block of contiguous rows and columns of a
+/// `DMatrix` can be passed to it directly, with the `lda` of the whole matrix. The `svd()` and
+/// `eigensystem()` of a block do this instead of first copying the block into a matrix of its
+/// own.
+///
+/// Like the decompositions of a `DMatrix`, those of a block destroy their input, here the
+/// elements of the block within the larger matrix; the rest of it is left unchanged. A copy,
+/// e.g. with `to_matrix()`, is still required to keep the elements, for data stored row by
+/// row (a transposed view, whose rows are not contiguous in memory), and for complex
+/// matrices, for which blocks are not supported. Blocks also always use the LAPACK routine,
+/// while `SVD::svd()` of a small matrix uses a closed-form solver.
+pub struct MatrixBlock<'a, N: 'a> {
+    data: &'a mut [N],
+    nrows: usize,
+    ncols: usize,
+    lda: usize,
+}
+
+impl<'a, N: Copy> MatrixBlock<'a, N> {
+    /// select the block of `a` with the given rows and columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `a` - The larger matrix.
+    /// * `rows` - The range of rows of the block.
+    /// * `cols` - The range of columns of the block.
+    ///
+    /// # Returns
+    ///
+    /// * `block` - The block. A `DimensionMismatch` error is returned if a range extends
+    ///   past the end of `a`.
+    pub fn new(a: &'a mut DMatrix<N>, rows: Range<usize>, cols: Range<usize>)
+        -> NalgebraLapackResult<MatrixBlock<'a, N>> {
+        let lda = a.nrows();
+        if rows.start > rows.end || rows.end > lda || cols.start > cols.end ||
+           cols.end > a.ncols() {
+            return Err(Error::from(ErrorKind::DimensionMismatch));
+        }
+        let nrows = rows.end - rows.start;
+        let ncols = cols.end - cols.start;
+        // An empty block may start past the end of the storage, e.g. at the far corner.
+        let (start, end) = if nrows == 0 || ncols == 0 {
+            (0, 0)
+        } else {
+            let start = rows.start + cols.start * lda;
+            (start, start + (ncols - 1) * lda + nrows)
+        };
+        Ok(MatrixBlock {
+            data: &mut a.as_mut_vector()[start..end],
+            nrows,
+            ncols,
+            lda,
+        })
+    }
+
+    /// the number of rows of the block.
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    /// the number of columns of the block.
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    /// the leading dimension, i.e. the number of rows of the larger matrix.
+    pub fn leading_dimension(&self) -> usize {
+        self.lda
+    }
+
+    /// copy the block into a matrix of its own.
+    pub fn to_matrix(&self) -> DMatrix<N> {
+        DMatrix::from_fn(self.nrows, self.ncols, |i, j| self.data[i + j * self.lda])
+    }
+}
+
+macro_rules! matrix_block_impl(
+    ($t: ty) => (
+        impl<'a> MatrixBlock<'a, $t> {
+            /// compute the singular value decomposition (SVD) of the block in place.
+            ///
+            /// This is `SVD::svd()` of the block, which is destroyed.
+            ///
+            /// # Returns
+            ///
+            /// * `u` - The left-singular vectors.
+            /// * `s` - The singular values.
+            /// * `vt` - The right-singular vectors.
+            pub fn svd(self) -> NalgebraLapackResult<(DMatrix<$t>, DVector<$t>, DMatrix<$t>)> {
+                if self.nrows == 0 || self.ncols == 0 {
+                    // The trivial SVD, which does not call LAPACK.
+                    return DMatrix::<$t>::new_zeros(self.nrows, self.ncols).svd();
+                }
+                <$t as GesvdInPlace>::gesvd_in_place(self.data, self.nrows, self.ncols, self.lda)
+            }
+
+            /// compute eigenvalues and right eigenvectors of the square block in place.
+            ///
+            /// This is `Eigensystem::eigensystem()` of the block, which is destroyed.
+            ///
+            /// # Returns
+            ///
+            /// * `eigen_values` - The eigenvalues.
+            /// * `right_eigen_vectors` - The right eigenvectors. They are contained as columns
+            ///   of this matrix.
+            pub fn eigensystem(self)
+                -> NalgebraLapackResult<(DVector<Complex<$t>>, DMatrix<Complex<$t>>)> {
+                if self.nrows != self.ncols {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if self.nrows == 0 {
+                    return Ok((DVector { at: vec![] }, DMatrix::new_zeros(0, 0)));
+                }
+                <$t as GeevInPlace>::geev_in_place(self.data, self.nrows, self.lda)
+            }
+        }
+    );
+);
+
+matrix_block_impl!(f32);
+matrix_block_impl!(f64);
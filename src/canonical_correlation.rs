@@ -0,0 +1,146 @@
+use num::Float;
+
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use {LapackScalar, NalgebraLapackResult, Orthonormalize, SVD, SvdOptions, SvdWith};
+
+/// compute the canonical correlations between two sets of variables.
+///
+/// Canonical correlation analysis finds weights `w_x` and `w_y` maximizing the correlation
+/// between the canonical variates `X_c w_x` and `Y_c w_y`, where `X_c` and `Y_c` are the data
+/// with the column means subtracted; then the next pair uncorrelated with the first, and so
+/// on. This uses the SVD formulation of Bjorck and Golub, which is equivalent to the
+/// generalized SVD of the two data matrices: with the economy SVDs `X_c = U_x S_x V_x^T` and
+/// `Y_c = U_y S_y V_y^T`, the canonical correlations are the singular values of
+/// `U_x^T U_y = U S V^T`, i.e. the cosines of the principal angles between the column spaces
+/// of `X_c` and `Y_c`. The weights are then `W_x = V_x S_x^-1 U` and `W_y = V_y S_y^-1 V`.
+/// Directions of singular values below `max(n, p) eps` times the largest are discarded, so
+/// collinear variables are handled.
+///
+/// # Arguments
+///
+/// * `x` - The `n x p` first data set, one observation per row.
+/// * `y` - The `n x q` second data set, with the same observations as rows.
+///
+/// # Returns
+///
+/// * `correlations` - The `k` canonical correlations in descending order, where `k` is the
+///   smaller of the ranks of `X_c` and `Y_c`.
+/// * `w_x` - The `p x k` canonical weights of `x`, as columns. The canonical variates `X_c
+///   w_x` have unit norm (not unit variance) and are mutually orthogonal.
+/// * `w_y` - The `q x k` canonical weights of `y`, as columns, normalized in the same way.
+pub fn canonical_correlations<N>(x: DMatrix<N>, y: DMatrix<N>)
+    -> NalgebraLapackResult<(DVector<N>, DMatrix<N>, DMatrix<N>)>
+    where N: BaseFloat + LapackScalar<Real = N>,
+          DMatrix<N>: SvdWith<N = N>
+{
+    let n = x.nrows();
+    if y.nrows() != n {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let (p, q) = (x.ncols(), y.ncols());
+    let (ux, wx) = whiten(center(x))?;
+    let (uy, wy) = whiten(center(y))?;
+    let (rx, ry) = (ux.ncols(), uy.ncols());
+    let k = if rx <= ry { rx } else { ry };
+    if k == 0 {
+        return Ok((DVector { at: vec![] }, DMatrix::new_zeros(p, 0), DMatrix::new_zeros(q, 0)));
+    }
+
+    let cross = DMatrix::from_fn(rx, ry, |i, j| {
+        (0..n).fold(N::zero(), |acc, t| acc + ux[(t, i)] * uy[(t, j)])
+    });
+    let options = SvdOptions { economy: true, ..SvdOptions::default() };
+    let svd = cross.svd_with(options)?;
+    let u = svd.u.expect("the left singular vectors were requested");
+    let vt = svd.vt.expect("the right singular vectors were requested");
+
+    // Rounding can push the cosine of a zero angle slightly above one.
+    let correlations = svd.s.at[..k].iter().map(|s| s.min(N::one())).collect();
+    let w_x = DMatrix::from_fn(p, k, |i, j| {
+        (0..rx).fold(N::zero(), |acc, l| acc + wx[(i, l)] * u[(l, j)])
+    });
+    let w_y = DMatrix::from_fn(q, k, |i, j| {
+        (0..ry).fold(N::zero(), |acc, l| acc + wy[(i, l)] * vt[(j, l)])
+    });
+    Ok((DVector { at: correlations }, w_x, w_y))
+}
+
+/// compute the principal angles between the column spaces of two matrices.
+///
+/// Both sets of columns are orthonormalized with `Orthonormalize::orthonormalize()`, giving
+/// `Q_a` and `Q_b`, and the cosines of the principal angles are the singular values of
+/// `Q_a^T Q_b`. The largest angle is the usual measure of the distance between subspaces, e.g.
+/// before and after a perturbation. Taking the arccosine limits the accuracy of angles
+/// smaller than about `sqrt(eps)`.
+///
+/// # Arguments
+///
+/// * `a` - The `m x p` matrix whose columns span the first subspace.
+/// * `b` - The `m x q` matrix whose columns span the second subspace.
+///
+/// # Returns
+///
+/// * `angles` - The `min(rank(a), rank(b))` principal angles in radians, in ascending order
+///   in `[0, pi / 2]`.
+pub fn principal_angles<N>(a: DMatrix<N>, b: DMatrix<N>) -> NalgebraLapackResult<DVector<N>>
+    where N: BaseFloat,
+          DMatrix<N>: Orthonormalize<N = N> + SVD<M = N, V = N>
+{
+    let m = a.nrows();
+    if b.nrows() != m {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let qa = a.orthonormalize()?;
+    let qb = b.orthonormalize()?;
+    let (ra, rb) = (qa.ncols(), qb.ncols());
+    let k = if ra <= rb { ra } else { rb };
+    if k == 0 {
+        return Ok(DVector { at: vec![] });
+    }
+    let cross = DMatrix::from_fn(ra, rb, |i, j| {
+        (0..m).fold(N::zero(), |acc, t| acc + qa[(t, i)] * qb[(t, j)])
+    });
+    let (_, s, _) = cross.svd()?;
+    // Rounding can push a cosine slightly above one.
+    Ok(DVector { at: s.at[..k].iter().map(|c| c.min(N::one()).acos()).collect() })
+}
+
+/// Subtract the mean of each column.
+fn center<N: BaseFloat>(x: DMatrix<N>) -> DMatrix<N> {
+    let (n, p) = (x.nrows(), x.ncols());
+    if n == 0 {
+        return x;
+    }
+    let count = <N as Cast<f64>>::from(n as f64);
+    let means: Vec<N> = (0..p)
+        .map(|j| (0..n).fold(N::zero(), |acc, i| acc + x[(i, j)]) / count)
+        .collect();
+    DMatrix::from_fn(n, p, |i, j| x[(i, j)] - means[j])
+}
+
+/// From the economy SVD `X = U S V^T` of rank `r`, return the first `r` columns of `U`, an
+/// orthonormal basis of the column space, and `V S^-1` restricted to them, which maps `X` onto
+/// that basis.
+fn whiten<N>(x: DMatrix<N>) -> NalgebraLapackResult<(DMatrix<N>, DMatrix<N>)>
+    where N: BaseFloat + LapackScalar<Real = N>,
+          DMatrix<N>: SvdWith<N = N>
+{
+    let (n, p) = (x.nrows(), x.ncols());
+    if n == 0 || p == 0 {
+        return Ok((DMatrix::new_zeros(n, 0), DMatrix::new_zeros(p, 0)));
+    }
+    let options = SvdOptions { economy: true, ..SvdOptions::default() };
+    let svd = x.svd_with(options)?;
+    let u = svd.u.expect("the left singular vectors were requested");
+    let vt = svd.vt.expect("the right singular vectors were requested");
+    let s = svd.s;
+
+    let largest = if n >= p { n } else { p };
+    let tol = <N as Cast<f64>>::from(largest as f64) * <N as Float>::epsilon() * s[0];
+    let r = s.at.iter().take_while(|&&sigma| sigma > tol).count();
+    let basis = DMatrix::from_fn(n, r, |i, j| u[(i, j)]);
+    let weights = DMatrix::from_fn(p, r, |i, j| vt[(j, i)] / s[j]);
+    Ok((basis, weights))
+}
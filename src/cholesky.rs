@@ -0,0 +1,275 @@
+use num::complex::Complex;
+
+use nalgebra::{DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use {Cholesky, NalgebraLapackResult};
+
+/// The Cholesky factorization `C = L L^T` of a symmetric positive-definite matrix.
+///
+/// This is computed once with `CholeskyFactorize::cholesky_factorization()` and can then be
+/// reused, e.g. to evaluate many quadratic forms with the same matrix.
+#[derive(Clone)]
+pub struct CholeskyFactorization<N> {
+    l: DMatrix<N>,
+}
+
+impl<N> CholeskyFactorization<N> {
+    /// return the lower triangular factor `L`, with zeros above the diagonal.
+    pub fn lower(&self) -> &DMatrix<N> {
+        &self.l
+    }
+}
+
+/// A type for which a reusable Cholesky factorization can be computed.
+pub trait CholeskyFactorize {
+    type N;
+
+    /// compute the Cholesky factorization of a symmetric positive-definite matrix.
+    ///
+    /// This is `Cholesky::cholesky()`, keeping the factor for later use.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `cholesky` - The factorization.
+    fn cholesky_factorization(self) -> NalgebraLapackResult<CholeskyFactorization<Self::N>>;
+}
+
+impl<N> CholeskyFactorize for DMatrix<N>
+    where DMatrix<N>: Cholesky<N = N>
+{
+    type N = N;
+
+    fn cholesky_factorization(self) -> NalgebraLapackResult<CholeskyFactorization<N>> {
+        Ok(CholeskyFactorization { l: self.cholesky()? })
+    }
+}
+
+/// A type for which the pivoted Cholesky factorization of a positive-semidefinite matrix can
+/// be computed.
+pub trait PivotedCholesky {
+    type N;
+
+    /// compute the Cholesky factorization with complete pivoting `P^T A P = L L^T` of a
+    /// symmetric positive-semidefinite matrix, using `?pstrf`.
+    ///
+    /// At each step the largest remaining diagonal element is chosen as the pivot, and the
+    /// factorization stops once it is below `tol`, which reveals the rank. Unlike
+    /// `Cholesky::cholesky()`, this succeeds on singular matrices such as rank-deficient
+    /// covariance matrices. Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The pivot below which the remaining matrix is regarded as zero. A negative
+    ///   value selects LAPACK's default of `n eps` times the largest diagonal element.
+    ///
+    /// # Returns
+    ///
+    /// * `permutation` - The pivot order: `(P^T A P)_ij = A_(p_i, p_j)` with
+    ///   `p = permutation`, 0-based.
+    /// * `lower` - The `n x rank` lower trapezoidal factor `L`.
+    /// * `rank` - The detected rank of `A`.
+    fn cholesky_pivoted(self, tol: Self::N)
+        -> NalgebraLapackResult<(Vec<usize>, DMatrix<Self::N>, usize)>;
+}
+
+/// A type for which the Cholesky factor of the inverse can be computed.
+pub trait CholeskyOfInverse {
+    type N;
+
+    /// compute the lower triangular Cholesky factor `M` of the inverse, `A^-1 = M M^T`, of a
+    /// symmetric positive-definite matrix.
+    ///
+    /// This converts e.g. a precision matrix into the Cholesky factor of the covariance
+    /// matrix without forming the inverse. With the exchange matrix `J`, which reverses the
+    /// order of the rows, the flipped matrix `J A J = L L^T` is factorized, and then
+    /// `A^-1 = (J L^-T J) (J L^-1 J)` where `M = J L^-T J` is lower triangular with a positive
+    /// diagonal. This costs one Cholesky factorization and one triangular inversion with
+    /// `?trtri`, about half of inverting `A` and factorizing again. Only the lower triangle of
+    /// the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `m` - The lower triangular factor, with zeros above the diagonal, or `None` if the
+    ///   matrix is not square or not positive-definite.
+    fn cholesky_of_inverse(self) -> Option<DMatrix<Self::N>>;
+}
+
+macro_rules! cholesky_of_inverse_impl(
+    ($t: ty, $trtri: path) => (
+        impl CholeskyOfInverse for DMatrix<$t> {
+            type N = $t;
+
+            fn cholesky_of_inverse(self) -> Option<DMatrix<$t>> {
+                let uplo = b'L';
+                let diag = b'N';
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return None;
+                }
+                if n == 0 {
+                    return Some(self);
+                }
+                // Reading the lower triangle of `J A J` is reading the upper triangle of `A`,
+                // so the flipped matrix is built from the lower triangle by symmetry.
+                let flipped = DMatrix::from_fn(n, n, |i, j| {
+                    let (r, c) = (n - 1 - i, n - 1 - j);
+                    if r >= c { self[(r, c)] } else { self[(c, r)] }
+                });
+                let mut l = flipped.cholesky().ok()?;
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($trtri), n, n, &[uplo, diag]);
+                $trtri(uplo, diag, n as i32, l.as_mut_vector(), n as i32, &mut info);
+                if info != 0 {
+                    return None;
+                }
+
+                // `M = J L^-T J`, and `L^-1` is lower triangular.
+                Some(DMatrix::from_fn(n, n, |i, j| {
+                    if i >= j { l[(n - 1 - j, n - 1 - i)] } else { 0.0 }
+                }))
+            }
+        }
+    );
+);
+
+macro_rules! pivoted_cholesky_impl(
+    ($t: ty, $pstrf: path) => (
+        impl PivotedCholesky for DMatrix<$t> {
+            type N = $t;
+
+            fn cholesky_pivoted(self, tol: $t)
+                -> NalgebraLapackResult<(Vec<usize>, DMatrix<$t>, usize)> {
+                let uplo = b'L';
+                let mut a = self;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok((vec![], DMatrix::new_zeros(0, 0), 0));
+                }
+                let lda = n as i32;
+                let mut piv = vec![0; n];
+                let mut rank = 0;
+                let mut work = vec![0.0; 2 * n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($pstrf), n, n, &[uplo]);
+                $pstrf(uplo, n as i32, a.as_mut_vector(), lda, &mut piv, &mut rank, tol,
+                    &mut work, &mut info);
+                // A positive `info` only means that the matrix is rank deficient.
+                if info < 0 {
+                    return Err(Error::from(ErrorKind::LapackIllegalArgument(-info)));
+                }
+
+                let rank = rank as usize;
+                let lower = DMatrix::from_fn(n, rank, |i, j| if i >= j { a[(i, j)] } else { 0.0 });
+                let permutation = piv.iter().map(|p| (*p - 1) as usize).collect();
+                Ok((permutation, lower, rank))
+            }
+        }
+    );
+);
+
+macro_rules! cholesky_factorization_impl(
+    ($t: ty, $trtrs: path) => (
+        impl CholeskyFactorization<$t> {
+            /// evaluate the quadratic form `x^T C^-1 x` of the inverse of the factorized
+            /// matrix.
+            ///
+            /// With `C = L L^T`, this is `y^T y` for the solution of the triangular system
+            /// `L y = x`, so `C^-1` is never formed. This is the squared Mahalanobis distance
+            /// of `x` for the covariance matrix `C`.
+            ///
+            /// # Arguments
+            ///
+            /// * `x` - The vector.
+            ///
+            /// # Returns
+            ///
+            /// * `q` - The value of `x^T C^-1 x`.
+            pub fn quadratic_form(&self, x: &DVector<$t>) -> NalgebraLapackResult<$t> {
+                let uplo = b'L';
+                let trans = b'N';
+                let diag = b'N';
+                let n = self.l.nrows();
+                if x.len() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                if n == 0 {
+                    return Ok(0.0);
+                }
+                let mut y = x.at.clone();
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($trtrs), n, n, &[uplo, trans, diag]);
+                $trtrs(uplo, trans, diag, n as i32, 1, self.l.as_vector(), n as i32, &mut y,
+                    n as i32, &mut info);
+                check_info!(info);
+
+                Ok(y.iter().fold(0.0, |acc, v| acc + v * v))
+            }
+        }
+    );
+);
+
+macro_rules! cholesky_factorization_complex_impl(
+    ($t: ty, $trtrs: path) => (
+        impl CholeskyFactorization<Complex<$t>> {
+            /// evaluate the Hermitian form `x^H C^-1 x` of the inverse of the factorized
+            /// matrix.
+            ///
+            /// With `C = L L^H`, this is `y^H y` for the solution of the triangular system
+            /// `L y = x`, so `C^-1` is never formed. It is real and positive for a nonzero `x`.
+            ///
+            /// # Arguments
+            ///
+            /// * `x` - The vector.
+            ///
+            /// # Returns
+            ///
+            /// * `q` - The value of `x^H C^-1 x`.
+            pub fn quadratic_form(&self, x: &DVector<Complex<$t>>) -> NalgebraLapackResult<$t> {
+                let uplo = b'L';
+                let trans = b'N';
+                let diag = b'N';
+                let n = self.l.nrows();
+                if x.len() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                if n == 0 {
+                    return Ok(0.0);
+                }
+                let mut y = x.at.clone();
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($trtrs), n, n, &[uplo, trans, diag]);
+                $trtrs(uplo, trans, diag, n as i32, 1, self.l.as_vector(), n as i32, &mut y,
+                    n as i32, &mut info);
+                check_info!(info);
+
+                Ok(y.iter().fold(0.0, |acc, v| acc + v.norm_sqr()))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+cholesky_factorization_impl!(f32, interface::strtrs);
+cholesky_factorization_impl!(f64, interface::dtrtrs);
+cholesky_factorization_complex_impl!(f32, interface::ctrtrs);
+cholesky_factorization_complex_impl!(f64, interface::ztrtrs);
+
+pivoted_cholesky_impl!(f32, interface::spstrf);
+pivoted_cholesky_impl!(f64, interface::dpstrf);
+cholesky_of_inverse_impl!(f32, interface::strtri);
+cholesky_of_inverse_impl!(f64, interface::dtrtri);
@@ -0,0 +1,81 @@
+//! Glue for writing tolerant matrix assertions with the `matrixcompare` crate.
+//!
+//! This module is only available with the `matrixcompare` feature. A failed assertion reports
+//! every element outside the tolerance, rather than only that the matrices differ.
+
+use matrixcompare_core::{Access, DenseAccess, Matrix};
+
+use nalgebra::DMatrix;
+
+/// A borrowed matrix which can be passed to the `matrixcompare` assertions.
+///
+/// `matrixcompare` cannot compare `DMatrix` directly, so the matrices are wrapped with
+/// `comparable()`. The `assert_matrix_approx_eq!` and `assert_reconstructs!` macros do this
+/// for you.
+pub struct Comparable<'a, N: 'a>(&'a DMatrix<N>);
+
+/// wrap a matrix for comparison with `matrixcompare`.
+pub fn comparable<N>(m: &DMatrix<N>) -> Comparable<N> {
+    Comparable(m)
+}
+
+impl<'a, N: Copy> Matrix<N> for Comparable<'a, N> {
+    fn rows(&self) -> usize {
+        self.0.nrows()
+    }
+
+    fn cols(&self) -> usize {
+        self.0.ncols()
+    }
+
+    fn access(&self) -> Access<N> {
+        Access::Dense(self)
+    }
+}
+
+impl<'a, N: Copy> DenseAccess<N> for Comparable<'a, N> {
+    fn fetch_single(&self, row: usize, col: usize) -> N {
+        self.0[(row, col)]
+    }
+}
+
+/// assert that two real matrices are equal to within an absolute tolerance on each element.
+///
+/// On failure, the panic message lists the elements which differ by more than the tolerance.
+///
+/// # Arguments
+///
+/// * `a` - The first matrix. It is borrowed.
+/// * `b` - The second matrix. It is borrowed.
+/// * `tol` - The largest allowed absolute difference between corresponding elements.
+#[macro_export]
+macro_rules! assert_matrix_approx_eq(
+    ($a: expr, $b: expr, $tol: expr) => (
+        $crate::matrixcompare::assert_matrix_eq!($crate::compare::comparable(&$a),
+                                                 $crate::compare::comparable(&$b),
+                                                 comp = abs, tol = $tol)
+    );
+);
+
+/// assert that a decomposition reconstructs a real matrix to within an absolute tolerance on
+/// each element.
+///
+/// The decomposition is anything with a `reconstruct()` method returning
+/// `Option<DMatrix<N>>`, such as `SvdResult`. The assertion also fails if the decomposition
+/// does not have the factors needed to reconstruct the matrix.
+///
+/// # Arguments
+///
+/// * `decomposition` - The decomposition. It is borrowed.
+/// * `original` - The matrix which was decomposed. It is borrowed.
+/// * `tol` - The largest allowed absolute difference between corresponding elements.
+#[macro_export]
+macro_rules! assert_reconstructs(
+    ($decomposition: expr, $original: expr, $tol: expr) => (
+        match $decomposition.reconstruct() {
+            Some(reconstructed) => $crate::assert_matrix_approx_eq!(reconstructed, $original,
+                                                                    $tol),
+            None => panic!("the decomposition does not have the factors to reconstruct the matrix"),
+        }
+    );
+);
@@ -0,0 +1,66 @@
+use std::cell::{Cell, RefCell};
+
+/// A record of a call to a LAPACK decomposition routine, as returned by `last_routine()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoutineCall {
+    /// The name of the routine, e.g. `"dgesvd"`.
+    pub name: &'static str,
+    /// The number of rows of the decomposed matrix.
+    pub nrows: usize,
+    /// The number of columns of the decomposed matrix.
+    pub ncols: usize,
+    /// The leading dimension with which the matrix was passed: its number of rows, or that
+    /// of the larger matrix for a `MatrixBlock` decomposed in place.
+    pub lda: usize,
+    /// The character arguments selecting what is computed, in the order of the routine's
+    /// signature, e.g. `JOBU` and `JOBVT` for `?gesvd`.
+    pub jobs: Vec<u8>,
+}
+
+thread_local!(static LAST_ROUTINE: RefCell<Option<RoutineCall>> = const { RefCell::new(None) });
+thread_local!(static ROUTINE_CALLS: Cell<usize> = const { Cell::new(0) });
+
+/// return the LAPACK routine most recently called by a decomposition on this thread.
+///
+/// This is meant for debugging, e.g. to check that an economy SVD really called `dgesvd`
+/// with `JOBU = 'S'`. The decompositions (eigensystems, SVDs, Cholesky, LU and QR
+/// factorizations) record the routine that computes them; solvers and inputs that are
+/// handled without calling LAPACK leave the record unchanged.
+///
+/// # Returns
+///
+/// * `call` - The most recent call, or `None` if no decomposition was computed yet on this
+///   thread.
+pub fn last_routine() -> Option<RoutineCall> {
+    LAST_ROUTINE.with(|last| last.borrow().clone())
+}
+
+/// return the number of LAPACK routine calls recorded by decompositions on this thread.
+///
+/// The same calls as for `last_routine()` are counted, so the difference between two
+/// readings shows e.g. whether a cached decomposition was reused or computed again.
+pub fn routine_call_count() -> usize {
+    ROUTINE_CALLS.with(|count| count.get())
+}
+
+/// Record a call of `routine`, the stringified path of the function passed to an implementing
+/// macro, such as `interface::dgesvd`.
+pub fn record(routine: &'static str, nrows: usize, ncols: usize, jobs: &[u8]) {
+    record_with_lda(routine, nrows, ncols, nrows, jobs)
+}
+
+/// Record a call of `routine` on a matrix passed with the leading dimension `lda`.
+pub fn record_with_lda(routine: &'static str, nrows: usize, ncols: usize, lda: usize,
+                       jobs: &[u8]) {
+    let name = routine.rsplit(':').next().unwrap_or(routine).trim();
+    ROUTINE_CALLS.with(|count| count.set(count.get() + 1));
+    LAST_ROUTINE.with(|last| {
+        *last.borrow_mut() = Some(RoutineCall {
+            name,
+            nrows,
+            ncols,
+            lda,
+            jobs: jobs.to_vec(),
+        });
+    });
+}
@@ -0,0 +1,115 @@
+use num::complex::Complex;
+
+use nalgebra::{BaseFloat, DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use {Eigensystem, NalgebraLapackResult, SVD};
+
+/// A type whose SVD and eigensystem can be read off directly when it is diagonal.
+pub trait DiagonalFastPath {
+    type N;
+
+    /// compute the singular value decomposition, without calling LAPACK if the matrix is
+    /// diagonal within `tol`.
+    ///
+    /// The matrix is taken as diagonal if every element off the diagonal has a magnitude of at
+    /// most `tol` times the largest magnitude of all the elements; a `tol` of zero requires the
+    /// off-diagonal elements to be exactly zero. The SVD of the diagonal part is then formed
+    /// directly: the singular values are the magnitudes of the diagonal elements sorted in
+    /// descending order, the right-singular vectors are the corresponding unit vectors, and
+    /// the left-singular vectors are the same unit vectors multiplied by the signs of the
+    /// diagonal elements. The off-diagonal elements within the tolerance are ignored, so they
+    /// perturb the result by at most their norm. Otherwise, this is `SVD::svd()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The relative tolerance for the off-diagonal elements.
+    ///
+    /// # Returns
+    ///
+    /// The same as `SVD::svd()`.
+    fn svd_diagonal_fast(self, tol: Self::N)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute eigenvalues and right eigenvectors, without calling LAPACK if the matrix is
+    /// diagonal within `tol`.
+    ///
+    /// The tolerance is that of `svd_diagonal_fast()`. The eigenvalues of the diagonal part
+    /// are its diagonal elements, in the same order, and the eigenvectors are the columns of
+    /// the identity matrix, as LAPACK returns them for an exactly diagonal matrix. Otherwise,
+    /// this is `Eigensystem::eigensystem()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The relative tolerance for the off-diagonal elements.
+    ///
+    /// # Returns
+    ///
+    /// The same as `Eigensystem::eigensystem()`.
+    fn eigensystem_diagonal_fast(self, tol: Self::N)
+        -> NalgebraLapackResult<(DVector<Complex<Self::N>>, DMatrix<Complex<Self::N>>)>;
+}
+
+/// Return `true` if all the off-diagonal elements of `a` are within `tol` times its largest
+/// element in magnitude.
+fn is_diagonal<N: BaseFloat>(a: &DMatrix<N>, tol: N) -> bool {
+    let max = a.as_vector().iter().fold(N::zero(), |acc, x| acc.max(x.abs()));
+    let bound = tol * max;
+    (0..a.ncols()).all(|j| (0..a.nrows()).all(|i| i == j || a[(i, j)].abs() <= bound))
+}
+
+impl<N> DiagonalFastPath for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SVD<M = N, V = N> + Eigensystem<N = N>
+{
+    type N = N;
+
+    fn svd_diagonal_fast(self, tol: N)
+        -> NalgebraLapackResult<(DMatrix<N>, DVector<N>, DMatrix<N>)> {
+        if !is_diagonal(&self, tol) {
+            return self.svd();
+        }
+        let (m, n) = (self.nrows(), self.ncols());
+        let k = if m <= n { m } else { n };
+        let mut order: Vec<usize> = (0..k).collect();
+        order.sort_by(|&i, &j| {
+            let (a, b) = (self[(i, i)].abs(), self[(j, j)].abs());
+            b.partial_cmp(&a).unwrap_or(::std::cmp::Ordering::Equal)
+        });
+        // The columns of the singular values come first, followed by the remaining unit
+        // vectors in their natural order.
+        let position = |j: usize| if j < k { order[j] } else { j };
+        let u = DMatrix::from_fn(m, m, |i, j| {
+            if i != position(j) {
+                N::zero()
+            } else if j < k && self[(i, i)] < N::zero() {
+                -N::one()
+            } else {
+                N::one()
+            }
+        });
+        let s = DVector { at: order.iter().map(|&i| self[(i, i)].abs()).collect() };
+        let vt = DMatrix::from_fn(n, n, |i, j| if j == position(i) { N::one() } else { N::zero() });
+        Ok((u, s, vt))
+    }
+
+    fn eigensystem_diagonal_fast(self, tol: N)
+        -> NalgebraLapackResult<(DVector<Complex<N>>, DMatrix<Complex<N>>)> {
+        let n = self.nrows();
+        if self.ncols() != n {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        if !is_diagonal(&self, tol) {
+            return self.eigensystem();
+        }
+        let zero = Complex::new(N::zero(), N::zero());
+        let one = Complex::new(N::one(), N::zero());
+        let values = (0..n).map(|i| Complex::new(self[(i, i)], N::zero())).collect();
+        let vectors = DMatrix::from_fn(n, n, |i, j| if i == j { one } else { zero });
+        Ok((DVector { at: values }, vectors))
+    }
+}
@@ -0,0 +1,1193 @@
+use num::complex::Complex;
+use num::{Float, Num};
+
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector, Eye, Transpose};
+
+use errors::{Error, ErrorKind};
+use {Eigensystem, IterOptions, LU, NalgebraLapackResult, SVD, Solve, SymmetricEigensystem};
+
+/// The eigenvalues and right eigenvectors of a matrix.
+///
+/// This holds the same data as returned by `Eigensystem::eigensystem()` and can be built
+/// from that tuple with `EigenResult::from`.
+#[derive(Clone)]
+pub struct EigenResult<N> {
+    /// The eigenvalues.
+    pub eigen_values: DVector<Complex<N>>,
+    /// The right eigenvectors. They are contained as columns of this matrix.
+    pub eigen_vectors: DMatrix<Complex<N>>,
+}
+
+impl<N> From<(DVector<Complex<N>>, DMatrix<Complex<N>>)> for EigenResult<N> {
+    fn from(result: (DVector<Complex<N>>, DMatrix<Complex<N>>)) -> EigenResult<N> {
+        let (eigen_values, eigen_vectors) = result;
+        EigenResult {
+            eigen_values,
+            eigen_vectors,
+        }
+    }
+}
+
+/// A type for which the eigenvector of a known eigenvalue can be computed.
+pub trait InverseIteration {
+    type N;
+
+    /// compute the right eigenvector for a known (approximate) eigenvalue.
+    ///
+    /// This uses inverse iteration: `(A - lambda I)` is factorized once, and `(A - lambda I)
+    /// x_{k+1} = x_k` is solved repeatedly, normalizing `x_{k+1}`, which converges to the
+    /// eigenvector of the eigenvalue closest to `lambda`. This is much cheaper than a full
+    /// eigendecomposition when only one eigenvector is needed.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `lambda` - The eigenvalue, or an approximation of it.
+    /// * `tol` - The iteration stops when `||A x - lambda x|| <= tol ||A||`, with the Frobenius
+    ///   norm of `A`.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_vector` - The eigenvector, normalized to have Euclidean norm equal to 1 and
+    ///   largest component real. `None` is returned if `A` is not square or if the tolerance is
+    ///   not reached; use `eigenvector_for_with()` to tell these apart.
+    fn eigenvector_for(self, lambda: Complex<Self::N>, tol: Self::N)
+        -> Option<DVector<Complex<Self::N>>>;
+
+    /// compute the right eigenvector for a known (approximate) eigenvalue with explicit
+    /// convergence control.
+    ///
+    /// This is `eigenvector_for()`, which allows 50 steps, with `options.tol` as the relative
+    /// residual tolerance and at most `options.max_iters` steps.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `lambda` - The eigenvalue, or an approximation of it.
+    /// * `options` - The tolerance and the maximum number of iterations.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_vector` - The normalized eigenvector. A `ConvergenceFailure` error is returned
+    ///   if `options.max_iters` is exceeded.
+    fn eigenvector_for_with(self, lambda: Complex<Self::N>, options: IterOptions<Self::N>)
+        -> NalgebraLapackResult<DVector<Complex<Self::N>>>;
+}
+
+/// The maximum number of inverse iteration steps of `InverseIteration::eigenvector_for()`.
+/// With an accurate eigenvalue one or two steps are usually enough.
+const MAX_INVERSE_ITERATIONS: usize = 50;
+
+/// Scale `x` to unit Euclidean norm with its largest component real and positive.
+fn normalize_eigenvector<T: Float>(x: &mut [Complex<T>]) {
+    let mut largest = Complex::new(T::zero(), T::zero());
+    let mut norm2 = T::zero();
+    for c in x.iter() {
+        norm2 = norm2 + c.norm_sqr();
+        if c.norm_sqr() > largest.norm_sqr() {
+            largest = *c;
+        }
+    }
+    if norm2 == T::zero() {
+        return;
+    }
+    // Dividing by `largest / |largest|` makes the largest component real.
+    let scale = largest.unscale(largest.norm()).scale(norm2.sqrt());
+    for c in x.iter_mut() {
+        *c = *c / scale;
+    }
+}
+
+macro_rules! inverse_iteration_impl(
+    ($t: ty, $getrf: path, $getrs: path) => (
+        impl InverseIteration for DMatrix<$t> {
+            type N = $t;
+
+            fn eigenvector_for(self, lambda: Complex<$t>, tol: $t)
+                -> Option<DVector<Complex<$t>>> {
+                let options = IterOptions { tol, max_iters: MAX_INVERSE_ITERATIONS };
+                self.eigenvector_for_with(lambda, options).ok()
+            }
+
+            fn eigenvector_for_with(self, lambda: Complex<$t>, options: IterOptions<$t>)
+                -> NalgebraLapackResult<DVector<Complex<$t>>> {
+                let tol = options.tol;
+                let trans = b'N';
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok(DVector { at: vec![] });
+                }
+                let lda = n as i32;
+                let norm_a = Float::sqrt(self.as_vector().iter().fold(0.0, |acc, x| acc + x * x));
+                let a: DMatrix<Complex<$t>> =
+                    DMatrix::from_fn(n, n, |i, j| Complex::new(self[(i, j)], 0.0));
+
+                let mut shifted = DMatrix::from_fn(n, n, |i, j| {
+                    if i == j { a[(i, j)] - lambda } else { a[(i, j)] }
+                });
+                let mut ipiv: Vec<i32> = vec![0; n];
+                let mut info = 0;
+
+                $getrf(n as i32, n as i32, shifted.as_mut_vector(), lda, &mut ipiv, &mut info);
+                if info < 0 {
+                    return Err(Error::from(ErrorKind::LapackIllegalArgument(-info)));
+                }
+                // When `lambda` is an exact eigenvalue the shifted matrix is singular. A tiny
+                // pivot in place of the zero one still gives the eigenvector direction.
+                let tiny = <$t as Float>::epsilon() * if norm_a > 0.0 { norm_a } else { 1.0 };
+                for i in 0..n {
+                    if shifted[(i, i)].norm() == 0.0 {
+                        shifted[(i, i)] = Complex::new(tiny, 0.0);
+                    }
+                }
+
+                // A start with components of varying size, to avoid being orthogonal to the
+                // eigenvector.
+                let mut x: Vec<Complex<$t>> =
+                    (0..n).map(|i| Complex::new(1.0 / (i + 1) as $t + 0.5, 0.0)).collect();
+                normalize_eigenvector(&mut x);
+                for _ in 0..options.max_iters {
+                    $getrs(trans, n as i32, 1, shifted.as_vector(), lda, &ipiv, &mut x,
+                        n as i32, &mut info);
+                    check_info!(info);
+                    normalize_eigenvector(&mut x);
+
+                    let mut residual2 = 0.0;
+                    for i in 0..n {
+                        let mut r = -lambda * x[i];
+                        for j in 0..n {
+                            r += a[(i, j)] * x[j];
+                        }
+                        residual2 += r.norm_sqr();
+                    }
+                    if Float::sqrt(residual2) <= tol * norm_a {
+                        return Ok(DVector { at: x });
+                    }
+                }
+                Err(Error::from(ErrorKind::ConvergenceFailure))
+            }
+        }
+    );
+);
+
+/// A type for which the eigenvalues closest to a shift can be computed.
+pub trait ShiftInvert {
+    type N;
+
+    /// compute the `k` eigenvalues closest to the shift `sigma` by shift-and-invert.
+    ///
+    /// The eigenvalues `mu` of `(A - sigma I)^-1` are `1 / (lambda - sigma)` for the
+    /// eigenvalues `lambda` of `A`, so those of `A` closest to `sigma` become the ones of
+    /// largest magnitude, and are well separated from the rest. `(A - sigma I)^-1` is computed
+    /// from its LU factorization, all its eigenvalues are computed, and the `k` of largest
+    /// magnitude are mapped back with `lambda = sigma + 1 / mu`.
+    ///
+    /// If `sigma` is exactly an eigenvalue of `A`, `A - sigma I` is singular and an error is
+    /// returned; perturbing `sigma` slightly then works. A shift very close to an eigenvalue is
+    /// fine, as the error in `(A - sigma I)^-1` is mostly along the wanted eigenvector.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `sigma` - The shift.
+    /// * `k` - The number of eigenvalues to compute. At most `n` are returned.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The `k` eigenvalues of `A` closest to `sigma`, the closest first. A
+    ///   `LapackFailure` error is returned if `A - sigma I` is exactly singular.
+    fn eigenvalues_near(self, sigma: Self::N, k: usize)
+        -> NalgebraLapackResult<DVector<Complex<Self::N>>>;
+}
+
+macro_rules! shift_invert_impl(
+    ($t: ty) => (
+        impl ShiftInvert for DMatrix<$t> {
+            type N = $t;
+
+            fn eigenvalues_near(self, sigma: $t, k: usize)
+                -> NalgebraLapackResult<DVector<Complex<$t>>> {
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let mut shifted = self;
+                for i in 0..n {
+                    shifted[(i, i)] -= sigma;
+                }
+                let identity = DMatrix::from_fn(n, n, |i, j| if i == j { 1.0 } else { 0.0 });
+                let inverse = shifted.lu()?.solve(identity)?;
+                let (mut mu, _) = inverse.eigensystem()?;
+
+                mu.at.sort_by(|a, b| {
+                    b.norm_sqr().partial_cmp(&a.norm_sqr()).unwrap_or(::std::cmp::Ordering::Equal)
+                });
+                let shift = Complex::new(sigma, 0.0);
+                let one = Complex::new(1.0, 0.0);
+                let k = if k <= n { k } else { n };
+                Ok(DVector { at: mu.at[..k].iter().map(|m| shift + one / *m).collect() })
+            }
+        }
+    );
+);
+
+/// An orthogonal matrix whose eigenvalues, on the unit circle, can be computed.
+pub trait OrthogonalEigenvalues {
+    type N;
+
+    /// compute the eigenvalues of an orthogonal matrix.
+    ///
+    /// The eigenvalues of an orthogonal matrix lie on the unit circle, but those computed by
+    /// the general eigensolver are only within rounding errors of it. After checking that
+    /// `||Q^T Q - I||_F <= sqrt(eps)`, the eigenvalues are computed with
+    /// `Eigensystem::eigensystem()` and projected onto the unit circle, so each has magnitude
+    /// one to within an ulp, e.g. for the phases of a rotation.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues, of unit magnitude. A `MatrixNotOrthogonal` error is
+    ///   returned if the check fails.
+    fn orthogonal_eigenvalues(self) -> NalgebraLapackResult<DVector<Complex<Self::N>>>;
+}
+
+macro_rules! orthogonal_eigenvalues_impl(
+    ($t: ty) => (
+        impl OrthogonalEigenvalues for DMatrix<$t> {
+            type N = $t;
+
+            fn orthogonal_eigenvalues(self) -> NalgebraLapackResult<DVector<Complex<$t>>> {
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let qtq = &self.transpose() * &self;
+                let deviation = (0..n).fold(0.0, |acc, j| {
+                    (0..n).fold(acc, |acc, i| {
+                        let d = qtq[(i, j)] - if i == j { 1.0 } else { 0.0 };
+                        acc + d * d
+                    })
+                });
+                // A NaN element makes the deviation NaN, which is rejected as well.
+                let deviation = Float::sqrt(deviation);
+                if deviation.is_nan() || deviation > Float::sqrt(<$t as Float>::epsilon()) {
+                    return Err(Error::from(ErrorKind::MatrixNotOrthogonal));
+                }
+                let (values, _) = self.eigensystem()?;
+                Ok(DVector { at: values.at.iter().map(|x| *x / x.norm()).collect() })
+            }
+        }
+    );
+);
+
+/// A type whose eigenvectors can be computed as the rows of a matrix.
+pub trait EigensystemRowVectors {
+    type N;
+
+    /// compute eigenvalues and right eigenvectors, with the eigenvectors as rows.
+    ///
+    /// LAPACK, and `Eigensystem::eigensystem()`, return the eigenvectors as the columns of
+    /// `V` with `A V = V diag(lambda)`. This returns the plain transpose `V^T` instead, so row
+    /// `i` is the eigenvector of `eigen_values[i]`, for libraries that expect that layout. The
+    /// rows are right eigenvectors, not left ones: `V^T` is not conjugated, and `V^T A` is in
+    /// general not `diag(lambda) V^T`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues.
+    /// * `right_eigen_vectors` - The right eigenvectors. They are contained as rows of this
+    ///   matrix, in the order of the eigenvalues.
+    fn eigensystem_row_vectors(self)
+        -> NalgebraLapackResult<(DVector<Complex<Self::N>>, DMatrix<Complex<Self::N>>)>;
+}
+
+impl<N> EigensystemRowVectors for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: Eigensystem<N = N>
+{
+    type N = N;
+
+    fn eigensystem_row_vectors(self)
+        -> NalgebraLapackResult<(DVector<Complex<N>>, DMatrix<Complex<N>>)> {
+        let (values, vectors) = self.eigensystem()?;
+        Ok((values, vectors.transpose()))
+    }
+}
+
+/// A type whose eigensystem can be computed in a canonical order and normalization.
+pub trait DeterministicEigensystem {
+    type N;
+
+    /// compute eigenvalues and right eigenvectors in a canonical form, for results that can
+    /// be compared across LAPACK implementations such as OpenBLAS, MKL and Accelerate.
+    ///
+    /// The eigenvalues are sorted by real part, then by imaginary part, and the eigenvectors
+    /// are permuted with them. Each eigenvector is then multiplied by the unit complex number
+    /// that makes its largest-magnitude element (the first one in case of ties) real and
+    /// positive, which for a real eigenvector is the sign convention of
+    /// `SvdResult::apply_sign_convention()`. The results still differ by rounding errors between
+    /// implementations, and eigenvalues that are equal up to rounding, or eigenvectors of
+    /// repeated eigenvalues, may still come out differently.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues, in ascending order of real, then imaginary part.
+    /// * `eigen_vectors` - The unit right eigenvectors. They are contained as columns of this
+    ///   matrix, in the order of the eigenvalues.
+    fn eigensystem_deterministic(self)
+        -> NalgebraLapackResult<(DVector<Complex<Self::N>>, DMatrix<Complex<Self::N>>)>;
+}
+
+impl<N> DeterministicEigensystem for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: Eigensystem<N = N>
+{
+    type N = N;
+
+    fn eigensystem_deterministic(self)
+        -> NalgebraLapackResult<(DVector<Complex<N>>, DMatrix<Complex<N>>)> {
+        let (values, vectors) = self.eigensystem()?;
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|&i, &j| {
+            let (a, b) = (values.at[i], values.at[j]);
+            (a.re, a.im).partial_cmp(&(b.re, b.im)).unwrap_or(::std::cmp::Ordering::Equal)
+        });
+
+        let mut sorted = DMatrix::from_fn(vectors.nrows(), order.len(),
+                                          |i, j| vectors[(i, order[j])]);
+        for j in 0..sorted.ncols() {
+            let mut largest = 0;
+            for i in 0..sorted.nrows() {
+                if sorted[(i, j)].norm() > sorted[(largest, j)].norm() {
+                    largest = i;
+                }
+            }
+            let norm = sorted[(largest, j)].norm();
+            if norm > N::zero() {
+                let phase = sorted[(largest, j)].conj() / norm;
+                for i in 0..sorted.nrows() {
+                    sorted[(i, j)] *= phase;
+                }
+                // Without the rounding error of the product.
+                sorted[(largest, j)] = Complex::new(norm, N::zero());
+            }
+        }
+        Ok((DVector { at: order.iter().map(|&i| values.at[i]).collect() }, sorted))
+    }
+}
+
+/// A square matrix whose departure from normality can be measured.
+pub trait DepartureFromNormality {
+    type N;
+
+    /// compute the norm of the commutator `||A A^T - A^T A||_F`.
+    ///
+    /// This is zero exactly when `A` is normal, in which case its eigenvectors are orthogonal
+    /// and its eigenvalues perfectly conditioned. A value that is large relative to `||A||_F^2`
+    /// warns that `A` is highly non-normal: its eigenvectors may be nearly parallel and its
+    /// eigenvalues sensitive to perturbations, so its eigendecomposition should be used with
+    /// care. This is cheaper than Henrici's departure from normality, which needs the Schur
+    /// form, and only involves matrix products.
+    ///
+    /// # Returns
+    ///
+    /// * `departure` - The Frobenius norm of the commutator, or a `MatrixNotSquare` error.
+    fn departure_from_normality(&self) -> NalgebraLapackResult<Self::N>;
+}
+
+impl<N: BaseFloat> DepartureFromNormality for DMatrix<N> {
+    type N = N;
+
+    fn departure_from_normality(&self) -> NalgebraLapackResult<N> {
+        let n = self.nrows();
+        if self.ncols() != n {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        let at = self.transpose();
+        let commutator = self * &at - &at * self;
+        Ok(commutator.as_vector().iter().fold(N::zero(), |acc, x| acc + *x * *x).sqrt())
+    }
+}
+
+/// A type for which points on the boundary of the numerical range can be computed.
+pub trait NumericalRange {
+    type N;
+
+    /// compute points on the boundary of the numerical range (field of values).
+    ///
+    /// The numerical range `{x^H A x : ||x|| = 1}` is a convex set containing the eigenvalues,
+    /// and for a non-normal matrix it can be much larger than their convex hull, which makes
+    /// it useful to bound the transient behaviour of `exp(t A)`. For each angle `theta`, the
+    /// eigenvector `x` of the largest eigenvalue of the Hermitian part of `e^(-i theta) A`
+    /// gives the boundary point `x^H A x` furthest in the direction `e^(i theta)`. The
+    /// Hermitian eigenproblem of order `n` is solved as the real symmetric one of order `2 n`
+    /// for the real and imaginary parts.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `n_points` - The number of angles, equally spaced from `0` to `2 pi`.
+    ///
+    /// # Returns
+    ///
+    /// * `boundary` - The boundary point for each angle, in counterclockwise order.
+    fn numerical_range_boundary(self, n_points: usize)
+        -> NalgebraLapackResult<Vec<Complex<Self::N>>>;
+}
+
+impl<N> NumericalRange for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    type N = N;
+
+    fn numerical_range_boundary(self, n_points: usize)
+        -> NalgebraLapackResult<Vec<Complex<N>>> {
+        let a = self;
+        let n = a.nrows();
+        if a.ncols() != n {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        if n == 0 {
+            return Ok(vec![]);
+        }
+        let half = <N as Cast<f64>>::from(0.5);
+        let mut boundary = Vec::with_capacity(n_points);
+        for k in 0..n_points {
+            let theta = <N as Cast<f64>>::from(2.0 * ::std::f64::consts::PI * k as f64 /
+                                               n_points as f64);
+            let (sin, cos) = theta.sin_cos();
+            // The Hermitian part of `e^(-i theta) A` is `cos S - i sin K`, with `S` and `K` the
+            // symmetric and skew-symmetric parts of `A`; `[[Re, -Im], [Im, Re]]` is the real
+            // symmetric matrix with the same eigenvalues, each twice.
+            let re = |i: usize, j: usize| cos * half * (a[(i, j)] + a[(j, i)]);
+            let im = |i: usize, j: usize| -sin * half * (a[(i, j)] - a[(j, i)]);
+            let embedded = DMatrix::from_fn(2 * n, 2 * n, |i, j| {
+                match (i < n, j < n) {
+                    (true, true) => re(i, j),
+                    (true, false) => -im(i, j - n),
+                    (false, true) => im(i - n, j),
+                    (false, false) => re(i - n, j - n),
+                }
+            });
+            let (_, vectors) = embedded.symmetric_eigensystem()?;
+            let xr: Vec<N> = (0..n).map(|i| vectors[(i, 2 * n - 1)]).collect();
+            let xi: Vec<N> = (0..n).map(|i| vectors[(n + i, 2 * n - 1)]).collect();
+
+            // `x^H A x` for `x = xr + i xi`, of unit norm.
+            let form = |u: &[N], v: &[N]| {
+                let mut sum = N::zero();
+                for i in 0..n {
+                    for j in 0..n {
+                        sum += u[i] * a[(i, j)] * v[j];
+                    }
+                }
+                sum
+            };
+            boundary.push(Complex::new(form(&xr, &xr) + form(&xi, &xi),
+                                       form(&xr, &xi) - form(&xi, &xr)));
+        }
+        Ok(boundary)
+    }
+}
+
+/// A square matrix whose numerical abscissa can be computed.
+pub trait NumericalAbscissa {
+    type N;
+
+    /// compute the numerical abscissa, the largest eigenvalue of the symmetric part
+    /// `(A + A^T) / 2`.
+    ///
+    /// This is the rightmost real part of the numerical range, and the initial growth rate
+    /// `d/dt ||exp(t A)|| = omega(A)` at `t = 0` in the 2-norm, so that `||exp(t A)|| <=
+    /// exp(t omega(A))` for all `t >= 0`. It is at least the spectral abscissa, the largest real
+    /// part of an eigenvalue, with equality for a normal matrix; a numerical abscissa much
+    /// larger than the spectral abscissa signals transient growth. It is computed with the
+    /// symmetric eigensolver, without eigenvectors.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `omega` - The numerical abscissa, or `None` if the matrix is not square, is empty or
+    ///   LAPACK fails.
+    fn numerical_abscissa(self) -> Option<Self::N>;
+}
+
+impl<N> NumericalAbscissa for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    type N = N;
+
+    fn numerical_abscissa(self) -> Option<N> {
+        let n = self.nrows();
+        if self.ncols() != n || n == 0 {
+            return None;
+        }
+        let half = <N as Cast<f64>>::from(0.5);
+        let symmetric = DMatrix::from_fn(n, n, |i, j| half * (self[(i, j)] + self[(j, i)]));
+        let values = symmetric.symmetric_eigenvalues()?;
+        values.at.last().cloned()
+    }
+}
+
+/// A set of square blocks for which the eigensystem of their block-diagonal matrix can be
+/// computed.
+pub trait BlockDiagonalEigensystem {
+    type N;
+
+    /// compute eigenvalues and right eigenvectors of the block-diagonal matrix with these
+    /// blocks on its diagonal.
+    ///
+    /// The eigensystem of each block is computed separately, which is much cheaper than
+    /// that of the assembled matrix. The eigenvectors of a block are padded with zeros
+    /// outside of the rows of that block.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues of all blocks, block by block in order.
+    /// * `right_eigen_vectors` - The right eigenvectors of the block-diagonal matrix. They are
+    ///   contained as columns of this matrix, in the same order as the eigenvalues.
+    fn block_diagonal_eigensystem(&self)
+        -> NalgebraLapackResult<(DVector<Complex<Self::N>>, DMatrix<Complex<Self::N>>)>;
+}
+
+impl<N> BlockDiagonalEigensystem for [DMatrix<N>]
+    where N: Num + Copy,
+          DMatrix<N>: Eigensystem<N = N> + Clone
+{
+    type N = N;
+
+    fn block_diagonal_eigensystem(&self)
+        -> NalgebraLapackResult<(DVector<Complex<N>>, DMatrix<Complex<N>>)> {
+        let n = self.iter().map(|b| b.nrows()).sum();
+        let mut eigen_values = Vec::with_capacity(n);
+        let mut eigen_vectors = DMatrix::from_element(n, n, Complex::new(N::zero(), N::zero()));
+
+        let mut offset = 0;
+        for block in self {
+            let (values, vectors) = block.clone().eigensystem()?;
+            for j in 0..values.len() {
+                for i in 0..vectors.nrows() {
+                    eigen_vectors[(offset + i, offset + j)] = vectors[(i, j)];
+                }
+            }
+            offset += values.len();
+            eigen_values.extend(values.at);
+        }
+        Ok((DVector { at: eigen_values }, eigen_vectors))
+    }
+}
+
+/// compute the eigensystems of many independent matrices.
+///
+/// With the `rayon` feature enabled, the matrices are distributed across threads. Each LAPACK
+/// call uses its own workspace, so the solves are independent. Without the feature they are
+/// solved one after the other; the bounds are the same either way.
+///
+/// # Arguments
+///
+/// * `mats` - The square matrices.
+///
+/// # Returns
+///
+/// * `results` - The eigensystem of each matrix, or the error in computing it, in the same
+///   order as `mats`.
+pub fn eigensystem_batch<N>(mats: Vec<DMatrix<N>>) -> Vec<NalgebraLapackResult<EigenResult<N>>>
+    where N: Send,
+          DMatrix<N>: Eigensystem<N = N> + Send
+{
+    solve_batch(mats)
+}
+
+/// Solve the eigensystems of `eigensystem_batch()` in parallel.
+#[cfg(feature = "rayon")]
+fn solve_batch<N>(mats: Vec<DMatrix<N>>) -> Vec<NalgebraLapackResult<EigenResult<N>>>
+    where N: Send,
+          DMatrix<N>: Eigensystem<N = N> + Send
+{
+    use rayon::prelude::*;
+
+    mats.into_par_iter().map(|m| m.eigensystem().map(EigenResult::from)).collect()
+}
+
+/// Solve the eigensystems of `eigensystem_batch()` one after the other.
+#[cfg(not(feature = "rayon"))]
+fn solve_batch<N>(mats: Vec<DMatrix<N>>) -> Vec<NalgebraLapackResult<EigenResult<N>>>
+    where DMatrix<N>: Eigensystem<N = N>
+{
+    mats.into_iter().map(|m| m.eigensystem().map(EigenResult::from)).collect()
+}
+
+/// A Jordan chain of a matrix `A`: vectors with `(A - lambda I) v_1 = 0` and
+/// `(A - lambda I) v_{k+1} = v_k`.
+#[derive(Clone)]
+pub struct JordanChain<N> {
+    /// The eigenvalue.
+    pub eigen_value: Complex<N>,
+    /// The chain, starting with the eigenvector `v_1`, followed by the generalized
+    /// eigenvectors.
+    pub vectors: Vec<DVector<Complex<N>>>,
+}
+
+/// A type for which generalized eigenvectors can be computed.
+pub trait GeneralizedEigenvectors {
+    type N;
+
+    /// compute Jordan chains of generalized eigenvectors (experimental).
+    ///
+    /// After an eigensolve, eigenvalues within `tol ||A||` of each other are grouped, their
+    /// number being the algebraic multiplicity `m`. The null space of `A - lambda I`, from its
+    /// SVD, gives the eigenvectors. If there are fewer than `m` of them the eigenvalue is
+    /// defective, and each eigenvector `v_1` is extended into a chain by solving
+    /// `(A - lambda I) v_{k+1} = v_k` in the minimum-norm least-squares sense, for as long as
+    /// the residual stays below `tol ||A||` and the chains have fewer than `m` vectors in
+    /// total. The Frobenius norm of `A` is used.
+    ///
+    /// This is numerically fragile: a defective eigenvalue of multiplicity `m` is only
+    /// computed to about `eps^(1/m)`, so in floating point it typically appears as a cluster
+    /// of close simple eigenvalues, and the result depends strongly on `tol`. The chains are
+    /// only reliable for an eigenvalue with a single Jordan block; with several blocks, the
+    /// eigenvectors that start the longer chains are not identified, so shorter chains may be
+    /// returned.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The relative tolerance for grouping eigenvalues, the null space and the
+    ///   residuals.
+    ///
+    /// # Returns
+    ///
+    /// * `chains` - The Jordan chains, one for each eigenvector.
+    fn generalized_eigenvectors(self, tol: Self::N)
+        -> NalgebraLapackResult<Vec<JordanChain<Self::N>>>;
+}
+
+impl<N> GeneralizedEigenvectors for DMatrix<N>
+    where N: Float,
+          DMatrix<N>: Eigensystem<N = N> + Clone,
+          DMatrix<Complex<N>>: SVD<M = Complex<N>, V = N>
+{
+    type N = N;
+
+    fn generalized_eigenvectors(self, tol: N)
+        -> NalgebraLapackResult<Vec<JordanChain<N>>> {
+        let n = self.nrows();
+        if self.ncols() != n {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        let zero = Complex::new(N::zero(), N::zero());
+        let norm_a = self.as_vector().iter().fold(N::zero(), |acc, x| acc + *x * *x).sqrt();
+        let threshold = tol * if norm_a > N::zero() { norm_a } else { N::one() };
+        let a = DMatrix::from_fn(n, n, |i, j| Complex::new(self[(i, j)], N::zero()));
+        let (values, _) = self.eigensystem()?;
+
+        let mut assigned = vec![false; n];
+        let mut chains = vec![];
+        for i in 0..n {
+            if assigned[i] {
+                continue;
+            }
+            let cluster: Vec<usize> = (i..n)
+                .filter(|&j| !assigned[j] && (values[j] - values[i]).norm() <= threshold)
+                .collect();
+            for &j in cluster.iter() {
+                assigned[j] = true;
+            }
+            let multiplicity = cluster.len();
+            let count = N::from(multiplicity).unwrap();
+            let lambda = cluster.iter().fold(zero, |acc, &j| acc + values[j]).unscale(count);
+
+            let shifted = DMatrix::from_fn(n, n, |r, c| {
+                if r == c { a[(r, c)] - lambda } else { a[(r, c)] }
+            });
+            let (u, s, vt) = shifted.clone().svd()?;
+            // The right singular vectors of the smallest singular values span the null space.
+            let nonzero = s.at.iter().filter(|x| **x > threshold).count();
+            let geometric = ::std::cmp::max(1, ::std::cmp::min(multiplicity, n - nonzero));
+            let rank = ::std::cmp::min(nonzero, n - geometric);
+
+            let mut length = geometric;
+            for k in 0..geometric {
+                let row = n - 1 - k;
+                let v1 = DVector { at: (0..n).map(|c| vt[(row, c)].conj()).collect() };
+                let mut chain = vec![v1];
+                while length < multiplicity {
+                    // The minimum-norm solution of `(A - lambda I) x = v_k`, using the SVD.
+                    let v: &DVector<Complex<N>> = &chain[chain.len() - 1];
+                    let mut x = vec![zero; n];
+                    for p in 0..rank {
+                        let mut coeff = zero;
+                        for r in 0..n {
+                            coeff = coeff + u[(r, p)].conj() * v[r];
+                        }
+                        coeff = coeff.unscale(s[p]);
+                        for c in 0..n {
+                            x[c] = x[c] + coeff * vt[(p, c)].conj();
+                        }
+                    }
+                    let mut residual2 = N::zero();
+                    for r in 0..n {
+                        let mut sum = -v[r];
+                        for c in 0..n {
+                            sum = sum + shifted[(r, c)] * x[c];
+                        }
+                        residual2 = residual2 + sum.norm_sqr();
+                    }
+                    if residual2.sqrt() > threshold {
+                        break;
+                    }
+                    chain.push(DVector { at: x });
+                    length += 1;
+                }
+                chains.push(JordanChain { eigen_value: lambda, vectors: chain });
+            }
+        }
+        Ok(chains)
+    }
+}
+
+macro_rules! eigen_result_impl(
+    ($t: ty, $gees: path, $trsna: path) => (
+        impl EigenResult<$t> {
+            /// compute the Bauer-Fike condition number of the eigenvector basis.
+            ///
+            /// This is `||V|| ||V^-1||` in the 2-norm, where `V` is the matrix of right
+            /// eigenvectors. By the Bauer-Fike theorem, every eigenvalue of the perturbed
+            /// matrix `A + E` lies within `kappa(V) ||E||` of an eigenvalue of `A`. A normal
+            /// matrix has a condition number of 1; it grows without bound as the matrix
+            /// approaches a defective one.
+            ///
+            /// The norms are taken from the singular values of `V`, using
+            /// `||V^-1|| = 1 / sigma_min(V)`, so `V` is never explicitly inverted.
+            ///
+            /// # Returns
+            ///
+            /// * `condition_number` - The condition number. This is infinite if the
+            ///   eigenvectors are exactly linearly dependent.
+            pub fn bauer_fike_condition(&self) -> NalgebraLapackResult<$t> {
+                let n = self.eigen_vectors.ncols();
+                if n == 0 {
+                    return Ok(1.0);
+                }
+
+                let (_, s, _) = self.eigen_vectors.clone().svd()?;
+                let sigma_min = s[n - 1];
+                if sigma_min == 0.0 {
+                    return Ok(<$t as Float>::infinity());
+                }
+                Ok(s[0] / sigma_min)
+            }
+
+            /// return `true` if the eigenvectors are numerically linearly independent.
+            ///
+            /// A matrix is diagonalizable exactly when its eigenvectors span the whole space.
+            /// For a defective matrix, such as a Jordan block, LAPACK still returns a full set
+            /// of eigenvectors, but some of them are (nearly) parallel. This checks that the
+            /// smallest singular value of the matrix of unit-norm eigenvectors `V` is above
+            /// `tol`.
+            ///
+            /// # Arguments
+            ///
+            /// * `tol` - The smallest singular value of `V` at or below which the eigenvectors
+            ///   are considered linearly dependent.
+            pub fn is_diagonalizable(&self, tol: $t) -> NalgebraLapackResult<bool> {
+                let n = self.eigen_vectors.ncols();
+                if n == 0 {
+                    return Ok(true);
+                }
+
+                let (_, s, _) = self.eigen_vectors.clone().svd()?;
+                Ok(s[n - 1] > tol)
+            }
+
+            /// compute the residual of each eigenpair.
+            ///
+            /// For the eigenvalue `lambda_i` and eigenvector `x_i` this is the Euclidean norm
+            /// `||A x_i - lambda_i x_i||`, which is close to machine epsilon times `||A||` for
+            /// an accurately computed eigenpair.
+            ///
+            /// # Arguments
+            ///
+            /// * `a` - The matrix from which this eigensystem was computed.
+            ///
+            /// # Returns
+            ///
+            /// * `residuals` - The residuals, in the same order as the eigenvalues.
+            pub fn residuals(&self, a: &DMatrix<$t>) -> DVector<$t> {
+                let n = self.eigen_vectors.nrows();
+                let residuals = (0..self.eigen_vectors.ncols())
+                    .map(|j| {
+                        let lambda = self.eigen_values[j];
+                        let mut sum = 0.0;
+                        for i in 0..n {
+                            let mut r = -lambda * self.eigen_vectors[(i, j)];
+                            for k in 0..n {
+                                r += self.eigen_vectors[(k, j)] * a[(i, k)];
+                            }
+                            sum += r.norm_sqr();
+                        }
+                        Float::sqrt(sum)
+                    })
+                    .collect();
+                DVector { at: residuals }
+            }
+
+            /// compute the normwise backward error of the whole eigensystem.
+            ///
+            /// This is `max_i ||A x_i - lambda_i x_i|| / (||A|| ||x_i||)`, with the Frobenius
+            /// norm of `A`: each eigenpair is exact for a matrix within this relative distance
+            /// of `A`. A backward stable eigensolver gives a small multiple of machine
+            /// epsilon, so this is a single measure of the quality of the result.
+            ///
+            /// # Arguments
+            ///
+            /// * `a` - The matrix from which this eigensystem was computed.
+            ///
+            /// # Returns
+            ///
+            /// * `error` - The largest relative backward error, or zero if `A` or the
+            ///   eigensystem is empty. An eigenvector of zero norm gives infinity.
+            pub fn backward_error(&self, a: &DMatrix<$t>) -> $t {
+                let norm_a = Float::sqrt(a.as_vector().iter().fold(0.0, |acc, x| acc + x * x));
+                let n = self.eigen_vectors.nrows();
+                self.residuals(a).at
+                    .iter()
+                    .enumerate()
+                    .map(|(j, r)| {
+                        let norm_x = Float::sqrt((0..n).fold(0.0, |acc, i| {
+                            acc + self.eigen_vectors[(i, j)].norm_sqr()
+                        }));
+                        if *r == 0.0 { 0.0 } else { r / (norm_a * norm_x) }
+                    })
+                    .fold(0.0, |acc: $t, e| acc.max(e))
+            }
+
+            /// return `true` if any two eigenvalues are within `tol` of each other.
+            ///
+            /// The eigenvectors of clustered eigenvalues are ill-conditioned: only the
+            /// invariant subspace spanned by all of them together is well determined, so the
+            /// individual eigenvectors should not be trusted.
+            ///
+            /// # Arguments
+            ///
+            /// * `tol` - The distance in the complex plane at or below which two eigenvalues
+            ///   are considered clustered.
+            pub fn has_clustered_eigenvalues(&self, tol: $t) -> bool {
+                let values = &self.eigen_values.at;
+                for i in 0..values.len() {
+                    for j in (i + 1)..values.len() {
+                        if (values[i] - values[j]).norm() <= tol {
+                            return true;
+                        }
+                    }
+                }
+                false
+            }
+
+            /// group the eigenvalues into clusters, each with the eigenvectors spanning it.
+            ///
+            /// Two eigenvalues within `tol` of each other are in the same cluster, and so are
+            /// eigenvalues linked by a chain of such pairs, as in `has_clustered_eigenvalues()`.
+            /// A repeated eigenvalue is computed as a cluster of nearby ones, and while its
+            /// individual eigenvectors are not well determined, the space they span together
+            /// is. The eigenvectors are complex, as for a real matrix the eigenspace of a
+            /// complex eigenvalue has no real basis.
+            ///
+            /// # Arguments
+            ///
+            /// * `tol` - The distance in the complex plane at or below which two eigenvalues
+            ///   are in the same cluster.
+            ///
+            /// # Returns
+            ///
+            /// * `groups` - For each cluster, in order of its first eigenvalue, the mean of its
+            ///   eigenvalues and a matrix whose columns are their eigenvectors. The number of
+            ///   columns is the multiplicity of the eigenvalue; they span its (numerical)
+            ///   eigenspace, or fail to if the matrix is (nearly) defective.
+            pub fn grouped(&self, tol: $t) -> Vec<(Complex<$t>, DMatrix<Complex<$t>>)> {
+                let values = &self.eigen_values.at;
+                let v = &self.eigen_vectors;
+                let n = values.len();
+                let mut label: Vec<usize> = (0..n).collect();
+                for i in 0..n {
+                    for j in 0..i {
+                        if (values[i] - values[j]).norm() <= tol && label[j] != label[i] {
+                            // Each cluster is labelled by its first member.
+                            let (from, to) = if label[i] > label[j] {
+                                (label[i], label[j])
+                            } else {
+                                (label[j], label[i])
+                            };
+                            for l in label.iter_mut() {
+                                if *l == from {
+                                    *l = to;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let mut groups = vec![];
+                for i in 0..n {
+                    if label[i] != i {
+                        continue;
+                    }
+                    let members: Vec<usize> = (0..n).filter(|j| label[*j] == i).collect();
+                    let sum = members.iter()
+                        .fold(Complex::new(0.0, 0.0), |acc, j| acc + values[*j]);
+                    let mean = sum.unscale(members.len() as $t);
+                    let vectors = DMatrix::from_fn(v.nrows(), members.len(),
+                                                   |r, c| v[(r, members[c])]);
+                    groups.push((mean, vectors));
+                }
+                groups
+            }
+
+            /// reconstruct the matrix from its eigendecomposition.
+            ///
+            /// This computes `V diag(lambda) V^-1`, with the eigenvector matrix `V`, which is
+            /// the original matrix up to rounding errors. See `map_eigenvalues()` for a
+            /// function of the matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `a` - The reconstructed matrix, or `None` if `V` is numerically singular, i.e.
+            ///   the matrix is (nearly) defective. See `is_diagonalizable()`.
+            pub fn reconstruct(&self) -> Option<DMatrix<Complex<$t>>> {
+                self.map_eigenvalues(|lambda| lambda)
+            }
+
+            /// compute a function of the matrix from its eigendecomposition.
+            ///
+            /// This computes `V diag(f(lambda)) V^-1`, with the eigenvector matrix `V`. It is
+            /// only accurate when `V` is well conditioned; `MatrixFunction::matrix_function()`
+            /// is preferable for non-normal matrices.
+            ///
+            /// # Arguments
+            ///
+            /// * `f` - The scalar function, applied to each eigenvalue.
+            ///
+            /// # Returns
+            ///
+            /// * `f_a` - The matrix function, or `None` if `V` is numerically singular, i.e.
+            ///   the matrix is (nearly) defective. See `is_diagonalizable()`.
+            pub fn map_eigenvalues<F>(&self, f: F) -> Option<DMatrix<Complex<$t>>>
+                where F: Fn(Complex<$t>) -> Complex<$t>
+            {
+                let v = &self.eigen_vectors;
+                let n = v.ncols();
+                if n == 0 {
+                    return Some(DMatrix::new_zeros(0, 0));
+                }
+                let tol = n as $t * <$t as Float>::epsilon();
+                match self.is_diagonalizable(tol) {
+                    Ok(true) => {}
+                    _ => return None,
+                }
+
+                let f_lambda: Vec<Complex<$t>> =
+                    self.eigen_values.at.iter().map(|lambda| f(*lambda)).collect();
+                let v_lambda = DMatrix::from_fn(n, n, |i, j| v[(i, j)] * f_lambda[j]);
+                // `X V = V diag(f(lambda))` is solved as `V^T X^T = (V diag(f(lambda)))^T`.
+                let xt = v.transpose().solve(v_lambda.transpose()).ok()?;
+                Some(xt.transpose())
+            }
+
+            /// compute the participation factors of the states in the modes.
+            ///
+            /// In modal analysis, e.g. of power systems, the participation factor
+            /// `p_ki = v_ki w_ik` measures the share of state `k` in mode `i`, where the
+            /// columns `v_i` of `V` are the right eigenvectors and the rows `w_i` of `W = V^-1`
+            /// are the left eigenvectors with the normalization `w_i v_i = 1`. `W` is computed
+            /// by solving with `V`, so this reflects the accuracy of the eigenvectors and
+            /// requires an (at least numerically) diagonalizable matrix. Each column of the
+            /// result sums to one, and for a real matrix a real mode has real factors.
+            ///
+            /// # Returns
+            ///
+            /// * `p` - The `n x n` participation factors, with `p[(k, i)]` for state `k` and
+            ///   mode `i`. An error is returned if `V` is exactly singular.
+            pub fn participation_factors(&self) -> NalgebraLapackResult<DMatrix<Complex<$t>>> {
+                let v = &self.eigen_vectors;
+                let n = v.ncols();
+                let w = v.clone().solve(DMatrix::new_identity(n))?;
+                Ok(DMatrix::from_fn(n, n, |k, i| v[(k, i)] * w[(i, k)]))
+            }
+
+            /// return the sum and the product of the eigenvalues.
+            ///
+            /// These equal the trace and the determinant of the matrix, up to rounding errors.
+            ///
+            /// # Returns
+            ///
+            /// * `trace` - The sum of the eigenvalues.
+            /// * `determinant` - The product of the eigenvalues.
+            pub fn trace_and_det(&self) -> (Complex<$t>, Complex<$t>) {
+                let values = &self.eigen_values.at;
+                let trace = values.iter().fold(Complex::new(0.0, 0.0), |acc, x| acc + x);
+                let det = values.iter().fold(Complex::new(1.0, 0.0), |acc, x| acc * x);
+                (trace, det)
+            }
+
+            /// check that the eigenvalues are consistent with the trace and determinant of `a`.
+            ///
+            /// This is a cheap sanity check of an eigensolve: the sum and product of the
+            /// eigenvalues must match the trace and the determinant of `a`, the latter being
+            /// computed from an LU factorization. Each difference is compared to `tol` times
+            /// the larger of 1 and the magnitude of the trace or determinant of `a`.
+            ///
+            /// # Arguments
+            ///
+            /// * `a` - The matrix from which this eigensystem was computed.
+            /// * `tol` - The relative tolerance of the comparisons.
+            ///
+            /// # Returns
+            ///
+            /// * `consistent` - `true` if both the trace and the determinant match.
+            pub fn verify_against(&self, a: &DMatrix<$t>, tol: $t) -> NalgebraLapackResult<bool> {
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if self.eigen_values.len() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let trace_a = (0..n).fold(0.0, |acc, i| acc + a[(i, i)]);
+
+                let lu = a.clone().lu()?;
+                let mut det_a = lu.determinant_sign() as $t;
+                for i in 0..n {
+                    det_a *= Float::abs(lu.factors()[(i, i)]);
+                }
+
+                let (trace, det) = self.trace_and_det();
+                let close = |x: Complex<$t>, y: $t| {
+                    (x - Complex::new(y, 0.0)).norm() <= tol * Float::max(1.0, Float::abs(y))
+                };
+                Ok(close(trace, trace_a) && close(det, det_a))
+            }
+
+            /// compute the separation of each eigenvalue from the rest of the spectrum, which
+            /// governs the sensitivity of its eigenvector.
+            ///
+            /// With the Schur form `T` of `A` ordered so that `lambda_i` comes first,
+            /// `T = [lambda_i, t; 0, T22]`, the separation is `sep_i = sigma_min(T22 - lambda_i
+            /// I)`, and a perturbation `E` of `A` rotates the eigenvector by an angle of about
+            /// `||E|| / sep_i` at most. A small separation means a nearly defective eigenpair,
+            /// or one close to another eigenvalue. The Schur form is computed with `?gees` and
+            /// the separations are estimated with `?trsna`; the two eigenvalues of a complex
+            /// conjugate pair share their separation.
+            ///
+            /// # Arguments
+            ///
+            /// * `a` - The matrix from which this eigensystem was computed.
+            ///
+            /// # Returns
+            ///
+            /// * `separations` - The estimated separations, in the same order as the
+            ///   eigenvalues. Each eigenvalue is matched with the nearest eigenvalue of the Schur
+            ///   form not matched yet. A `DimensionMismatch` error is returned if `a` does not
+            ///   have as many rows as there are eigenvalues.
+            pub fn eigenvalue_separations(&self, a: &DMatrix<$t>)
+                -> NalgebraLapackResult<DVector<$t>> {
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if self.eigen_values.len() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                if n == 0 {
+                    return Ok(DVector { at: vec![] });
+                }
+                let jobvs = b'N';
+                let sort = b'N';
+                let mut t = a.clone();
+                let lda = n as i32;
+                let mut sdim = 0;
+                let mut wr: Vec<$t> = vec![0.0; n];
+                let mut wi: Vec<$t> = vec![0.0; n];
+                let mut vs: Vec<$t> = vec![0.0; 1];
+                let mut bwork = vec![0; n];
+                let mut info = 0;
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                $gees(jobvs, sort, None, n as i32, t.as_mut_vector(), lda, &mut sdim, &mut wr,
+                    &mut wi, &mut vs, 1, &mut work, lwork, &mut bwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+                ::diagnostics::record(stringify!($gees), n, n, &[jobvs, sort]);
+                $gees(jobvs, sort, None, n as i32, t.as_mut_vector(), lda, &mut sdim, &mut wr,
+                    &mut wi, &mut vs, 1, &mut work, lwork, &mut bwork, &mut info);
+                check_info!(info);
+
+                let job = b'V';
+                let howmny = b'A';
+                let select = vec![0; n];
+                let vectors: Vec<$t> = vec![0.0; 1];
+                let mut s: Vec<$t> = vec![0.0; n];
+                let mut sep: Vec<$t> = vec![0.0; n];
+                let mut m = 0;
+                let ldwork = n;
+                let mut work: Vec<$t> = vec![0.0; ldwork * (n + 6)];
+                let mut iwork = vec![0; ::std::cmp::max(1, 2 * (n - 1))];
+
+                ::diagnostics::record(stringify!($trsna), n, n, &[job, howmny]);
+                $trsna(job, howmny, &select, n as i32, t.as_vector(), lda, &vectors, 1,
+                    &vectors, 1, &mut s, &mut sep, n as i32, &mut m, &mut work, ldwork as i32,
+                    &mut iwork, &mut info);
+                check_info!(info);
+
+                let mut used = vec![false; n];
+                let separations = self.eigen_values.at.iter()
+                    .map(|lambda| {
+                        let mut nearest: Option<(usize, $t)> = None;
+                        for k in (0..n).filter(|&k| !used[k]) {
+                            let distance = (*lambda - Complex::new(wr[k], wi[k])).norm();
+                            if nearest.map_or(true, |(_, d)| distance < d) {
+                                nearest = Some((k, distance));
+                            }
+                        }
+                        let k = nearest.unwrap().0;
+                        used[k] = true;
+                        sep[k]
+                    })
+                    .collect();
+                Ok(DVector { at: separations })
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+eigen_result_impl!(f32, interface::sgees, interface::strsna);
+eigen_result_impl!(f64, interface::dgees, interface::dtrsna);
+
+inverse_iteration_impl!(f32, interface::cgetrf, interface::cgetrs);
+inverse_iteration_impl!(f64, interface::zgetrf, interface::zgetrs);
+
+shift_invert_impl!(f32);
+shift_invert_impl!(f64);
+
+orthogonal_eigenvalues_impl!(f32);
+orthogonal_eigenvalues_impl!(f64);
@@ -12,5 +12,29 @@ error_chain!{
             description("a square matrix is required")
             display("a square matrix is required")
         }
+        DimensionMismatch {
+            description("matrix dimensions are incompatible")
+            display("matrix dimensions are incompatible")
+        }
+        MatrixNotPositiveDefinite {
+            description("a positive-definite matrix is required")
+            display("a positive-definite matrix is required")
+        }
+        MatrixNotSymmetric {
+            description("a symmetric matrix is required")
+            display("a symmetric matrix is required")
+        }
+        MatrixNotOrthogonal {
+            description("an orthogonal matrix is required")
+            display("an orthogonal matrix is required")
+        }
+        ConvergenceFailure {
+            description("iteration failed to converge")
+            display("iteration failed to converge")
+        }
+        InvalidLayout {
+            description("invalid matrix storage layout")
+            display("the storage does not match the column-major layout given to LAPACK")
+        }
     }
 }
@@ -0,0 +1,181 @@
+use num::Float;
+
+use nalgebra::DMatrix;
+
+use errors::{Error, ErrorKind};
+use {CholeskyFactorization, CholeskyFactorize, LU, LuFactorization, NalgebraLapackResult,
+     QrCompact, QrFactorize};
+
+/// Indicators of the numerical health of a factorization, in one place.
+///
+/// These are returned alongside the factorization by the entry points of
+/// `FactorizeWithDiagnostics`. A small `rcond`, a `rank` below the dimension or
+/// `near_zero_pivots` all mean that solutions computed from the factorization may have lost
+/// most or all of their accuracy.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FactorizationDiagnostics<N> {
+    /// The estimate of the reciprocal condition number in the 1-norm, zero for an exactly
+    /// singular matrix.
+    pub rcond: N,
+    /// The numerical rank: the number of pivots greater than `max(m, n) eps` times the
+    /// largest one.
+    pub rank: usize,
+    /// `true` if some pivot is at or below that threshold.
+    pub near_zero_pivots: bool,
+}
+
+impl<N: Float> FactorizationDiagnostics<N> {
+    /// Build the diagnostics from the estimate `rcond` and the magnitudes of the pivots.
+    fn new(rcond: N, pivots: &[N], dim: usize) -> FactorizationDiagnostics<N> {
+        let largest = pivots.iter().fold(N::zero(), |acc, p| acc.max(*p));
+        let tol = N::from(dim).unwrap() * N::epsilon() * largest;
+        let rank = pivots.iter().filter(|p| **p > tol).count();
+        FactorizationDiagnostics {
+            rcond,
+            rank,
+            near_zero_pivots: rank < pivots.len(),
+        }
+    }
+
+    /// return the estimate `1 / rcond` of the condition number, infinite for an exactly
+    /// singular matrix.
+    pub fn condition_number(&self) -> N {
+        if self.rcond == N::zero() { N::infinity() } else { self.rcond.recip() }
+    }
+}
+
+/// A real matrix whose factorizations can be computed together with their diagnostics.
+pub trait FactorizeWithDiagnostics {
+    type N;
+
+    /// compute the LU factorization with partial pivoting and its diagnostics.
+    ///
+    /// This is `LU::factor_lu_with_condition()`, with the pivots taken from the diagonal of
+    /// `U`. Partial pivoting is not rank revealing, so a nearly singular matrix need not have
+    /// a small pivot, but then its condition number is still large.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `lu` - The factorization.
+    /// * `diagnostics` - Its diagnostics.
+    fn lu_with_diagnostics(self)
+        -> NalgebraLapackResult<(LuFactorization<Self::N>, FactorizationDiagnostics<Self::N>)>;
+
+    /// compute the QR factorization and its diagnostics.
+    ///
+    /// This is `QrFactorize::qr_compact()`, with the condition number of the leading `k x k`
+    /// triangle of `R` estimated by `?trcon`, which is that of the matrix when it is tall or
+    /// square, and the pivots taken from the diagonal of `R`. Without column pivoting, as
+    /// with `RankRevealingQr`, the rank may be overestimated.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `qr` - The factorization.
+    /// * `diagnostics` - Its diagnostics.
+    fn qr_with_diagnostics(self)
+        -> NalgebraLapackResult<(QrCompact<Self::N>, FactorizationDiagnostics<Self::N>)>;
+
+    /// compute the Cholesky factorization of a symmetric positive-definite matrix and its
+    /// diagnostics.
+    ///
+    /// This is `CholeskyFactorize::cholesky_factorization()`, with the condition number
+    /// estimated by `?pocon` and the pivots `l_ii^2` taken from the diagonal of `L`. A matrix
+    /// that is not numerically positive definite is still reported as an error.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `cholesky` - The factorization.
+    /// * `diagnostics` - Its diagnostics.
+    fn cholesky_with_diagnostics(self)
+        -> NalgebraLapackResult<(CholeskyFactorization<Self::N>,
+                                 FactorizationDiagnostics<Self::N>)>;
+}
+
+/// The 1-norm, the largest absolute column sum, of `a`.
+fn norm1<N: Float>(a: &DMatrix<N>) -> N {
+    (0..a.ncols())
+        .map(|j| (0..a.nrows()).fold(N::zero(), |acc, i| acc + a[(i, j)].abs()))
+        .fold(N::zero(), |acc, x| acc.max(x))
+}
+
+macro_rules! factorize_with_diagnostics_impl(
+    ($t: ty, $trcon: path, $pocon: path) => (
+        impl FactorizeWithDiagnostics for DMatrix<$t> {
+            type N = $t;
+
+            fn lu_with_diagnostics(self)
+                -> NalgebraLapackResult<(LuFactorization<$t>, FactorizationDiagnostics<$t>)> {
+                let (lu, rcond) = self.factor_lu_with_condition()?;
+                let n = lu.factors().nrows();
+                let pivots: Vec<$t> = (0..n).map(|i| Float::abs(lu.factors()[(i, i)])).collect();
+                Ok((lu, FactorizationDiagnostics::new(rcond, &pivots, n)))
+            }
+
+            fn qr_with_diagnostics(self)
+                -> NalgebraLapackResult<(QrCompact<$t>, FactorizationDiagnostics<$t>)> {
+                let norm = b'1';
+                let uplo = b'U';
+                let diag = b'N';
+                let (m, n) = (self.nrows(), self.ncols());
+                let qr = self.qr_compact()?;
+                let k = qr.tau().len();
+                if k == 0 {
+                    return Ok((qr, FactorizationDiagnostics::new(1.0, &[], 0)));
+                }
+                let r = DMatrix::from_fn(k, k, |i, j| qr.factors()[(i, j)]);
+
+                let mut rcond = 0.0;
+                let mut work: Vec<$t> = vec![0.0; 3 * k];
+                let mut iwork = vec![0; k];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($trcon), k, k, &[norm, uplo, diag]);
+                $trcon(norm, uplo, diag, k as i32, r.as_vector(), k as i32, &mut rcond,
+                    &mut work, &mut iwork, &mut info);
+                check_info!(info);
+
+                let pivots: Vec<$t> = (0..k).map(|i| Float::abs(r[(i, i)])).collect();
+                let dim = if m >= n { m } else { n };
+                Ok((qr, FactorizationDiagnostics::new(rcond, &pivots, dim)))
+            }
+
+            fn cholesky_with_diagnostics(self)
+                -> NalgebraLapackResult<(CholeskyFactorization<$t>,
+                                         FactorizationDiagnostics<$t>)> {
+                let uplo = b'L';
+                let n = self.nrows();
+                let anorm = norm1(&self);
+                let cholesky = self.cholesky_factorization()?;
+                if n == 0 {
+                    return Ok((cholesky, FactorizationDiagnostics::new(1.0, &[], 0)));
+                }
+
+                let mut rcond = 0.0;
+                let mut work: Vec<$t> = vec![0.0; 3 * n];
+                let mut iwork = vec![0; n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($pocon), n, n, &[uplo]);
+                $pocon(uplo, n as i32, cholesky.lower().as_vector(), n as i32, anorm, &mut rcond,
+                    &mut work, &mut iwork, &mut info);
+                check_info!(info);
+
+                let pivots: Vec<$t> = (0..n)
+                    .map(|i| cholesky.lower()[(i, i)] * cholesky.lower()[(i, i)])
+                    .collect();
+                Ok((cholesky, FactorizationDiagnostics::new(rcond, &pivots, n)))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+factorize_with_diagnostics_impl!(f32, interface::strcon, interface::spocon);
+factorize_with_diagnostics_impl!(f64, interface::dtrcon, interface::dpocon);
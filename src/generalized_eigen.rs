@@ -0,0 +1,573 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::{DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use NalgebraLapackResult;
+
+/// An eigenvalue `lambda` of the generalized eigenproblem `A x = lambda B x`.
+///
+/// LAPACK returns each generalized eigenvalue as a ratio `alpha / beta`. When `B` is singular
+/// `beta` may be zero, in which case the eigenvalue is infinite and is reported as such rather
+/// than as the result of a division by zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GeneralizedEigenvalue<N> {
+    /// A finite eigenvalue.
+    Finite(Complex<N>),
+    /// An infinite eigenvalue, for which `beta` was zero within the tolerance.
+    Infinite,
+}
+
+impl<N: Copy> GeneralizedEigenvalue<N> {
+    /// return `true` if the eigenvalue is infinite.
+    pub fn is_infinite(&self) -> bool {
+        match *self {
+            GeneralizedEigenvalue::Finite(_) => false,
+            GeneralizedEigenvalue::Infinite => true,
+        }
+    }
+
+    /// return the eigenvalue if it is finite, otherwise `None`.
+    pub fn finite(&self) -> Option<Complex<N>> {
+        match *self {
+            GeneralizedEigenvalue::Finite(x) => Some(x),
+            GeneralizedEigenvalue::Infinite => None,
+        }
+    }
+}
+
+/// A type for which generalized eigenvalues and eigenvectors can be computed.
+pub trait GeneralizedEigensystem {
+    type N;
+
+    /// compute generalized eigenvalues and right eigenvectors of the pair `(self, b)`.
+    ///
+    /// These are the `lambda` and `x` satisfying `self x = lambda b x`.
+    ///
+    /// Because the input matrices may be overwritten or destroyed, they are consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The right-hand matrix of the pair. It may be singular.
+    /// * `tol` - An eigenvalue is reported as infinite when the magnitude of its `beta` is at
+    ///   or below this.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The generalized eigenvalues.
+    /// * `right_eigen_vectors` - The right eigenvectors. They are contained as columns of this
+    ///   matrix.
+    fn generalized_eigensystem(self, b: DMatrix<Self::N>, tol: Self::N)
+        -> NalgebraLapackResult<(DVector<GeneralizedEigenvalue<Self::N>>,
+                                 DMatrix<Complex<Self::N>>)>;
+
+    /// compute generalized eigenvalues and right eigenvectors of the pair `(self, b)`, sorted
+    /// by the magnitude of the eigenvalues.
+    ///
+    /// This is `generalized_eigensystem()` with the finite eigenvalues in ascending order of
+    /// magnitude, followed by the infinite ones, and the eigenvectors reordered in the same
+    /// way. The sort is stable, so eigenvalues of equal magnitude, such as a complex conjugate
+    /// pair, keep the order in which LAPACK returned them. This makes e.g. modal analysis
+    /// reproducible, since the order of the eigenvalues computed by LAPACK is unspecified.
+    ///
+    /// Because the input matrices may be overwritten or destroyed, they are consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The right-hand matrix of the pair. It may be singular.
+    /// * `tol` - An eigenvalue is reported as infinite when the magnitude of its `beta` is at
+    ///   or below this.
+    ///
+    /// # Returns
+    ///
+    /// The same as `generalized_eigensystem()`, in the sorted order.
+    fn generalized_eigensystem_sorted(self, b: DMatrix<Self::N>, tol: Self::N)
+        -> NalgebraLapackResult<(DVector<GeneralizedEigenvalue<Self::N>>,
+                                 DMatrix<Complex<Self::N>>)>;
+
+    /// compute the generalized eigenvalues of the pair `(self, b)` only.
+    ///
+    /// This is `generalized_eigensystem()` without the eigenvectors, which are not computed
+    /// (`JOBVL = JOBVR = 'N'`). This is cheaper when only the spectrum matters, e.g. to check the
+    /// stability of a descriptor system `B x' = A x`.
+    ///
+    /// Because the input matrices may be overwritten or destroyed, they are consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The right-hand matrix of the pair. It may be singular.
+    /// * `tol` - An eigenvalue is reported as infinite when the magnitude of its `beta` is at
+    ///   or below this.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The generalized eigenvalues, in the order of
+    ///   `generalized_eigensystem()`.
+    fn generalized_eigenvalues(self, b: DMatrix<Self::N>, tol: Self::N)
+        -> NalgebraLapackResult<DVector<GeneralizedEigenvalue<Self::N>>>;
+}
+
+/// decode the `alpha / beta` ratios returned by `?ggev` into generalized eigenvalues.
+fn decode_eigenvalues<N: Float>(alphar: &[N], alphai: &[N], beta: &[N], tol: N)
+    -> Vec<GeneralizedEigenvalue<N>> {
+    (0..beta.len())
+        .map(|j| {
+            if beta[j].abs() <= tol {
+                GeneralizedEigenvalue::Infinite
+            } else {
+                GeneralizedEigenvalue::Finite(
+                    Complex { re: alphar[j] / beta[j], im: alphai[j] / beta[j] })
+            }
+        })
+        .collect()
+}
+
+/// reorder generalized eigenpairs by ascending magnitude of the eigenvalues, with the
+/// infinite ones last.
+fn sort_by_magnitude<N: Float>(values: DVector<GeneralizedEigenvalue<N>>,
+                               vectors: DMatrix<Complex<N>>)
+    -> (DVector<GeneralizedEigenvalue<N>>, DMatrix<Complex<N>>) {
+    let magnitude = |x: &GeneralizedEigenvalue<N>| x.finite().map_or(N::infinity(), |x| x.norm());
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&i, &j| {
+        let (a, b) = (magnitude(&values[i]), magnitude(&values[j]));
+        a.partial_cmp(&b).unwrap_or(::std::cmp::Ordering::Equal)
+    });
+    let sorted = DVector { at: order.iter().map(|&i| values[i]).collect() };
+    let vectors = DMatrix::from_fn(vectors.nrows(), order.len(), |i, j| vectors[(i, order[j])]);
+    (sorted, vectors)
+}
+
+/// compute the eigenvalues and eigenvectors of the quadratic eigenvalue problem
+/// `(lambda^2 M + lambda C + K) x = 0`.
+///
+/// This is the equation of the free vibrations `M q'' + C q' + K q = 0` of a damped system
+/// with mass `M`, damping `C` and stiffness `K`, for `q(t) = x e^(lambda t)`. It is solved
+/// through the companion linearization: with `z = [x; lambda x]`, the problem is equivalent
+/// to the `2n x 2n` generalized eigenproblem
+///
+/// ```text
+/// [  0   I ] z = lambda [ I  0 ] z
+/// [ -K  -C ]            [ 0  M ]
+/// ```
+///
+/// which is solved with `GeneralizedEigensystem::generalized_eigensystem()`. A singular `M`
+/// gives infinite eigenvalues: they are those whose `beta` is at most `2n eps` times the
+/// largest of one and the largest magnitude in `M`.
+///
+/// # Arguments
+///
+/// * `m` - The `n x n` coefficient of `lambda^2`.
+/// * `c` - The `n x n` coefficient of `lambda`.
+/// * `k` - The `n x n` constant coefficient.
+///
+/// # Returns
+///
+/// * `eigen_values` - The `2n` eigenvalues.
+/// * `eigen_vectors` - The `n x 2n` eigenvectors `x`, the first `n` components of the
+///   eigenvectors of the linearization. They are contained as columns of this matrix, in the
+///   same order as the eigenvalues, and are not normalized.
+pub fn quadratic_eigensystem<N>(m: DMatrix<N>, c: DMatrix<N>, k: DMatrix<N>)
+    -> NalgebraLapackResult<(DVector<GeneralizedEigenvalue<N>>, DMatrix<Complex<N>>)>
+    where N: Float,
+          DMatrix<N>: GeneralizedEigensystem<N = N>
+{
+    let n = m.nrows();
+    let square = |a: &DMatrix<N>| a.nrows() == n && a.ncols() == n;
+    if !square(&m) || !square(&c) || !square(&k) {
+        return Err(Error::from(ErrorKind::MatrixNotSquare));
+    }
+    let (zero, one) = (N::zero(), N::one());
+    let a = DMatrix::from_fn(2 * n, 2 * n, |i, j| {
+        match (i < n, j < n) {
+            (true, true) => zero,
+            (true, false) => if j - n == i { one } else { zero },
+            (false, true) => -k[(i - n, j)],
+            (false, false) => -c[(i - n, j - n)],
+        }
+    });
+    let b = DMatrix::from_fn(2 * n, 2 * n, |i, j| {
+        match (i < n, j < n) {
+            (true, true) => if i == j { one } else { zero },
+            (false, false) => m[(i - n, j - n)],
+            _ => zero,
+        }
+    });
+    let scale = m.as_vector().iter().fold(one, |acc, x| acc.max(x.abs()));
+    let tol = N::from(2 * n).unwrap() * N::epsilon() * scale;
+
+    let (values, vectors) = a.generalized_eigensystem(b, tol)?;
+    let x = DMatrix::from_fn(n, 2 * n, |i, j| vectors[(i, j)]);
+    Ok((values, x))
+}
+
+/// A real symmetric matrix for which selected eigenpairs of the symmetric-definite
+/// generalized eigenproblem can be computed.
+pub trait SymmetricGeneralizedEigensystem {
+    type N;
+
+    /// compute the generalized eigenpairs of `(self, b)` with indices from `il` to `iu`.
+    ///
+    /// These are the `lambda` and `x` satisfying `self x = lambda b x` for a symmetric
+    /// `self` and a symmetric positive-definite `b`, counting from zero in ascending order of
+    /// the eigenvalues, as computed by `?sygvx`. Only the requested eigenpairs are computed,
+    /// which is much cheaper than the full problem when few are needed, e.g. for the lowest
+    /// vibration modes of a finite element model with stiffness `self` and mass `b`. Only the
+    /// lower triangles of the matrices are read.
+    ///
+    /// Because the input matrices may be overwritten or destroyed, they are consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The symmetric positive-definite right-hand matrix of the pair.
+    /// * `il` - The index of the smallest eigenvalue to compute.
+    /// * `iu` - The index of the largest eigenvalue to compute, inclusive.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The `iu - il + 1` selected eigenvalues, in ascending order.
+    /// * `eigen_vectors` - The eigenvectors, normalized so that `x^T b x = 1`. They are
+    ///   contained as columns of this matrix, in the same order as the eigenvalues.
+    ///
+    /// A `DimensionMismatch` error is returned if the matrices differ in size or the indices
+    /// are out of range, and a `MatrixNotPositiveDefinite` error if `b` is not positive
+    /// definite.
+    fn symmetric_generalized_eigensystem_range(self, b: DMatrix<Self::N>, il: usize, iu: usize)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+}
+
+/// A matrix pair balanced by `BalancePair::balance_pair()`.
+///
+/// The balanced pair is `(A', B') = (D_l P_l A P_r D_r, D_l P_l B P_r D_r)`, with permutations
+/// `P_l` and `P_r` and diagonal scalings `D_l` and `D_r`, as computed by LAPACK's `?ggbal`.
+/// It has the same generalized eigenvalues as `(A, B)`.
+#[derive(Clone)]
+pub struct BalancedPair<N> {
+    /// The balanced left-hand matrix `A'`.
+    pub a: DMatrix<N>,
+    /// The balanced right-hand matrix `B'`.
+    pub b: DMatrix<N>,
+    /// The row permutation and scaling, in LAPACK's encoding: for `j` in `ilo..=ihi`
+    /// (1-based) `lscale[j - 1]` is the scale factor of row `j`, and otherwise it is the index
+    /// of the row that was interchanged with row `j`.
+    pub lscale: DVector<N>,
+    /// The column permutation and scaling, encoded as `lscale`.
+    pub rscale: DVector<N>,
+    /// The 1-based index of the first row and column of the part of `A'` and `B'` that is not
+    /// already triangular.
+    pub ilo: usize,
+    /// The 1-based index of the last row and column of the part of `A'` and `B'` that is not
+    /// already triangular.
+    pub ihi: usize,
+}
+
+impl<N: Float> BalancedPair<N> {
+    /// transform right eigenvectors of the balanced pair into those of the original pair.
+    ///
+    /// This is what LAPACK's `?ggbak` does, for complex eigenvectors: if `A' x' = lambda B' x'`,
+    /// then `x = P_r D_r x'` satisfies `A x = lambda B x`. The vectors are not normalized
+    /// again.
+    ///
+    /// # Arguments
+    ///
+    /// * `v` - The right eigenvectors of the balanced pair, as columns.
+    ///
+    /// # Returns
+    ///
+    /// * `v` - The right eigenvectors of the original pair.
+    pub fn back_transform(&self, v: DMatrix<Complex<N>>) -> DMatrix<Complex<N>> {
+        let mut v = v;
+        let n = v.nrows();
+        for i in (self.ilo - 1)..self.ihi {
+            for j in 0..v.ncols() {
+                v[(i, j)] = v[(i, j)].scale(self.rscale[i]);
+            }
+        }
+        let interchanges = (0..(self.ilo - 1)).rev().chain(self.ihi..n);
+        for i in interchanges {
+            let k = self.rscale[i].to_usize().unwrap() - 1;
+            if k != i {
+                for j in 0..v.ncols() {
+                    let x = v[(i, j)];
+                    v[(i, j)] = v[(k, j)];
+                    v[(k, j)] = x;
+                }
+            }
+        }
+        v
+    }
+}
+
+/// A type for which a matrix pair can be balanced before computing its generalized
+/// eigensystem.
+pub trait BalancePair {
+    type N;
+
+    /// balance the pair `(self, b)` to improve the accuracy of its generalized eigenvalues.
+    ///
+    /// Rows and columns are first permuted to isolate eigenvalues where possible, and the
+    /// remaining part is scaled so that the elements of `A` and `B` are close to 1 in
+    /// magnitude. This helps for pairs whose elements vary greatly in magnitude. The balanced
+    /// pair can be passed to `GeneralizedEigensystem::generalized_eigensystem()`; its
+    /// eigenvectors are transformed back with `BalancedPair::back_transform()`.
+    ///
+    /// Because the input matrices may be overwritten or destroyed, they are consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The right-hand matrix of the pair.
+    ///
+    /// # Returns
+    ///
+    /// * `balanced` - The balanced pair and the transformation.
+    fn balance_pair(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<BalancedPair<Self::N>>;
+}
+
+macro_rules! balance_pair_impl(
+    ($t: ty, $lapack_func: path) => (
+        impl BalancePair for DMatrix<$t> {
+            type N = $t;
+
+            fn balance_pair(self, b: DMatrix<$t>) -> NalgebraLapackResult<BalancedPair<$t>> {
+                let job = b'B';
+
+                let mut a = self;
+                let mut b = b;
+                let n = a.nrows();
+                if a.ncols() != n || b.nrows() != n || b.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let lda = ::std::cmp::max(1, n) as i32;
+                let ldb = lda;
+
+                let mut ilo = 0;
+                let mut ihi = 0;
+                let mut lscale: Vec<$t> = vec![0.0; n];
+                let mut rscale: Vec<$t> = vec![0.0; n];
+                let mut work: Vec<$t> = vec![0.0; ::std::cmp::max(1, 6 * n)];
+                let mut info = 0;
+
+                $lapack_func(job, n as i32, a.as_mut_vector(), lda, b.as_mut_vector(), ldb,
+                    &mut ilo, &mut ihi, &mut lscale, &mut rscale, &mut work, &mut info);
+                check_info!(info);
+
+                Ok(BalancedPair {
+                    a,
+                    b,
+                    lscale: DVector { at: lscale },
+                    rscale: DVector { at: rscale },
+                    ilo: ilo as usize,
+                    ihi: ihi as usize,
+                })
+            }
+        }
+    );
+);
+
+macro_rules! generalized_eigensystem_impl(
+    ($t: ty, $lapack_func: path) => (
+        impl GeneralizedEigensystem for DMatrix<$t> {
+            type N = $t;
+
+            fn generalized_eigensystem(self, b: DMatrix<$t>, tol: $t)
+                -> NalgebraLapackResult<(DVector<GeneralizedEigenvalue<$t>>,
+                                         DMatrix<Complex<$t>>)> {
+                let jobvl = b'N';
+                let jobvr = b'V';
+
+                let mut a = self;
+                let mut b = b;
+                let n = a.nrows();
+                if a.ncols() != n || b.nrows() != n || b.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok((DVector { at: vec![] }, DMatrix::new_zeros(0, 0)));
+                }
+                let lda = n as i32;
+                let ldb = n as i32;
+                let ldvl = 1 as i32;
+                let ldvr = n;
+
+                let mut alphar: Vec<$t> = vec![0.0; n];
+                let mut alphai: Vec<$t> = vec![0.0; n];
+                let mut beta: Vec<$t> = vec![0.0; n];
+                let mut vl: Vec<$t> = vec![0.0; 1];
+                let mut vr: Vec<$t> = vec![0.0; n * n];
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $lapack_func(jobvl, jobvr, n as i32, a.as_mut_vector(), lda, b.as_mut_vector(),
+                    ldb, &mut alphar[0], &mut alphai[0], &mut beta[0], &mut vl[0], ldvl,
+                    &mut vr[0], ldvr as i32, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                ::diagnostics::record(stringify!($lapack_func), n, n, &[jobvl, jobvr]);
+                $lapack_func(jobvl, jobvr, n as i32, a.as_mut_vector(), lda, b.as_mut_vector(),
+                    ldb, &mut alphar[0], &mut alphai[0], &mut beta[0], &mut vl[0], ldvl,
+                    &mut vr[0], ldvr as i32, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                let eigen_values = decode_eigenvalues(&alphar, &alphai, &beta, tol);
+
+                // As for `dgeev`, a complex conjugate pair of eigenvectors is stored as its
+                // real and imaginary parts in two consecutive columns.
+                let mut vectors: Vec<Complex<$t>> = Vec::with_capacity(n * n);
+                let mut j = 0;
+                while j < n {
+                    if alphai[j] == 0.0 {
+                        for i in 0..n {
+                            vectors.push(Complex { re: vr[i + j * ldvr], im: 0.0 });
+                        }
+                        j += 1;
+                    } else {
+                        for i in 0..n {
+                            vectors.push(Complex { re: vr[i + j * ldvr], im: vr[i + (j + 1) * ldvr] });
+                        }
+                        for i in 0..n {
+                            vectors.push(Complex { re: vr[i + j * ldvr], im: -vr[i + (j + 1) * ldvr] });
+                        }
+                        j += 2;
+                    }
+                }
+
+                Ok((DVector { at: eigen_values }, DMatrix::from_column_vector(n, n, &vectors)))
+            }
+
+            fn generalized_eigensystem_sorted(self, b: DMatrix<$t>, tol: $t)
+                -> NalgebraLapackResult<(DVector<GeneralizedEigenvalue<$t>>,
+                                         DMatrix<Complex<$t>>)> {
+                let (values, vectors) = self.generalized_eigensystem(b, tol)?;
+                Ok(sort_by_magnitude(values, vectors))
+            }
+
+            fn generalized_eigenvalues(self, b: DMatrix<$t>, tol: $t)
+                -> NalgebraLapackResult<DVector<GeneralizedEigenvalue<$t>>> {
+                let jobvl = b'N';
+                let jobvr = b'N';
+
+                let mut a = self;
+                let mut b = b;
+                let n = a.nrows();
+                if a.ncols() != n || b.nrows() != n || b.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok(DVector { at: vec![] });
+                }
+                let lda = n as i32;
+                let ldb = n as i32;
+                let ldv = 1 as i32;
+
+                let mut alphar: Vec<$t> = vec![0.0; n];
+                let mut alphai: Vec<$t> = vec![0.0; n];
+                let mut beta: Vec<$t> = vec![0.0; n];
+                let mut vl: Vec<$t> = vec![0.0; 1];
+                let mut vr: Vec<$t> = vec![0.0; 1];
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $lapack_func(jobvl, jobvr, n as i32, a.as_mut_vector(), lda, b.as_mut_vector(),
+                    ldb, &mut alphar[0], &mut alphai[0], &mut beta[0], &mut vl[0], ldv,
+                    &mut vr[0], ldv, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                ::diagnostics::record(stringify!($lapack_func), n, n, &[jobvl, jobvr]);
+                $lapack_func(jobvl, jobvr, n as i32, a.as_mut_vector(), lda, b.as_mut_vector(),
+                    ldb, &mut alphar[0], &mut alphai[0], &mut beta[0], &mut vl[0], ldv,
+                    &mut vr[0], ldv, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                Ok(DVector { at: decode_eigenvalues(&alphar, &alphai, &beta, tol) })
+            }
+        }
+    );
+);
+
+macro_rules! symmetric_generalized_eigensystem_impl(
+    ($t: ty, $sygvx: path) => (
+        impl SymmetricGeneralizedEigensystem for DMatrix<$t> {
+            type N = $t;
+
+            fn symmetric_generalized_eigensystem_range(self, b: DMatrix<$t>, il: usize,
+                                                       iu: usize)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                let itype = [1];
+                let jobz = b'V';
+                let range = b'I';
+                let uplo = b'L';
+
+                let mut a = self;
+                let mut b = b;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if b.nrows() != n || b.ncols() != n || il > iu || iu >= n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let lda = n as i32;
+                // Twice the underflow threshold, as recommended for inverse iteration.
+                let abstol = 2.0 * <$t as Float>::min_positive_value();
+
+                let mut found = 0;
+                let mut w: Vec<$t> = vec![0.0; n];
+                let mut z: DMatrix<$t> = DMatrix::new_zeros(n, iu - il + 1);
+                let mut iwork = vec![0; 5 * n];
+                let mut ifail = vec![0; n];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                // The binding declares `ifail` as a scalar, but LAPACK writes `n` elements to
+                // it, so the first element of a large enough buffer is passed.
+                $sygvx(&itype, jobz, range, uplo, n as i32, a.as_mut_vector(), lda,
+                    b.as_mut_vector(), lda, 0.0, 0.0, (il + 1) as i32, (iu + 1) as i32, abstol,
+                    &mut found, &mut w, z.as_mut_vector(), lda, &mut work, lwork, &mut iwork,
+                    &mut ifail[0], &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                ::diagnostics::record(stringify!($sygvx), n, n, &[jobz, range, uplo]);
+                $sygvx(&itype, jobz, range, uplo, n as i32, a.as_mut_vector(), lda,
+                    b.as_mut_vector(), lda, 0.0, 0.0, (il + 1) as i32, (iu + 1) as i32, abstol,
+                    &mut found, &mut w, z.as_mut_vector(), lda, &mut work, lwork, &mut iwork,
+                    &mut ifail[0], &mut info);
+                // A positive `info` above `n` means that the factorization of `B` failed,
+                // otherwise it is the number of eigenvectors that failed to converge.
+                if info > n as i32 {
+                    return Err(Error::from(ErrorKind::MatrixNotPositiveDefinite));
+                } else if info > 0 {
+                    return Err(Error::from(ErrorKind::ConvergenceFailure));
+                }
+                check_info!(info);
+
+                let m = found as usize;
+                w.truncate(m);
+                Ok((DVector { at: w }, DMatrix::from_fn(n, m, |i, j| z[(i, j)])))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+generalized_eigensystem_impl!(f32, interface::sggev);
+generalized_eigensystem_impl!(f64, interface::dggev);
+balance_pair_impl!(f32, interface::sggbal);
+balance_pair_impl!(f64, interface::dggbal);
+symmetric_generalized_eigensystem_impl!(f32, interface::ssygvx);
+symmetric_generalized_eigensystem_impl!(f64, interface::dsygvx);
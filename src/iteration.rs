@@ -0,0 +1,24 @@
+use num::Float;
+
+/// Convergence control for the iterative routines, such as
+/// `MatrixSign::matrix_sign_with()` and `InverseIteration::eigenvector_for_with()`.
+///
+/// The default is a tolerance of `sqrt(eps)` and at most 100 iterations. What the tolerance
+/// is relative to is documented by each routine.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IterOptions<N> {
+    /// The convergence tolerance.
+    pub tol: N,
+    /// The maximum number of iterations. A `ConvergenceFailure` error is returned if the
+    /// tolerance is not reached by then.
+    pub max_iters: usize,
+}
+
+impl<N: Float> Default for IterOptions<N> {
+    fn default() -> IterOptions<N> {
+        IterOptions {
+            tol: N::epsilon().sqrt(),
+            max_iters: 100,
+        }
+    }
+}
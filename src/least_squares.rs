@@ -0,0 +1,732 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::{DMatrix, DVector, Transpose};
+
+use errors::{Error, ErrorKind};
+use scratch::workspace;
+use {LapackScratch, NalgebraLapackResult, SvdOptions, SvdWith};
+
+/// A type for which linear least-squares problems can be solved.
+pub trait LeastSquares {
+    type N;
+
+    /// solve a linear least-squares problem using the SVD.
+    ///
+    /// Given `a` and `b`, find the `x` minimizing `||b - ax||`. If `a` is rank deficient, the
+    /// solution of minimum norm is returned. Singular values below machine precision times the
+    /// largest singular value are treated as zero.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The least-squares solution, with as many rows as `a` has columns.
+    fn least_squares(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a linear least-squares problem with a single right-hand side using the SVD.
+    ///
+    /// This is the same as `least_squares()` with `b` as a single-column matrix.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known vector, with as many elements as `a` has rows.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The least-squares solution, with as many elements as `a` has columns, or `None`
+    ///   if `least_squares()` fails.
+    fn least_squares_vector(self, b: DVector<Self::N>) -> Option<DVector<Self::N>>
+        where Self: Sized, Self::N: Copy
+    {
+        let b = DMatrix::from_column_vector(b.len(), 1, &b.at);
+        let x = self.least_squares(b).ok()?;
+        Some(DVector { at: x.into_vector() })
+    }
+
+    /// solve a linear least-squares problem using the SVD, also returning the numerical rank.
+    ///
+    /// This is the same as `least_squares()`, but also reports how rank deficient `a` was.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The minimum-norm least-squares solution.
+    /// * `rank` - The numerical rank of `a`: the number of singular values above machine
+    ///   precision times the largest one.
+    fn solve_reporting_rank(self, b: DMatrix<Self::N>)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, usize)>;
+
+    /// solve a linear least-squares problem using the SVD, also returning the residual.
+    ///
+    /// The residual `r = b - ax` is computed from a copy of `a` kept for the purpose, since
+    /// `a` is destroyed by the solve. Its columns are orthogonal to the column space of `a`
+    /// up to rounding errors, also for a rank-deficient `a`, and their norms measure the
+    /// goodness of fit.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The minimum-norm least-squares solution, as returned by `least_squares()`.
+    /// * `r` - The residual `b - ax`, of the same shape as `b`.
+    fn least_squares_with_residual(self, b: DMatrix<Self::N>)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DMatrix<Self::N>)>;
+
+    /// solve a weighted linear least-squares problem using the SVD.
+    ///
+    /// Given `a`, `b` and the weights `w`, find the `x` minimizing `sum_i w_i ||(b - ax)_i||^2`,
+    /// where `(.)_i` is row `i`. This is done by scaling row `i` of both `a` and `b` by
+    /// `sqrt(w_i)` and calling `least_squares()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    /// * `weights` - The non-negative weight of each row.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The weighted least-squares solution, with as many rows as `a` has columns.
+    fn weighted_least_squares(self, b: DMatrix<Self::N>, weights: DVector<Self::N>)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a full-rank linear least-squares problem using the QR or LQ factorization.
+    ///
+    /// If `a` has at least as many rows as columns, this finds the least-squares solution of
+    /// the overdetermined system. Otherwise it finds the minimum-norm solution of the
+    /// underdetermined system. This is faster than `least_squares()`, but `a` must have full
+    /// rank: an exactly rank-deficient `a` is reported as an error, and a nearly
+    /// rank-deficient one gives an inaccurate solution.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution, with as many rows as `a` has columns.
+    fn solve_qr(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a full-rank linear least-squares or minimum-norm problem using the QR or LQ
+    /// factorization, borrowing the LAPACK workspace from `scratch`.
+    ///
+    /// This is the same as `solve_qr()`, but reuses `scratch` instead of allocating a new
+    /// workspace.
+    fn solve_qr_with_workspace(self, b: DMatrix<Self::N>, scratch: &mut LapackScratch)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve an underdetermined full-rank linear system for its minimum-norm solution, using
+    /// the LQ factorization.
+    ///
+    /// For a wide `a` with full row rank, `ax = b` has infinitely many solutions. With
+    /// `a = L Q`, the one of minimum 2-norm is `x = Q^T L^-1 b`. This is `solve_qr()`
+    /// restricted to wide matrices, and is faster than the SVD used by `least_squares()`, with
+    /// the same caveats about rank deficiency.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The minimum-norm solution, with as many rows as `a` has columns. A
+    ///   `DimensionMismatch` error is returned unless `a` has fewer rows than columns.
+    fn min_norm_solve(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve the normal equations `a^T a x = a^T b` without forming `a^T a`.
+    ///
+    /// The solution of the normal equations is the least-squares solution, but computing it
+    /// as `(a^T a)^-1 a^T b` squares the condition number: a solution that `a` determines to
+    /// `eps cond(a)` relative accuracy is only found to about `eps cond(a)^2`, and `a^T a` is
+    /// numerically singular as soon as `cond(a)` approaches `1 / sqrt(eps)`. This solves the
+    /// same problem with the QR factorization `a = Q R` instead, as `R x = Q^T b`, which
+    /// works with `a` itself. It is `solve_qr()` restricted to tall matrices, under a name
+    /// that is easy to find when replacing that formula.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The least-squares solution, with as many rows as `a` has columns. A
+    ///   `DimensionMismatch` error is returned if `a` has fewer rows than columns.
+    fn solve_normal_equations(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a linear least-squares problem using the SVD, also returning measures of the
+    /// reliability of the solution.
+    ///
+    /// This is the same as `least_squares()`, but also returns the 2-norm condition number of
+    /// `a`, from the singular values that LAPACK computes anyway, and the norm of the residual
+    /// of each column. A large condition number means that the solution is sensitive to
+    /// perturbations of `a` and `b`, and more so when the residual is large.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The minimum-norm least-squares solution.
+    /// * `condition_number` - The ratio of the largest to the smallest singular value of `a`.
+    ///   This is infinite if `a` is exactly rank deficient.
+    /// * `residual_norms` - The Euclidean norm of each column of `b - ax`.
+    fn least_squares_with_condition(self, b: DMatrix<Self::N>)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, Self::N, DVector<Self::N>)>;
+
+    /// solve a possibly rank-deficient linear least-squares problem using the QR
+    /// factorization with column pivoting.
+    ///
+    /// This uses `?gelsy`, which estimates the effective rank from the triangular factor of
+    /// the pivoted QR factorization as the largest leading block whose estimated reciprocal
+    /// condition number stays above `rcond`, and returns the minimum-norm solution for that
+    /// rank through a complete orthogonal factorization. It is usually faster than the SVD of
+    /// `least_squares()`, and rank deficiency is handled, but like `RankRevealingQr` the
+    /// rank may be overestimated for some adversarial matrices.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    /// * `rcond` - The threshold on the reciprocal condition number of the leading triangular
+    ///   block.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The least-squares solution, with as many rows as `a` has columns.
+    /// * `rank` - The effective rank of `a`.
+    fn least_squares_qr(self, b: DMatrix<Self::N>, rcond: Self::N)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, usize)>;
+
+    /// solve a linear least-squares problem using the SVD, returning everything `?gelsd`
+    /// computes.
+    ///
+    /// This is the same as `least_squares()` with a caller-chosen truncation threshold, also
+    /// returning the singular values of `a` and its effective rank.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    /// * `rcond` - Singular values at or below `rcond` times the largest one are treated as
+    ///   zero. A negative value selects machine precision.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The minimum-norm least-squares solution of the truncated problem.
+    /// * `s` - The singular values of `a`, in decreasing order.
+    /// * `rank` - The effective rank of `a`: the number of singular values above the
+    ///   threshold.
+    fn least_squares_full(self, b: DMatrix<Self::N>, rcond: Self::N)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DVector<Self::N>, usize)>;
+
+    /// compute the effective degrees of freedom of ridge regression.
+    ///
+    /// This is the trace of the ridge hat matrix `A (A^T A + lambda I)^-1 A^T`, which is
+    /// `sum_i s_i^2 / (s_i^2 + lambda)` in terms of the singular values `s_i` of `a`. It goes
+    /// from the rank of `a` for `lambda = 0` down to zero as `lambda` grows, and is used for
+    /// model selection criteria such as generalized cross-validation.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `lambda` - The non-negative regularization parameter.
+    ///
+    /// # Returns
+    ///
+    /// * `dof` - The effective degrees of freedom, or `None` if the SVD fails.
+    fn ridge_effective_dof(self, lambda: Self::N) -> Option<Self::N>;
+
+    /// solve a ridge (Tikhonov) regularized linear least-squares problem using the SVD.
+    ///
+    /// Given `a`, `b` and `lambda`, find the `x` minimizing `||b - ax||^2 + lambda ||x||^2`.
+    /// With the economy SVD `a = U diag(s) V^T`, this is `x = V diag(s / (s^2 + lambda)) U^T b`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    /// * `lambda` - The non-negative regularization parameter.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The regularized solution, with as many rows as `a` has columns.
+    fn ridge_least_squares(self, b: DMatrix<Self::N>, lambda: Self::N)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve ridge regularized linear least-squares problems for several regularization
+    /// parameters from a single SVD.
+    ///
+    /// This is the same as calling `ridge_least_squares()` for each of `lambdas`, but the SVD
+    /// of `a` and `U^T b` are only computed once, as when scanning `lambda` for model
+    /// selection.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    /// * `lambdas` - The non-negative regularization parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `xs` - The regularized solution for each of `lambdas`, in the same order.
+    fn ridge_least_squares_path(self, b: DMatrix<Self::N>, lambdas: &[Self::N])
+        -> NalgebraLapackResult<Vec<DMatrix<Self::N>>>;
+
+    /// solve a ridge regularized linear least-squares problem with a single right-hand side
+    /// for several regularization parameters, returning the solutions as columns.
+    ///
+    /// This is `ridge_least_squares_path()` for the vector `b`, with the whole regularization
+    /// path in one matrix, e.g. to plot each coefficient against `lambda`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known vector, with as many elements as `a` has rows.
+    /// * `lambdas` - The non-negative regularization parameters.
+    ///
+    /// # Returns
+    ///
+    /// * `xs` - The matrix whose column `j` is the regularized solution for `lambdas[j]`.
+    fn ridge_path(self, b: DVector<Self::N>, lambdas: &[Self::N])
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// A complex matrix for which linear least-squares problems can be solved.
+pub trait ComplexLeastSquares {
+    type N;
+
+    /// solve a complex linear least-squares problem using the SVD.
+    ///
+    /// Given `a` and `b`, find the `x` minimizing `||b - ax||`, with the minimum norm solution
+    /// if `a` is rank deficient, as `LeastSquares::least_squares()` does for real matrices.
+    /// This calls `?gelsd` for complex matrices, which works with the conjugate transpose
+    /// `a^H` throughout: the solution satisfies the normal equations `a^H a x = a^H b`. With
+    /// the plain transpose instead, `a^T a x = a^T b`, which a port of real code may silently
+    /// form, the residual is not orthogonal to the column space and the answer is wrong for
+    /// complex data. Singular values below machine precision times the largest singular value
+    /// are treated as zero.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix, with as many rows as `a`.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The least-squares solution, with as many rows as `a` has columns.
+    fn least_squares_complex(self, b: DMatrix<Complex<Self::N>>)
+        -> NalgebraLapackResult<DMatrix<Complex<Self::N>>>;
+}
+
+/// The SVD-based least-squares driver behind `LeastSquares`.
+trait Gelsd {
+    type N;
+
+    /// Solve with `?gelsd`, treating singular values below `rcond` times the largest one as
+    /// zero, and return the solution, all singular values of `a` and the effective rank.
+    fn gelsd(self, b: DMatrix<Self::N>, rcond: Self::N)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, Vec<Self::N>, usize)>;
+}
+
+/// Copy `b` into the top of a `ldb x nrhs` matrix, as LAPACK's least-squares drivers need room
+/// for both the right-hand side and the solution.
+fn pad_rhs<N: Copy>(b: &DMatrix<N>, ldb: usize, zero: N) -> DMatrix<N> {
+    DMatrix::from_fn(ldb, b.ncols(), |i, j| if i < b.nrows() { b[(i, j)] } else { zero })
+}
+
+/// Take the top `n` rows of the `ldb x nrhs` matrix in which LAPACK returned the solution.
+fn take_solution<N: Copy>(b: &DMatrix<N>, n: usize) -> DMatrix<N> {
+    DMatrix::from_fn(n, b.ncols(), |i, j| b[(i, j)])
+}
+
+macro_rules! least_squares_impl(
+    ($t: ty, $gelsd: path, $gels: path, $gelsy: path) => (
+        impl Gelsd for DMatrix<$t> {
+            type N = $t;
+
+            fn gelsd(self, b: DMatrix<$t>, rcond: $t)
+                -> NalgebraLapackResult<(DMatrix<$t>, Vec<$t>, usize)> {
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                if b.nrows() != m {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let ldb = ::std::cmp::max(1, ::std::cmp::max(m, n));
+                let mut x = pad_rhs(&b, ldb, 0.0);
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, m) as i32;
+
+                let mut s: Vec<$t> = vec![0.0; ::std::cmp::max(1, ::std::cmp::min(m, n))];
+                let mut rank = 0;
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut iwork = vec![0];
+                let mut info = 0;
+
+                $gelsd(m as i32, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(),
+                    ldb as i32, &mut s, rcond, &mut rank, &mut work, lwork, &mut iwork,
+                    &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+                let mut iwork = vec![0; ::std::cmp::max(1, iwork[0]) as usize];
+
+                ::diagnostics::record(stringify!($gelsd), m, n, &[]);
+                $gelsd(m as i32, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(),
+                    ldb as i32, &mut s, rcond, &mut rank, &mut work, lwork, &mut iwork,
+                    &mut info);
+                check_info!(info);
+
+                s.truncate(::std::cmp::min(m, n));
+                Ok((take_solution(&x, n), s, rank as usize))
+            }
+        }
+
+        impl LeastSquares for DMatrix<$t> {
+            type N = $t;
+
+            fn least_squares(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                let (x, _) = self.solve_reporting_rank(b)?;
+                Ok(x)
+            }
+
+            fn solve_reporting_rank(self, b: DMatrix<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, usize)> {
+                let (x, _, rank) = self.gelsd(b, -1.0)?;
+                Ok((x, rank))
+            }
+
+            fn least_squares_with_residual(self, b: DMatrix<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, DMatrix<$t>)> {
+                let a = self.clone();
+                let x = self.least_squares(b.clone())?;
+                let r = b - &a * &x;
+                Ok((x, r))
+            }
+
+            fn weighted_least_squares(self, b: DMatrix<$t>, weights: DVector<$t>)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let m = self.nrows();
+                if b.nrows() != m || weights.len() != m {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let scale: Vec<$t> = weights.at.iter().map(|w| Float::sqrt(*w)).collect();
+                let a = DMatrix::from_fn(m, self.ncols(), |i, j| scale[i] * self[(i, j)]);
+                let b = DMatrix::from_fn(m, b.ncols(), |i, j| scale[i] * b[(i, j)]);
+                a.least_squares(b)
+            }
+
+            fn solve_qr(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                self.solve_qr_with_workspace(b, &mut LapackScratch::new())
+            }
+
+            fn solve_qr_with_workspace(self, b: DMatrix<$t>, scratch: &mut LapackScratch)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let trans = b'N';
+
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                if b.nrows() != m {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let ldb = ::std::cmp::max(1, ::std::cmp::max(m, n));
+                let mut x = pad_rhs(&b, ldb, 0.0);
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, m) as i32;
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $gels(trans, m as i32, n as i32, nrhs, a.as_mut_vector(), lda,
+                    x.as_mut_vector(), ldb as i32, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let work = workspace::<$t>(scratch, lwork as usize);
+
+                // A positive `info` means that a diagonal element of the triangular factor is
+                // exactly zero, i.e. `a` does not have full rank.
+                $gels(trans, m as i32, n as i32, nrhs, a.as_mut_vector(), lda,
+                    x.as_mut_vector(), ldb as i32, work, lwork, &mut info);
+                check_info!(info);
+
+                Ok(take_solution(&x, n))
+            }
+
+            fn min_norm_solve(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                if self.nrows() >= self.ncols() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                self.solve_qr(b)
+            }
+
+            fn solve_normal_equations(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                if self.nrows() < self.ncols() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                self.solve_qr(b)
+            }
+
+            fn least_squares_qr(self, b: DMatrix<$t>, rcond: $t)
+                -> NalgebraLapackResult<(DMatrix<$t>, usize)> {
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                if b.nrows() != m {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let ldb = ::std::cmp::max(1, ::std::cmp::max(m, n));
+                let mut x = pad_rhs(&b, ldb, 0.0);
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, m) as i32;
+
+                // Zero entries let every column be pivoted freely.
+                let mut jpvt: Vec<i32> = vec![0; n];
+                let mut rank = 0;
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $gelsy(m as i32, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(),
+                    ldb as i32, &mut jpvt, rcond, &mut rank, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                ::diagnostics::record(stringify!($gelsy), m, n, &[]);
+                $gelsy(m as i32, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(),
+                    ldb as i32, &mut jpvt, rcond, &mut rank, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                Ok((take_solution(&x, n), rank as usize))
+            }
+
+            fn least_squares_with_condition(self, b: DMatrix<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, $t, DVector<$t>)> {
+                let a = self.clone();
+                let (x, s, _) = self.gelsd(b.clone(), -1.0)?;
+                let condition_number = match s.len() {
+                    0 => 1.0,
+                    k if s[k - 1] == 0.0 => <$t as Float>::infinity(),
+                    k => s[0] / s[k - 1],
+                };
+                let residual = b - &a * &x;
+                let residual_norms = (0..residual.ncols())
+                    .map(|j| {
+                        Float::sqrt((0..residual.nrows())
+                            .fold(0.0, |acc, i| acc + residual[(i, j)] * residual[(i, j)]))
+                    })
+                    .collect();
+                Ok((x, condition_number, DVector { at: residual_norms }))
+            }
+
+            fn least_squares_full(self, b: DMatrix<$t>, rcond: $t)
+                -> NalgebraLapackResult<(DMatrix<$t>, DVector<$t>, usize)> {
+                let (x, s, rank) = self.gelsd(b, rcond)?;
+                Ok((x, DVector { at: s }, rank))
+            }
+
+            fn ridge_effective_dof(self, lambda: $t) -> Option<$t> {
+                let options = SvdOptions {
+                    compute_u: false,
+                    compute_vt: false,
+                    ..SvdOptions::default()
+                };
+                let s = self.svd_with(options).ok()?.s;
+                // Exactly zero singular values contribute nothing, even for `lambda = 0`.
+                Some(s.at.iter()
+                    .filter(|x| **x > 0.0)
+                    .fold(0.0, |acc, x| acc + x * x / (x * x + lambda)))
+            }
+
+            fn ridge_least_squares(self, b: DMatrix<$t>, lambda: $t)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let mut xs = self.ridge_least_squares_path(b, &[lambda])?;
+                Ok(xs.remove(0))
+            }
+
+            fn ridge_least_squares_path(self, b: DMatrix<$t>, lambdas: &[$t])
+                -> NalgebraLapackResult<Vec<DMatrix<$t>>> {
+                if b.nrows() != self.nrows() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let options = SvdOptions { economy: true, ..SvdOptions::default() };
+                let svd = self.svd_with(options)?;
+                // Both sets of singular vectors are computed by these options.
+                let (u, s, v) = (svd.u.unwrap(), svd.s, svd.vt.unwrap().transpose());
+                let k = s.len();
+                let utb = &u.transpose() * &b;
+
+                let xs = lambdas.iter()
+                    .map(|lambda| {
+                        let scaled = DMatrix::from_fn(k, b.ncols(), |i, j| {
+                            let d = s[i] * s[i] + lambda;
+                            if d > 0.0 { utb[(i, j)] * s[i] / d } else { 0.0 }
+                        });
+                        &v * &scaled
+                    })
+                    .collect();
+                Ok(xs)
+            }
+
+            fn ridge_path(self, b: DVector<$t>, lambdas: &[$t])
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let n = self.ncols();
+                let b = DMatrix::from_column_vector(b.len(), 1, &b.at);
+                let xs = self.ridge_least_squares_path(b, lambdas)?;
+                Ok(DMatrix::from_fn(n, lambdas.len(), |i, j| xs[j][(i, 0)]))
+            }
+        }
+    );
+);
+
+macro_rules! complex_least_squares_impl(
+    ($t: ty, $gelsd: path) => (
+        impl ComplexLeastSquares for DMatrix<Complex<$t>> {
+            type N = $t;
+
+            fn least_squares_complex(self, b: DMatrix<Complex<$t>>)
+                -> NalgebraLapackResult<DMatrix<Complex<$t>>> {
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                if b.nrows() != m {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let zero = Complex { re: 0.0, im: 0.0 };
+                let ldb = ::std::cmp::max(1, ::std::cmp::max(m, n));
+                let mut x = pad_rhs(&b, ldb, zero);
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, m) as i32;
+                let rcond = -1.0;
+
+                let mut s: Vec<$t> = vec![0.0; ::std::cmp::max(1, ::std::cmp::min(m, n))];
+                let mut rank = 0;
+                let mut work = vec![zero];
+                let mut lwork = -1 as i32;
+                let mut rwork: Vec<$t> = vec![0.0];
+                let mut iwork = vec![0];
+                let mut info = 0;
+
+                $gelsd(m as i32, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(),
+                    ldb as i32, &mut s, rcond, &mut rank, &mut work, lwork, &mut rwork,
+                    &mut iwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0].re as i32;
+                let mut work = vec![zero; lwork as usize];
+                let mut rwork: Vec<$t> = vec![0.0; ::std::cmp::max(1, rwork[0] as usize)];
+                let mut iwork = vec![0; ::std::cmp::max(1, iwork[0]) as usize];
+
+                ::diagnostics::record(stringify!($gelsd), m, n, &[]);
+                $gelsd(m as i32, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(),
+                    ldb as i32, &mut s, rcond, &mut rank, &mut work, lwork, &mut rwork,
+                    &mut iwork, &mut info);
+                check_info!(info);
+
+                Ok(take_solution(&x, n))
+            }
+        }
+    );
+);
+
+/// `?gelsd` overwrites `a`, which the `lapack` binding declares as a shared slice, so the
+/// routines are declared again here with `a` as a mutable pointer.
+mod gelsd_mut {
+    use num::complex::Complex;
+
+    macro_rules! real_gelsd(
+        ($name: ident, $symbol: ident, $t: ty) => (
+            extern "C" {
+                fn $symbol(m: *const i32, n: *const i32, nrhs: *const i32, a: *mut $t,
+                           lda: *const i32, b: *mut $t, ldb: *const i32, s: *mut $t,
+                           rcond: *const $t, rank: *mut i32, work: *mut $t, lwork: *const i32,
+                           iwork: *mut i32, info: *mut i32);
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            pub fn $name(m: i32, n: i32, nrhs: i32, a: &mut [$t], lda: i32, b: &mut [$t],
+                         ldb: i32, s: &mut [$t], rcond: $t, rank: &mut i32, work: &mut [$t],
+                         lwork: i32, iwork: &mut [i32], info: &mut i32) {
+                unsafe {
+                    $symbol(&m, &n, &nrhs, a.as_mut_ptr(), &lda, b.as_mut_ptr(), &ldb,
+                            s.as_mut_ptr(), &rcond, rank, work.as_mut_ptr(), &lwork,
+                            iwork.as_mut_ptr(), info)
+                }
+            }
+        );
+    );
+
+    macro_rules! complex_gelsd(
+        ($name: ident, $symbol: ident, $t: ty) => (
+            extern "C" {
+                fn $symbol(m: *const i32, n: *const i32, nrhs: *const i32, a: *mut Complex<$t>,
+                           lda: *const i32, b: *mut Complex<$t>, ldb: *const i32, s: *mut $t,
+                           rcond: *const $t, rank: *mut i32, work: *mut Complex<$t>,
+                           lwork: *const i32, rwork: *mut $t, iwork: *mut i32, info: *mut i32);
+            }
+
+            #[allow(clippy::too_many_arguments)]
+            pub fn $name(m: i32, n: i32, nrhs: i32, a: &mut [Complex<$t>], lda: i32,
+                         b: &mut [Complex<$t>], ldb: i32, s: &mut [$t], rcond: $t,
+                         rank: &mut i32, work: &mut [Complex<$t>], lwork: i32, rwork: &mut [$t],
+                         iwork: &mut [i32], info: &mut i32) {
+                unsafe {
+                    $symbol(&m, &n, &nrhs, a.as_mut_ptr(), &lda, b.as_mut_ptr(), &ldb,
+                            s.as_mut_ptr(), &rcond, rank, work.as_mut_ptr(), &lwork,
+                            rwork.as_mut_ptr(), iwork.as_mut_ptr(), info)
+                }
+            }
+        );
+    );
+
+    real_gelsd!(sgelsd, sgelsd_, f32);
+    real_gelsd!(dgelsd, dgelsd_, f64);
+    complex_gelsd!(cgelsd, cgelsd_, f32);
+    complex_gelsd!(zgelsd, zgelsd_, f64);
+}
+
+use lapack::fortran as interface;
+
+least_squares_impl!(f32, gelsd_mut::sgelsd, interface::sgels, interface::sgelsy);
+least_squares_impl!(f64, gelsd_mut::dgelsd, interface::dgels, interface::dgelsy);
+complex_least_squares_impl!(f32, gelsd_mut::cgelsd);
+complex_least_squares_impl!(f64, gelsd_mut::zgelsd);
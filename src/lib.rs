@@ -52,28 +52,128 @@ fn main() {
 [2]: https://crates.io/crates/lapack
 */
 
+// The decompositions return their factors as tuples, as `svd()` and `eigensystem()` always
+// have, rather than through an alias per combination.
+#![allow(clippy::type_complexity)]
+
 extern crate nalgebra;
 extern crate lapack;
 extern crate num;
 #[macro_use]
 extern crate error_chain;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "matrixcompare")]
+pub extern crate matrixcompare;
+#[cfg(feature = "matrixcompare")]
+extern crate matrixcompare_core;
+
+// Defined before the modules below so that they can use it.
+macro_rules! check_info(
+    ($info: expr) => (
+        if $info < 0 {
+            return Err(Error::from(ErrorKind::LapackIllegalArgument(-$info)));
+        } else if $info > 0 {
+            return Err(Error::from(ErrorKind::LapackFailure($info)));
+        }
+    );
+);
 
 pub mod errors;
+mod diagnostics;
+mod eigen;
+mod symmetric_eigen;
+mod svd;
+mod svd_handle;
+mod lu;
+mod generalized_eigen;
+mod least_squares;
+mod matrix_functions;
+mod riccati;
+mod qr;
+mod partial_svd;
+mod structured_solve;
+mod schur;
+mod polynomial;
+mod procrustes;
+mod real;
+mod canonical_correlation;
+mod cholesky;
+mod iteration;
+mod scratch;
+mod banded;
+mod threading;
+mod block;
+mod spectral;
+mod diagonal;
+mod pca;
+mod factorization_diagnostics;
+#[cfg(feature = "matrixcompare")]
+pub mod compare;
 
 use errors::{Error, ErrorKind};
 use num::complex::Complex;
-use num::Zero;
+use num::{Num, One, Zero};
 
-use nalgebra::{DMatrix, DVector, Iterable, Eye};
+use nalgebra::{DMatrix, DVector, Eye};
 
 pub use errors::Error as NalgebraLapackError;
 pub use errors::Result as NalgebraLapackResult;
 
+pub use eigen::{EigenResult, InverseIteration, BlockDiagonalEigensystem, eigensystem_batch,
+                GeneralizedEigenvectors, JordanChain, ShiftInvert, NumericalRange,
+                OrthogonalEigenvalues, EigensystemRowVectors, DepartureFromNormality,
+                DeterministicEigensystem, NumericalAbscissa};
+pub use symmetric_eigen::{SymmetricEigensystem, WhiteningTransform, SymmetricPseudoinverse,
+                          SymmetricTridiagonalize, SkewSymmetricEigenvalues, TraceOfFunction,
+                          SymmetricTrigonometric, RealIfSymmetric, RealOrComplexEigensystem,
+                          TraceOfInverse, spd_geometric_mean};
+pub use svd::{ColumnSpace, SvdAlgorithm, SvdOptions, SvdResult, SvdWith, SvdBuilder,
+              TruncatedSvd, SMART_SVD_ASPECT_RATIO, DistanceToSingularity, BlockSvd,
+              MixedPrecisionSvd, PseudoDeterminant, InverseOperatorNorm};
+pub use svd_handle::{SvdCutoff, SvdHandle};
+pub use lu::{LU, LuFactorization, LuSolveIter, DeterminantSign, DeterminantAndInverse,
+             ScaledDeterminant, ReducedRowEchelon, apply_permutation, log_determinant_ratio};
+pub use generalized_eigen::{GeneralizedEigensystem, GeneralizedEigenvalue, BalancePair,
+                            BalancedPair, SymmetricGeneralizedEigensystem,
+                            quadratic_eigensystem};
+pub use least_squares::{ComplexLeastSquares, LeastSquares};
+pub use matrix_functions::{GroupInverse, InverseSqrt, MatrixAbs, MatrixFunction, MatrixSign,
+                           PolarNewton};
+pub use riccati::solve_care;
+pub use qr::{MutualCoherence, Orthonormalize, QrCompact, QrFactorize, RankRevealingQr};
+pub use partial_svd::PartialSvd;
+pub use structured_solve::{HermitianSolve, ScaledSolve, SchurComplement, SolveMethod,
+                           StructuredSolve, solve_block_tridiagonal};
+pub use schur::{StableSubspace, real_schur_to_complex};
+pub use polynomial::polynomial_roots;
+pub use diagnostics::{RoutineCall, last_routine, routine_call_count};
+pub use procrustes::{kabsch, orthogonal_procrustes, nearest_rotation, rotation_from_skew};
+pub use real::ToRealChecked;
+pub use canonical_correlation::{canonical_correlations, principal_angles};
+pub use cholesky::{CholeskyFactorization, CholeskyFactorize, CholeskyOfInverse, PivotedCholesky};
+pub use iteration::IterOptions;
+pub use scratch::LapackScratch;
+pub use banded::{BandedMatrix, EigenvalueRange};
+pub use threading::{get_num_threads, set_num_threads};
+pub use block::MatrixBlock;
+pub use spectral::laplacian_eigenmaps;
+pub use diagonal::DiagonalFastPath;
+pub use pca::pca_dimension_mle;
+pub use factorization_diagnostics::{FactorizationDiagnostics, FactorizeWithDiagnostics};
+
 /// A type for which eigenvalues and eigenvectors can be computed.
 pub trait Eigensystem {
     type N;
     /// compute eigenvalues and right eigenvectors
     ///
+    /// An empty or all-zero matrix is handled without calling LAPACK: its eigenvalues are zero
+    /// and the identity matrix is returned as the eigenvectors.
+    ///
+    /// For a real matrix, the complex eigenvalues come in conjugate pairs, which are always
+    /// returned adjacently with the member of positive imaginary part first. The eigenvectors
+    /// of a pair are conjugate as well, in the same order.
+    ///
     /// Because the input matrix may be overwritten or destroyed, it is consumed.
     ///
     /// # Returns
@@ -91,6 +191,13 @@ pub trait SVD {
     type M;
     /// compute the singular value decomposition (SVD). Returns full matrices.
     ///
+    /// An empty or all-zero matrix is handled without calling LAPACK: its singular values are
+    /// zero and identity matrices are returned as the singular vectors.
+    ///
+    /// Real 2x2 and 3x3 matrices use a closed-form solver instead, which avoids the overhead
+    /// of calling into LAPACK for tiny matrices. LAPACK is still used if the closed form does
+    /// not give a finite result.
+    ///
     /// Because the input matrix may be overwritten or destroyed, it is consumed.
     ///
     /// # Returns
@@ -119,6 +226,27 @@ pub trait Solve
     ///
     /// * `x` - The solution to the linear equation `ax=b`.
     fn solve(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a linear equation with a single right-hand side.
+    ///
+    /// This is the same as `solve()` with `b` as a single-column matrix.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known vector.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`, or `None` if `solve()` fails.
+    fn solve_vector(self, b: DVector<Self::N>) -> Option<DVector<Self::N>>
+        where Self: Sized, Self::N: Copy
+    {
+        let b = DMatrix::from_column_vector(b.len(), 1, &b.at);
+        let x = self.solve(b).ok()?;
+        Some(DVector { at: x.into_vector() })
+    }
 }
 
 /// A type for which the inverse can be computed.
@@ -134,6 +262,28 @@ pub trait Inverse : Solve
     fn inv(self) -> NalgebraLapackResult<DMatrix<<Self as Solve>::N>> where Self: std::marker::Sized;
 }
 
+/// The triangle of a symmetric or Hermitian matrix that a routine reads.
+///
+/// The other triangle is never accessed, so it need not be filled in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Triangle {
+    /// The upper triangle, including the diagonal.
+    Upper,
+    /// The lower triangle, including the diagonal.
+    #[default]
+    Lower,
+}
+
+impl Triangle {
+    /// The LAPACK `uplo` argument for this triangle.
+    fn uplo(self) -> u8 {
+        match self {
+            Triangle::Upper => b'U',
+            Triangle::Lower => b'L',
+        }
+    }
+}
+
 /// A type for which the Cholesky decomposition can be computed.
 pub trait Cholesky
 {
@@ -141,39 +291,181 @@ pub trait Cholesky
 
     /// computes the cholesky decomposition of a Hermitian positive-definite matrix.
     ///
+    /// Only the lower triangle of the matrix is read.
+    ///
     /// Because the input matrix may be overwritten or destroyed, it is consumed.
     ///
     /// # Returns
     ///
     /// * `lower_triangular` - The lower triangular part of the decomposed matrix.
     fn cholesky(self) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// computes the cholesky decomposition of a Hermitian positive-definite matrix, reading
+    /// only the given triangle.
+    ///
+    /// This is `cholesky()` for a matrix of which only `triangle` has been filled in.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `triangle` - The triangle of the matrix that is read.
+    ///
+    /// # Returns
+    ///
+    /// * `lower_triangular` - The lower triangular factor `L` with `A = L L^H`, whichever
+    ///   triangle was read.
+    fn cholesky_with_triangle(self, triangle: Triangle)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
 }
 
-macro_rules! check_info(
-    ($info: expr) => (
-        if $info < 0 {
-            return Err(Error::from(ErrorKind::LapackIllegalArgument(-$info)));
-        } else if $info > 0 {
-            return Err(Error::from(ErrorKind::LapackFailure($info)));
+/// A scalar type for which LAPACK-backed decompositions are available.
+///
+/// This exposes the scalar-specific dispatch (`f32` vs `f64` vs complex) so that generic code
+/// can be written over any supported element type. It is implemented for `f32`, `f64`,
+/// `Complex<f32>` and `Complex<f64>` by calling the corresponding LAPACK routines. A
+/// downstream crate can implement it for its own scalar type (for example, an extended
+/// precision float) by providing the routines below, at which point generic code bounded by
+/// `LapackScalar` works with that type too.
+///
+/// # Required items
+///
+/// * `Real` - The real type underlying the scalar. This is `Self` for real types and the type
+///   of the real and imaginary parts for complex types. Singular values and the parts of
+///   eigenvalues are of this type.
+/// * `svd` - The singular value decomposition routine (`?gesvd` for the built-in types), with
+///   the semantics of `SVD::svd()`.
+/// * `eigensystem` - The general eigensystem routine (`?geev` for the built-in types), with
+///   the semantics of `Eigensystem::eigensystem()`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate nalgebra_lapack;
+/// extern crate nalgebra as na;
+///
+/// use nalgebra_lapack::{LapackScalar, NalgebraLapackResult};
+/// use na::DMatrix;
+///
+/// /// Return the number of singular values of any supported matrix.
+/// fn process<N: LapackScalar>(m: DMatrix<N>) -> NalgebraLapackResult<usize> {
+///     let (_, s, _) = N::svd(m)?;
+///     Ok(s.len())
+/// }
+///
+/// fn main() {
+///     let m = DMatrix::from_row_vector(2, 3, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+///     assert_eq!(process(m).unwrap(), 2);
+/// }
+/// ```
+pub trait LapackScalar: Copy {
+    type Real: Copy;
+
+    /// compute the singular value decomposition (SVD) of `m`. Returns full matrices.
+    ///
+    /// # Returns
+    ///
+    /// * `u` - The left-singular vectors.
+    /// * `s` - The singular values.
+    /// * `vt` - The right-singular vectors.
+    fn svd(m: DMatrix<Self>)
+        -> NalgebraLapackResult<(DMatrix<Self>, DVector<Self::Real>, DMatrix<Self>)>;
+
+    /// compute eigenvalues and right eigenvectors of `m`.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues.
+    /// * `right_eigen_vectors` - The right eigenvectors. They are contained as columns of this
+    ///   matrix.
+    fn eigensystem(m: DMatrix<Self>)
+        -> NalgebraLapackResult<(DVector<Complex<Self::Real>>, DMatrix<Complex<Self::Real>>)>;
+}
+
+macro_rules! lapack_scalar_impl(
+    ($t: ty, $real: ty) => (
+        impl LapackScalar for $t {
+            type Real = $real;
+
+            fn svd(m: DMatrix<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, DVector<$real>, DMatrix<$t>)> {
+                SVD::svd(m)
+            }
+
+            fn eigensystem(m: DMatrix<$t>)
+                -> NalgebraLapackResult<(DVector<Complex<$real>>, DMatrix<Complex<$real>>)> {
+                Eigensystem::eigensystem(m)
+            }
         }
     );
 );
 
+/// Return the SVD `(I, 0, I)` of a matrix that is empty or all zero, for which the singular
+/// vectors are arbitrary, or `None` if the matrix has a nonzero element.
+fn trivial_svd<M, V>(a: &DMatrix<M>) -> Option<(DMatrix<M>, DVector<V>, DMatrix<M>)>
+    where M: Zero + One + Copy + PartialEq,
+          V: Zero + Clone
+{
+    if a.as_vector().iter().any(|x| *x != M::zero()) {
+        return None;
+    }
+    let min_mn = if a.nrows() <= a.ncols() { a.nrows() } else { a.ncols() };
+    Some((DMatrix::new_identity(a.nrows()),
+          DVector::from_element(min_mn, V::zero()),
+          DMatrix::new_identity(a.ncols())))
+}
+
+/// Return the eigensystem of a square matrix that is empty or all zero, for which every
+/// vector is an eigenvector, or `None` if the matrix has a nonzero element.
+fn trivial_eigensystem<M, N>(a: &DMatrix<M>)
+    -> Option<(DVector<Complex<N>>, DMatrix<Complex<N>>)>
+    where M: Zero + PartialEq,
+          N: Num + Copy
+{
+    if a.as_vector().iter().any(|x| *x != M::zero()) {
+        return None;
+    }
+    Some((DVector::from_element(a.nrows(), Complex::zero()), DMatrix::new_identity(a.nrows())))
+}
+
+/// The real general eigensystem routine behind `Eigensystem`, on column-major storage with
+/// leading dimension `lda`, which may be a block of a larger matrix.
+trait GeevInPlace: Sized {
+    fn geev_in_place(a: &mut [Self], n: usize, lda: usize)
+        -> NalgebraLapackResult<(DVector<Complex<Self>>, DMatrix<Complex<Self>>)>;
+}
+
+/// The real SVD routine behind `SVD`, on column-major storage with leading dimension `lda`,
+/// which may be a block of a larger matrix.
+trait GesvdInPlace: Sized {
+    fn gesvd_in_place(a: &mut [Self], m: usize, n: usize, lda: usize)
+        -> NalgebraLapackResult<(DMatrix<Self>, DVector<Self>, DMatrix<Self>)>;
+}
+
+/// Check that `a` can hold an `m x n` column-major matrix with leading dimension `lda`, as
+/// LAPACK will access it.
+///
+/// LAPACK only receives a pointer, so a leading dimension smaller than the number of rows, or
+/// storage that ends before the last column, would make it read and write the wrong elements
+/// or past the end of the buffer. An `InvalidLayout` error is returned instead.
+fn check_layout<N>(a: &[N], m: usize, n: usize, lda: usize) -> NalgebraLapackResult<()> {
+    let needed = if m == 0 || n == 0 { 0 } else { (n - 1) * lda + m };
+    if lda < ::std::cmp::max(1, m) || a.len() < needed {
+        return Err(Error::from(ErrorKind::InvalidLayout));
+    }
+    Ok(())
+}
+
 macro_rules! eigensystem_impl(
     ($t: ty, $lapack_func: path) => (
-        impl Eigensystem for DMatrix<$t> {
-            type N = $t;
-            fn eigensystem(mut self) ->
+        impl GeevInPlace for $t {
+            fn geev_in_place(a: &mut [$t], n: usize, lda: usize) ->
             NalgebraLapackResult<(DVector<Complex<$t>>, DMatrix<Complex<$t>>)> {
+                check_layout(a, n, n, lda)?;
                 let jobvl = b'N';
                 let jobvr = b'V';
 
-                if self.ncols() != self.nrows() {
-                    return Err(Error::from(ErrorKind::MatrixNotSquare));
-                }
-                let n = self.ncols();
-
-                let lda = n as i32;
+                let lda = lda as i32;
                 let ldvl = 1 as i32;
                 let ldvr = n;
 
@@ -187,7 +479,7 @@ macro_rules! eigensystem_impl(
                 let mut lwork = -1 as i32;
                 let mut info = 0;
 
-                $lapack_func(jobvl, jobvr, n as i32, self.as_mut_vector(), lda, wr.as_mut(),
+                $lapack_func(jobvl, jobvr, n as i32, a, lda, wr.as_mut(),
                     wi.as_mut(), vl.as_mut(), ldvl, vr.as_mut(), ldvr as i32,
                     &mut work, lwork, &mut info);
 
@@ -196,29 +488,48 @@ macro_rules! eigensystem_impl(
                 lwork = work[0] as i32;
                 let mut work = vec![0.0; lwork as usize];
 
-                $lapack_func(jobvl, jobvr, n as i32, self.as_mut_vector(), lda, wr.as_mut(),
+                ::diagnostics::record_with_lda(stringify!($lapack_func), n, n, lda as usize,
+                                               &[jobvl, jobvr]);
+                $lapack_func(jobvl, jobvr, n as i32, a, lda, wr.as_mut(),
                     wi.as_mut(), vl.as_mut(), ldvl, vr.as_mut(), ldvr as i32,
                     &mut work, lwork, &mut info);
 
                 // TODO: should figure out how to return the correct eigenvalues.
                 check_info!(info);
 
-                let x: Vec<Complex<$t>> = wr
-                    .iter()
-                    .zip(wi.iter())
-                    .map( |(r,i)| {Complex{re:*r,im:*i}} )
-                    .collect();
+                // LAPACK returns a conjugate pair adjacently, the vector `x + i y` of the first
+                // member being packed as the columns `x` and `y`. The first member should have
+                // positive imaginary part; should it not, the pair is swapped, which conjugates
+                // the vector.
+                let mut x: Vec<Complex<$t>> = Vec::with_capacity(n);
+                let mut sign: Vec<$t> = Vec::with_capacity(n);
+                let mut j = 0;
+                while j < n {
+                    if wi[j] == 0.0 || j + 1 == n {
+                        x.push(Complex { re: wr[j], im: wi[j] });
+                        sign.push(0.0);
+                        j += 1;
+                    } else {
+                        let s = if wi[j] > 0.0 { 1.0 } else { -1.0 };
+                        x.push(Complex { re: wr[j], im: wi[j].abs() });
+                        x.push(Complex { re: wr[j], im: -wi[j].abs() });
+                        sign.push(s);
+                        sign.push(s);
+                        j += 2;
+                    }
+                }
                 let eigen_values = DVector{at:x};
                 let mut result: Vec<Complex<$t>> = Vec::with_capacity(n*n);
                 for i in 0..n {
                     let mut j = 0;
                     while j < n {
-                        if eigen_values[j].im == 0.0 {
+                        if sign[j] == 0.0 {
                             result.push( Complex{ re: vr[i+j*ldvr], im: 0.0 });
                             j += 1;
                         } else {
-                            result.push( Complex{ re: vr[i+j*ldvr], im: vr[i+(j+1)*ldvr] });
-                            result.push( Complex{ re: vr[i+j*ldvr], im: -vr[i+(j+1)*ldvr] });
+                            let im = sign[j] * vr[i+(j+1)*ldvr];
+                            result.push( Complex{ re: vr[i+j*ldvr], im });
+                            result.push( Complex{ re: vr[i+j*ldvr], im: -im });
                             j += 2;
                         }
                     }
@@ -227,6 +538,21 @@ macro_rules! eigensystem_impl(
                 Ok((eigen_values,right_eigen_vectors))
             }
         }
+
+        impl Eigensystem for DMatrix<$t> {
+            type N = $t;
+            fn eigensystem(mut self) ->
+            NalgebraLapackResult<(DVector<Complex<$t>>, DMatrix<Complex<$t>>)> {
+                if self.ncols() != self.nrows() {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if let Some(result) = trivial_eigensystem(&self) {
+                    return Ok(result);
+                }
+                let n = self.ncols();
+                <$t as GeevInPlace>::geev_in_place(self.as_mut_vector(), n, n)
+            }
+        }
     );
 );
 
@@ -243,6 +569,9 @@ macro_rules! eigensystem_complex_impl(
                 if self.ncols() != self.nrows() {
                     return Err(Error::from(ErrorKind::MatrixNotSquare));
                 }
+                if let Some(result) = trivial_eigensystem(&self) {
+                    return Ok(result);
+                }
                 let n = self.ncols();
 
                 let lda = n as i32;
@@ -271,6 +600,7 @@ macro_rules! eigensystem_complex_impl(
                 lwork = work[0].re as i32;
                 let mut work = vec![Complex{re:0.0, im:0.0}; lwork as usize];
 
+                ::diagnostics::record(stringify!($lapack_func), n, n, &[jobvl, jobvr]);
                 $lapack_func(jobvl, jobvr, n as i32, self.as_mut_vector(), lda, w.as_mut(),
                     vl.as_mut(), ldvl, vr.as_mut(), ldvr,
                     & mut work, lwork, & mut rwork, &mut info);
@@ -286,17 +616,14 @@ macro_rules! eigensystem_complex_impl(
 
 macro_rules! svd_impl(
     ($t: ty, $lapack_func: path) => (
-        impl SVD for DMatrix<$t> {
-            type V = $t;
-            type M = $t;
-            fn svd(mut self) -> NalgebraLapackResult<(DMatrix<$t>, DVector<$t>, DMatrix<$t>)> {
-                let m = self.nrows();
-                let n = self.ncols();
-
+        impl GesvdInPlace for $t {
+            fn gesvd_in_place(a: &mut [$t], m: usize, n: usize, lda: usize)
+                -> NalgebraLapackResult<(DMatrix<$t>, DVector<$t>, DMatrix<$t>)> {
+                check_layout(a, m, n, lda)?;
                 let jobu = b'A';
                 let jobvt = b'A';
 
-                let lda = m as i32;
+                let lda = lda as i32;
                 let min_mn = if m <= n { m } else {n};
                 let mut s: DVector<$t> = DVector::from_element( min_mn, 0.0);
                 let ldu = m;
@@ -307,7 +634,7 @@ macro_rules! svd_impl(
                 let mut lwork = -1 as i32;
                 let mut info = 0;
 
-                $lapack_func(jobu, jobvt, m as i32, n as i32, self.as_mut_vector(), lda,
+                $lapack_func(jobu, jobvt, m as i32, n as i32, a, lda,
                     &mut s.as_mut(), u.as_mut_vector(), ldu as i32, vt.as_mut_vector(),
                     ldvt as i32, &mut work, lwork, &mut info);
                 check_info!(info);
@@ -315,7 +642,9 @@ macro_rules! svd_impl(
                 lwork = work[0] as i32;
                 work = vec![0.0; lwork as usize];
 
-                $lapack_func(jobu, jobvt, m as i32, n as i32, self.as_mut_vector(), lda,
+                ::diagnostics::record_with_lda(stringify!($lapack_func), m, n, lda as usize,
+                                               &[jobu, jobvt]);
+                $lapack_func(jobu, jobvt, m as i32, n as i32, a, lda,
                     &mut s.as_mut(), u.as_mut_vector(), ldu as i32, vt.as_mut_vector(),
                     ldvt as i32, &mut work, lwork, &mut info);
                 check_info!(info);
@@ -323,6 +652,22 @@ macro_rules! svd_impl(
                 Ok((u, s, vt))
             }
         }
+
+        impl SVD for DMatrix<$t> {
+            type V = $t;
+            type M = $t;
+            fn svd(mut self) -> NalgebraLapackResult<(DMatrix<$t>, DVector<$t>, DMatrix<$t>)> {
+                if let Some(result) = trivial_svd(&self) {
+                    return Ok(result);
+                }
+                if let Some(result) = ::svd::closed_form_svd(&self) {
+                    return Ok(result);
+                }
+                let m = self.nrows();
+                let n = self.ncols();
+                <$t as GesvdInPlace>::gesvd_in_place(self.as_mut_vector(), m, n, m)
+            }
+        }
     );
 );
 
@@ -333,6 +678,9 @@ macro_rules! svd_complex_impl(
             type M=Complex<$t>;
             fn svd(mut self)
                 -> NalgebraLapackResult<(DMatrix<Complex<$t>>, DVector<$t>, DMatrix<Complex<$t>>)> {
+                if let Some(result) = trivial_svd(&self) {
+                    return Ok(result);
+                }
                 let m = self.nrows();
                 let n = self.ncols();
 
@@ -361,6 +709,7 @@ macro_rules! svd_complex_impl(
                 lwork = work[0].re as i32;
                 let mut work: Vec<Complex<$t>> = vec![Complex{re:0.0, im:0.0}; lwork as usize];
 
+                ::diagnostics::record(stringify!($lapack_func), m, n, &[jobu, jobvt]);
                 $lapack_func(jobu, jobvt, m as i32, n as i32, self.as_mut_vector(), lda,
                              &mut s.as_mut(),
                              u.as_mut_vector(), ldu as i32, vt.as_mut_vector(), ldvt as i32,
@@ -386,7 +735,11 @@ macro_rules! solve_impl(
                     return Err(Error::from(ErrorKind::MatrixNotSquare));
                 }
 
-                let nrhs = n as i32;
+                if b.nrows() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+
+                let nrhs = b.ncols() as i32;
                 let lda = n as i32;
                 let ldb = n as i32;
 
@@ -418,19 +771,36 @@ macro_rules! inverse_impl(
 );
 
 macro_rules! cholesky_impl(
-    ($t: ty, $lapack_func: path) => (
+    ($t: ty, $lapack_func: path, $conj: expr) => (
         impl Cholesky for DMatrix<$t> {
             type N = $t;
             fn cholesky(self) -> NalgebraLapackResult<DMatrix<$t>> {
-                let uplo = b'L';
+                self.cholesky_with_triangle(Triangle::Lower)
+            }
+
+            fn cholesky_with_triangle(self, triangle: Triangle)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let uplo = triangle.uplo();
                 let mut a = self;
                 let n = a.nrows() as i32;
                 let lda = n;
                 let mut info = 0;
 
+                ::diagnostics::record(stringify!($lapack_func), n as usize, n as usize, &[uplo]);
                 $lapack_func(uplo, n, a.as_mut_vector(), lda, &mut info);
                 check_info!(info);
 
+                // With the upper triangle, LAPACK computes `U = L^H`.
+                if triangle == Triangle::Upper {
+                    let conj = $conj;
+                    for i in 0..a.nrows() {
+                        for j in 0..i {
+                            a[(i,j)] = conj(a[(j,i)]);
+                        }
+                        a[(i,i)] = conj(a[(i,i)]);
+                    }
+                }
+
 // zero the upper-triangular part
 
                 for i in 0..a.nrows() {
@@ -469,7 +839,12 @@ inverse_impl!(f64);
 inverse_impl!(Complex<f32>);
 inverse_impl!(Complex<f64>);
 
-cholesky_impl!(f32, interface::spotrf);
-cholesky_impl!(f64, interface::dpotrf);
-cholesky_impl!(Complex<f32>, interface::cpotrf);
-cholesky_impl!(Complex<f64>, interface::zpotrf);
+cholesky_impl!(f32, interface::spotrf, |x: f32| x);
+cholesky_impl!(f64, interface::dpotrf, |x: f64| x);
+cholesky_impl!(Complex<f32>, interface::cpotrf, |x: Complex<f32>| x.conj());
+cholesky_impl!(Complex<f64>, interface::zpotrf, |x: Complex<f64>| x.conj());
+
+lapack_scalar_impl!(f32, f32);
+lapack_scalar_impl!(f64, f64);
+lapack_scalar_impl!(Complex<f32>, f32);
+lapack_scalar_impl!(Complex<f64>, f64);
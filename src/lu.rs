@@ -0,0 +1,1091 @@
+use num::complex::Complex;
+use num::{Float, NumCast, One, Zero};
+
+use nalgebra::{DMatrix, DVector, Transpose};
+
+use errors::{Error, ErrorKind};
+use {LapackScalar, NalgebraLapackResult};
+
+/// The LU factorization `P A = L U` of a square matrix, with partial pivoting.
+///
+/// This is computed once with `LU::lu()` and can then be reused to solve several systems.
+/// With `LU::lu_as_transpose()`, the factorized matrix is the transpose of the logical matrix
+/// `A`, which is remembered so that systems with `A` and `A^T` are solved correctly.
+#[derive(Clone)]
+pub struct LuFactorization<N> {
+    lu: DMatrix<N>,
+    ipiv: Vec<i32>,
+    transposed: bool,
+}
+
+impl<N> LuFactorization<N> {
+    /// return the factors `L` and `U` packed in one matrix, as computed by LAPACK.
+    ///
+    /// These are the factors of the matrix that was factorized, i.e. of `A^T` if
+    /// `is_transposed()`. `U` is the upper triangle including the diagonal. `L` is the
+    /// strictly lower triangle; its unit diagonal is not stored.
+    pub fn factors(&self) -> &DMatrix<N> {
+        &self.lu
+    }
+
+    /// return the 1-based pivot indices: row `i` was interchanged with row `pivots()[i]`.
+    pub fn pivots(&self) -> &[i32] {
+        &self.ipiv
+    }
+
+    /// return `true` if the factorized matrix is the transpose `A^T` of the logical matrix.
+    pub fn is_transposed(&self) -> bool {
+        self.transposed
+    }
+
+    /// return the row permutation `P` as 0-based indices: row `i` of `P A` is row
+    /// `permutation_vector()[i]` of `A`.
+    ///
+    /// This replays the interchanges of `pivots()` in order. It is the cheap way to apply `P`,
+    /// with `apply_permutation()`, rather than multiplying by the dense matrix.
+    pub fn permutation_vector(&self) -> Vec<usize> {
+        let mut perm: Vec<usize> = (0..self.ipiv.len()).collect();
+        for (i, p) in self.ipiv.iter().enumerate() {
+            perm.swap(i, (*p - 1) as usize);
+        }
+        perm
+    }
+}
+
+impl<N: Copy + Zero + One> LuFactorization<N> {
+    /// Split the packed factors into the unit lower triangular `L` and the upper triangular
+    /// `U`.
+    fn unpack(&self) -> (DMatrix<N>, DMatrix<N>) {
+        let n = self.lu.nrows();
+        let l = DMatrix::from_fn(n, n, |i, j| {
+            if i > j { self.lu[(i, j)] } else if i == j { N::one() } else { N::zero() }
+        });
+        let u = DMatrix::from_fn(n, n, |i, j| if i <= j { self.lu[(i, j)] } else { N::zero() });
+        (l, u)
+    }
+}
+
+/// apply a row permutation to a vector.
+///
+/// # Arguments
+///
+/// * `perm` - The 0-based permutation, e.g. from `LuFactorization::permutation_vector()`.
+/// * `v` - The vector to permute, of the same length.
+///
+/// # Returns
+///
+/// * `permuted` - The vector `P v`, whose element `i` is `v[perm[i]]`.
+pub fn apply_permutation<N: Copy>(perm: &[usize], v: &DVector<N>) -> DVector<N> {
+    DVector { at: perm.iter().map(|&p| v[p]).collect() }
+}
+
+impl<N: Float> LuFactorization<N> {
+    /// compute a basis of the null space from the zero pivots of `U`.
+    ///
+    /// As `L` and `P` are invertible, `A` has the null space of `U`. For each pivot `u_kk` that
+    /// is zero to within `tol` times the largest element of `U`, the vector with `x_k = 1` and
+    /// zeros after it is completed by back substitution through `U x = 0`. This is much
+    /// cheaper than an SVD and exact for well-structured matrices, e.g. with small integer
+    /// entries, but partial pivoting is not rank revealing: a nearly singular matrix need not
+    /// have a small pivot, and a candidate is dropped when the back substitution meets a
+    /// second zero pivot whose equation is not satisfied. The SVD, e.g. the complement from
+    /// `ColumnSpace::column_space_split()` of `A^T`, should be used when robustness matters.
+    ///
+    /// This is the null space of the factorized matrix, i.e. of `A^T` if `is_transposed()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The relative threshold below which a pivot is regarded as zero.
+    ///
+    /// # Returns
+    ///
+    /// * `null_space` - The `n x k` matrix whose columns, normalized to unit norm, are linearly
+    ///   independent null vectors, one for each zero pivot that gave a consistent solution.
+    pub fn null_space(&self, tol: N) -> DMatrix<N> {
+        let u = &self.lu;
+        let n = u.nrows();
+        let mut largest = N::zero();
+        for j in 0..n {
+            for i in 0..(j + 1) {
+                largest = largest.max(u[(i, j)].abs());
+            }
+        }
+        let threshold = tol * largest;
+        let is_zero_pivot = |k: usize| u[(k, k)].abs() <= threshold;
+
+        let mut basis: Vec<Vec<N>> = vec![];
+        'pivots: for k in (0..n).filter(|&k| is_zero_pivot(k)) {
+            let mut x = vec![N::zero(); n];
+            x[k] = N::one();
+            for i in (0..k).rev() {
+                let sum = ((i + 1)..(k + 1)).fold(N::zero(), |acc, j| acc + u[(i, j)] * x[j]);
+                if is_zero_pivot(i) {
+                    // `x_i` is free and set to zero; the equation must already hold.
+                    let scale = x.iter().fold(N::zero(), |acc, v| acc.max(v.abs()));
+                    if sum.abs() > threshold * scale {
+                        continue 'pivots;
+                    }
+                } else {
+                    x[i] = -sum / u[(i, i)];
+                }
+            }
+            let norm = x.iter().fold(N::zero(), |acc, v| acc + *v * *v).sqrt();
+            basis.push(x.into_iter().map(|v| v / norm).collect());
+        }
+        DMatrix::from_fn(n, basis.len(), |i, j| basis[j][i])
+    }
+
+    /// compute the sign and the natural logarithm of the absolute value of the determinant.
+    ///
+    /// The logarithm is accumulated as the sum of `ln|u_ii|` over the diagonal of `U`, so
+    /// unlike the determinant itself it does not overflow or underflow for large matrices.
+    ///
+    /// # Returns
+    ///
+    /// * `sign` - `1` or `-1`, or `0` if the matrix is exactly singular.
+    /// * `log_abs_det` - `ln|det(A)|`; negative infinity if the matrix is exactly singular.
+    pub fn sign_and_log_determinant(&self) -> (i8, N) {
+        let u = &self.lu;
+        let mut sign = 1;
+        let mut log_abs_det = N::zero();
+        for i in 0..u.nrows() {
+            if u[(i, i)] == N::zero() {
+                return (0, N::neg_infinity());
+            }
+            if (self.ipiv[i] != (i + 1) as i32) != (u[(i, i)] < N::zero()) {
+                sign = -sign;
+            }
+            log_abs_det = log_abs_det + u[(i, i)].abs().ln();
+        }
+        (sign, log_abs_det)
+    }
+
+    /// compute the determinant as a mantissa and a binary exponent.
+    ///
+    /// The mantissa and the exponent of each diagonal element of `U` are multiplied and added
+    /// separately, with the product renormalized after each step, so the signed determinant
+    /// `mantissa * 2^exponent` is kept to full precision even where the determinant itself
+    /// overflows or underflows.
+    ///
+    /// # Returns
+    ///
+    /// * `mantissa` - The signed mantissa, with `0.5 <= |mantissa| < 1`, or zero if the matrix
+    ///   is exactly singular.
+    /// * `exponent` - The binary exponent, zero if the matrix is exactly singular.
+    pub fn determinant_scaled(&self) -> (N, i32) {
+        let u = &self.lu;
+        let mut mantissa = N::one();
+        let mut exponent = 0;
+        for i in 0..u.nrows() {
+            if u[(i, i)] == N::zero() {
+                return (N::zero(), 0);
+            }
+            let (m, e) = frexp(u[(i, i)]);
+            let (product, e_product) = frexp(mantissa * m);
+            mantissa = product;
+            exponent += e + e_product;
+            if self.ipiv[i] != (i + 1) as i32 {
+                mantissa = -mantissa;
+            }
+        }
+        if u.nrows() == 0 {
+            return frexp(mantissa);
+        }
+        (mantissa, exponent)
+    }
+}
+
+/// Split the nonzero, finite `x` into `m 2^e` with `0.5 <= |m| < 1`.
+fn frexp<N: Float>(x: N) -> (N, i32) {
+    let (mantissa, exponent, sign) = x.integer_decode();
+    let bits = 64 - mantissa.leading_zeros() as i32;
+    let two = N::one() + N::one();
+    // The integer mantissa has no more bits than `N` has digits, so this is exact.
+    let m = <N as NumCast>::from(mantissa).unwrap() * two.powi(-bits);
+    (if sign < 0 { -m } else { m }, exponent as i32 + bits)
+}
+
+/// compute the ratio of two determinants as a sign and a logarithm.
+///
+/// Each determinant is found from an LU factorization as by
+/// `LuFactorization::sign_and_log_determinant()`, and the logarithms are subtracted, so
+/// `det(A) / det(B)` is never formed, e.g. for a likelihood ratio of large covariance
+/// matrices whose determinants overflow.
+///
+/// Because the input matrices may be overwritten or destroyed, they are consumed.
+///
+/// # Arguments
+///
+/// * `a` - The square matrix of the numerator.
+/// * `b` - The square matrix of the denominator.
+///
+/// # Returns
+///
+/// * `sign` - The sign of `det(A) / det(B)`, `0` if `a` is exactly singular.
+/// * `log_abs_ratio` - `ln|det(A) / det(B)|`.
+///
+/// `None` is returned if either matrix is not square or if `b` is exactly singular.
+pub fn log_determinant_ratio<N>(a: DMatrix<N>, b: DMatrix<N>) -> Option<(i8, N)>
+    where N: Float,
+          DMatrix<N>: LU<N = N>
+{
+    let (sign_a, log_a) = a.lu().ok()?.sign_and_log_determinant();
+    let (sign_b, log_b) = b.lu().ok()?.sign_and_log_determinant();
+    if sign_b == 0 {
+        return None;
+    }
+    if sign_a == 0 {
+        return Some((0, N::neg_infinity()));
+    }
+    Some((sign_a * sign_b, log_a - log_b))
+}
+
+/// An iterator over the solutions for a stream of right-hand sides, returned by
+/// `LuFactorization::solve_iter()`.
+pub struct LuSolveIter<'a, N: 'a, I> {
+    lu: &'a LuFactorization<N>,
+    rhs: I,
+}
+
+/// A type for which the LU factorization can be computed.
+pub trait LU {
+    type N: LapackScalar;
+
+    /// compute the LU factorization with partial pivoting.
+    ///
+    /// An exactly singular matrix is factorized successfully; the error is only reported when
+    /// the factorization is used to solve a system.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `lu` - The factorization.
+    fn lu(self) -> NalgebraLapackResult<LuFactorization<Self::N>>;
+
+    /// compute the LU factorization with partial pivoting, regarding this matrix as the
+    /// transpose `A^T` of the logical matrix `A`.
+    ///
+    /// This is useful when the column-major data at hand is the transpose of the matrix of
+    /// interest, e.g. a row-major `A`. The data is factorized as is, without transposing it,
+    /// and `solve()` and `solve_transpose()` of the factorization then refer to `A`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `lu` - The factorization.
+    fn lu_as_transpose(self) -> NalgebraLapackResult<LuFactorization<Self::N>>;
+
+    /// compute the LU factorization with partial pivoting together with an estimate of the
+    /// reciprocal condition number.
+    ///
+    /// The reciprocal condition number `1 / (||A||_1 ||A^-1||_1)` is estimated with `?gecon`
+    /// from the factorization, at a cost of `O(n^2)`, so one factorization serves both to
+    /// solve systems and to check how reliable their solutions are: about
+    /// `-log10(rcond)` digits of accuracy are lost.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `lu` - The factorization.
+    /// * `rcond` - The estimate of the reciprocal condition number in the 1-norm, zero for an
+    ///   exactly singular matrix. It is real, also for a complex matrix.
+    fn factor_lu_with_condition(self)
+        -> NalgebraLapackResult<(LuFactorization<Self::N>, <Self::N as LapackScalar>::Real)>;
+
+    /// compute the LU factorization with partial pivoting, with the row permutation as a
+    /// vector of indices.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `perm` - The 0-based row permutation of `P A = L U`, as from
+    ///   `LuFactorization::permutation_vector()`.
+    /// * `l` - The unit lower triangular factor.
+    /// * `u` - The upper triangular factor.
+    fn lu_with_permutation_vector(self)
+        -> NalgebraLapackResult<(Vec<usize>, DMatrix<Self::N>, DMatrix<Self::N>)>;
+}
+
+/// A type for which the sign of the determinant can be computed.
+pub trait DeterminantSign {
+    /// compute the sign of the determinant.
+    ///
+    /// The sign is found from the LU factorization as the parity of the row interchanges times
+    /// the signs of the diagonal of `U`. The magnitude of the determinant, which may overflow
+    /// or underflow, is never formed.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `sign` - `1` if the determinant is positive (e.g. a rotation), `-1` if it is negative
+    ///   (e.g. a reflection) and `0` if the matrix is exactly singular. `None` is returned if
+    ///   the matrix is not square or the factorization fails.
+    fn determinant_sign(self) -> Option<i8>;
+}
+
+/// A type for which the determinant can be computed without overflow.
+pub trait ScaledDeterminant {
+    type N;
+
+    /// compute the determinant as a mantissa and a binary exponent, `det = mantissa *
+    /// 2^exponent`.
+    ///
+    /// See `LuFactorization::determinant_scaled()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `mantissa` - The signed mantissa, with `0.5 <= |mantissa| < 1`, or zero if the matrix
+    ///   is exactly singular.
+    /// * `exponent` - The binary exponent.
+    ///
+    /// `None` is returned if the matrix is not square.
+    fn determinant_scaled(self) -> Option<(Self::N, i32)>;
+}
+
+/// A type for which the determinant and the inverse can be computed together.
+pub trait DeterminantAndInverse {
+    type N;
+
+    /// compute both the determinant and the inverse from a single LU factorization.
+    ///
+    /// This is cheaper than factorizing once for each, e.g. for a small transform whose
+    /// inverse is needed along with the sign or scale of its determinant.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `determinant` - The determinant.
+    /// * `inverse` - The inverted matrix.
+    ///
+    /// `None` is returned if the matrix is not square or is exactly singular.
+    fn det_and_inverse(self) -> Option<(Self::N, DMatrix<Self::N>)>;
+}
+
+/// A matrix whose reduced row echelon form can be computed.
+pub trait ReducedRowEchelon {
+    type N;
+
+    /// compute the reduced row echelon form with a tolerance, e.g. for teaching or to find
+    /// the linearly dependent columns of a matrix.
+    ///
+    /// This is Gauss-Jordan elimination with partial pivoting, column by column: the entry of
+    /// largest magnitude at or below the current row is taken as the pivot, unless it is at
+    /// most `tol`, in which case the column is dependent on the previous pivot columns and its
+    /// remaining entries are set to zero. The pivot row is scaled to a unit pivot and the
+    /// column eliminated from all other rows. Partial pivoting is not rank revealing, so the
+    /// result is only as reliable as the choice of `tol`; `max(m, n) eps ||A||_inf` is a
+    /// common one, and the SVD should be preferred to determine the rank itself. This does
+    /// not call LAPACK.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Entries of at most this magnitude are regarded as zero pivots.
+    ///
+    /// # Returns
+    ///
+    /// * `r` - The reduced row echelon form, with exact zeros and ones in the pivot columns
+    ///   and zeros below the last pivot row.
+    /// * `pivots` - The indices of the pivot columns, in increasing order. The other columns
+    ///   are linear combinations of them.
+    fn rref(self, tol: Self::N) -> (DMatrix<Self::N>, Vec<usize>);
+}
+
+impl<N: Float> ReducedRowEchelon for DMatrix<N> {
+    type N = N;
+
+    fn rref(self, tol: N) -> (DMatrix<N>, Vec<usize>) {
+        let mut a = self;
+        let (m, n) = (a.nrows(), a.ncols());
+        let mut pivots = Vec::new();
+        for j in 0..n {
+            let r = pivots.len();
+            if r == m {
+                break;
+            }
+            let p = (r..m).fold(r, |p, i| if a[(i, j)].abs() > a[(p, j)].abs() { i } else { p });
+            if a[(p, j)].abs() <= tol {
+                for i in r..m {
+                    a[(i, j)] = N::zero();
+                }
+                continue;
+            }
+            for k in j..n {
+                let tmp = a[(r, k)];
+                a[(r, k)] = a[(p, k)];
+                a[(p, k)] = tmp;
+            }
+            let pivot = a[(r, j)];
+            for k in j..n {
+                a[(r, k)] = a[(r, k)] / pivot;
+            }
+            for i in (0..m).filter(|&i| i != r) {
+                let factor = a[(i, j)];
+                if factor != N::zero() {
+                    for k in j..n {
+                        a[(i, k)] = a[(i, k)] - factor * a[(r, k)];
+                    }
+                }
+            }
+            a[(r, j)] = N::one();
+            for i in (0..m).filter(|&i| i != r) {
+                a[(i, j)] = N::zero();
+            }
+            pivots.push(j);
+        }
+        (a, pivots)
+    }
+}
+
+macro_rules! lu_impl(
+    ($t: ty, $getrf: path, $getrs: path, $getri: path, $gecon: path, $lacn2: path) => (
+        impl LU for DMatrix<$t> {
+            type N = $t;
+
+            fn lu(self) -> NalgebraLapackResult<LuFactorization<$t>> {
+                let mut a = self;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let lda = ::std::cmp::max(1, n) as i32;
+
+                let mut ipiv: Vec<i32> = vec![0; n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($getrf), n, n, &[]);
+                $getrf(n as i32, n as i32, a.as_mut_vector(), lda, &mut ipiv, &mut info);
+                // A positive `info` means that `U` is exactly singular, which is still a valid
+                // factorization.
+                if info < 0 {
+                    return Err(Error::from(ErrorKind::LapackIllegalArgument(-info)));
+                }
+
+                Ok(LuFactorization { lu: a, ipiv, transposed: false })
+            }
+
+            fn lu_as_transpose(self) -> NalgebraLapackResult<LuFactorization<$t>> {
+                let mut lu = self.lu()?;
+                lu.transposed = true;
+                Ok(lu)
+            }
+
+            fn lu_with_permutation_vector(self)
+                -> NalgebraLapackResult<(Vec<usize>, DMatrix<$t>, DMatrix<$t>)> {
+                let lu = self.lu()?;
+                let (l, u) = lu.unpack();
+                Ok((lu.permutation_vector(), l, u))
+            }
+
+            fn factor_lu_with_condition(self)
+                -> NalgebraLapackResult<(LuFactorization<$t>, $t)> {
+                let norm = b'1';
+
+                // The 1-norm, the largest absolute column sum, of the original matrix.
+                let anorm = (0..self.ncols())
+                    .map(|j| (0..self.nrows()).fold(0.0, |acc, i| acc + Float::abs(self[(i, j)])))
+                    .fold(0.0, |acc: $t, x| acc.max(x));
+                let lu = self.lu()?;
+                let n = lu.lu.nrows();
+                if lu.check_nonsingular().is_err() {
+                    return Ok((lu, 0.0));
+                }
+                if n == 0 {
+                    return Ok((lu, 1.0));
+                }
+
+                let mut rcond = 0.0;
+                let mut work: Vec<$t> = vec![0.0; 4 * n];
+                let mut iwork = vec![0; n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($gecon), n, n, &[norm]);
+                $gecon(norm, n as i32, lu.lu.as_vector(), n as i32, anorm, &mut rcond, &mut work,
+                    &mut iwork, &mut info);
+                check_info!(info);
+
+                Ok((lu, rcond))
+            }
+        }
+
+        impl LuFactorization<$t> {
+            /// return an error if `U` has an exactly zero diagonal element.
+            fn check_nonsingular(&self) -> NalgebraLapackResult<()> {
+                for i in 0..self.lu.nrows() {
+                    if self.lu[(i, i)] == 0.0 {
+                        return Err(Error::from(ErrorKind::LapackFailure((i + 1) as i32)));
+                    }
+                }
+                Ok(())
+            }
+
+            /// compute the sign of the determinant of the factorized matrix.
+            ///
+            /// See `DeterminantSign::determinant_sign()`.
+            pub fn determinant_sign(&self) -> i8 {
+                let mut sign = 1;
+                for i in 0..self.lu.nrows() {
+                    if self.lu[(i, i)] == 0.0 {
+                        return 0;
+                    }
+                    if self.ipiv[i] != (i + 1) as i32 {
+                        sign = -sign;
+                    }
+                    if self.lu[(i, i)] < 0.0 {
+                        sign = -sign;
+                    }
+                }
+                sign
+            }
+
+            /// solve the linear matrix equation `ax=b` using the factorization of `a`.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `ax=b`.
+            pub fn solve(&self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                let trans = if self.transposed { b'T' } else { b'N' };
+                self.solve_with(b, trans)
+            }
+
+            /// solve the linear matrix equation `a^T x=b` using the factorization of `a`.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `a^T x=b`.
+            pub fn solve_transpose(&self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                let trans = if self.transposed { b'N' } else { b'T' };
+                self.solve_with(b, trans)
+            }
+
+            /// solve the linear equation `ax=b` with a single right-hand side using the
+            /// factorization of `a`.
+            ///
+            /// The solution is computed in the storage of `b`, without allocating.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known vector.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `ax=b`.
+            pub fn solve_vector(&self, b: DVector<$t>) -> NalgebraLapackResult<DVector<$t>> {
+                let trans = if self.transposed { b'T' } else { b'N' };
+                let mut b = b;
+                if b.len() != self.lu.nrows() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                self.solve_in_place(&mut b.at, 1, trans)?;
+                Ok(b)
+            }
+
+            /// solve `ax=b` for each right-hand side of a stream, lazily.
+            ///
+            /// The returned iterator solves each vector of `rhs` as it is requested, reusing
+            /// this factorization and the storage of the vector itself, as in
+            /// `solve_vector()`. This avoids assembling all right-hand sides into one matrix
+            /// when they become available one at a time.
+            ///
+            /// # Arguments
+            ///
+            /// * `rhs` - The known vectors.
+            ///
+            /// # Returns
+            ///
+            /// * `solutions` - An iterator over the solution of each right-hand side, in order.
+            pub fn solve_iter<'a, I>(&'a self, rhs: I) -> LuSolveIter<'a, $t, I::IntoIter>
+                where I: IntoIterator<Item = DVector<$t>>
+            {
+                LuSolveIter { lu: self, rhs: rhs.into_iter() }
+            }
+
+            /// solve `(a + u v^T) x = b` for a low-rank update of `a`, reusing the
+            /// factorization of `a`.
+            ///
+            /// This uses the Sherman-Morrison-Woodbury formula
+            /// `(a + u v^T)^-1 = a^-1 - a^-1 u (I + v^T a^-1 u)^-1 v^T a^-1`: one solve with `a`
+            /// for `b` and `u` together, and the solution of a `k x k` system with the
+            /// capacitance matrix `I + v^T a^-1 u`. This costs `O(n^2 (k + nrhs) + k^3)` instead
+            /// of the `O(n^3)` of factorizing the updated matrix. The formula can lose accuracy
+            /// when the capacitance matrix is ill-conditioned, even if `a + u v^T` is not.
+            ///
+            /// # Arguments
+            ///
+            /// * `u` - The `n x k` left factor of the update.
+            /// * `v` - The `n x k` right factor of the update.
+            /// * `b` - The known matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `(a + u v^T) x = b`, or `None` if
+            ///   the dimensions do not match or `a` or the capacitance matrix is exactly
+            ///   singular.
+            pub fn solve_low_rank_update(&self, u: DMatrix<$t>, v: DMatrix<$t>, b: DMatrix<$t>)
+                -> Option<DMatrix<$t>> {
+                let n = self.lu.nrows();
+                let (k, nrhs) = (u.ncols(), b.ncols());
+                if u.nrows() != n || v.nrows() != n || v.ncols() != k || b.nrows() != n {
+                    return None;
+                }
+                let rhs = DMatrix::from_fn(n, nrhs + k, |i, j| {
+                    if j < nrhs { b[(i, j)] } else { u[(i, j - nrhs)] }
+                });
+                let y = self.solve(rhs).ok()?;
+                let x = DMatrix::from_fn(n, nrhs, |i, j| y[(i, j)]);
+                if k == 0 {
+                    return Some(x);
+                }
+                let z = DMatrix::from_fn(n, k, |i, j| y[(i, nrhs + j)]);
+
+                let vt = v.transpose();
+                let mut capacitance = &vt * &z;
+                for i in 0..k {
+                    capacitance[(i, i)] += 1.0;
+                }
+                let w = capacitance.lu().ok()?.solve(&vt * &x).ok()?;
+                Some(x - &z * &w)
+            }
+
+            /// solve with the factorized matrix, transposed by LAPACK according to `trans`.
+            fn solve_with(&self, b: DMatrix<$t>, trans: u8)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let mut b = b;
+                if b.nrows() != self.lu.nrows() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let nrhs = b.ncols();
+                self.solve_in_place(b.as_mut_vector(), nrhs, trans)?;
+                Ok(b)
+            }
+
+            /// overwrite the `n x nrhs` column-major `b` with the solution.
+            fn solve_in_place(&self, b: &mut [$t], nrhs: usize, trans: u8)
+                -> NalgebraLapackResult<()> {
+                let n = self.lu.nrows();
+                self.check_nonsingular()?;
+                let lda = ::std::cmp::max(1, n) as i32;
+                let ldb = ::std::cmp::max(1, n) as i32;
+                let mut info = 0;
+
+                $getrs(trans, n as i32, nrhs as i32, self.lu.as_vector(), lda, &self.ipiv, b,
+                    ldb, &mut info);
+                check_info!(info);
+                Ok(())
+            }
+
+            /// estimate the 1-norm `||a^-1||_1` of the inverse of the logical matrix `a`.
+            ///
+            /// This is the estimator used internally by `?gecon`, Higham's modification of
+            /// Hager's method, driven through the reverse-communication interface of `?lacn2`:
+            /// it only needs a few solves with `a` and `a^T` using this factorization (usually
+            /// four or five), instead of the `O(n^3)` cost of forming the inverse. The estimate
+            /// is a lower bound which is almost always within a factor of three of the true
+            /// norm, and often exact. Multiplied by `||a||_1`, it estimates the 1-norm condition
+            /// number of a matrix whose factorization is at hand.
+            ///
+            /// # Returns
+            ///
+            /// * `estimate` - The estimate of `||a^-1||_1`. It is infinite if `U` is exactly
+            ///   singular, and zero for an empty matrix.
+            pub fn inverse_norm1_estimate(&self) -> NalgebraLapackResult<$t> {
+                let n = self.lu.nrows();
+                if n == 0 {
+                    return Ok(0.0);
+                }
+                if self.check_nonsingular().is_err() {
+                    return Ok(<$t as Float>::infinity());
+                }
+                let (forward, transpose) = if self.transposed {
+                    (b'T', b'N')
+                } else {
+                    (b'N', b'T')
+                };
+
+                let mut v: Vec<$t> = vec![0.0; n];
+                let mut x: Vec<$t> = vec![0.0; n];
+                let mut isgn = vec![0; n];
+                let mut est = [0.0];
+                let mut kase = 0;
+                let mut isave = [0; 3];
+                loop {
+                    $lacn2(n as i32, &mut v, &mut x, &mut isgn, &mut est, &mut kase, &mut isave);
+                    // `kase` asks for `x` to be overwritten by `a^-1 x` (1) or `a^-T x` (2), or
+                    // is zero once the estimate is final.
+                    match kase {
+                        0 => break,
+                        1 => self.solve_in_place(&mut x, 1, forward)?,
+                        _ => self.solve_in_place(&mut x, 1, transpose)?,
+                    }
+                }
+                Ok(est[0])
+            }
+
+            /// compute both the inverse of `a` and the solution of `ax=b`, reusing the single
+            /// factorization for both.
+            ///
+            /// Forming the explicit inverse is discouraged: solving with `solve()` is both
+            /// faster and more accurate. This is meant for formulas that need `a^-1` itself
+            /// alongside a solution.
+            ///
+            /// Because the inverse is computed in place of the factorization, it is consumed.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `ax=b`.
+            /// * `inverse` - The inverted matrix.
+            pub fn solve_and_inverse(self, b: DMatrix<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, DMatrix<$t>)> {
+                let x = self.solve(b)?;
+                Ok((x, self.inverse()?))
+            }
+
+            /// compute the determinant of the logical matrix `a`.
+            ///
+            /// This is the product of the diagonal of `U`, with the sign changed for each row
+            /// interchange. It may overflow or underflow for large matrices, in which case
+            /// `determinant_sign()` still gives the sign.
+            pub fn determinant(&self) -> $t {
+                let mut det = 1.0;
+                for i in 0..self.lu.nrows() {
+                    det *= self.lu[(i, i)];
+                    if self.ipiv[i] != (i + 1) as i32 {
+                        det = -det;
+                    }
+                }
+                det
+            }
+
+            /// compute the inverse of the logical matrix `a`.
+            ///
+            /// Forming the explicit inverse is discouraged: solving with `solve()` is both
+            /// faster and more accurate.
+            ///
+            /// Because the inverse is computed in place of the factorization, it is consumed.
+            ///
+            /// # Returns
+            ///
+            /// * `inverse` - The inverted matrix. An exactly singular matrix is reported as an
+            ///   error.
+            pub fn inverse(self) -> NalgebraLapackResult<DMatrix<$t>> {
+                let mut a = self.lu;
+                let n = a.nrows();
+                let lda = ::std::cmp::max(1, n) as i32;
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $getri(n as i32, a.as_mut_vector(), lda, &self.ipiv, &mut work, lwork,
+                    &mut info);
+                check_info!(info);
+
+                lwork = ::std::cmp::max(1, work[0] as i32);
+                let mut work = vec![0.0; lwork as usize];
+
+                $getri(n as i32, a.as_mut_vector(), lda, &self.ipiv, &mut work, lwork,
+                    &mut info);
+                check_info!(info);
+
+                if self.transposed {
+                    a = a.transpose();
+                }
+                Ok(a)
+            }
+        }
+
+        impl<'a, I> Iterator for LuSolveIter<'a, $t, I>
+            where I: Iterator<Item = DVector<$t>>
+        {
+            type Item = NalgebraLapackResult<DVector<$t>>;
+
+            fn next(&mut self) -> Option<NalgebraLapackResult<DVector<$t>>> {
+                let lu = self.lu;
+                self.rhs.next().map(|b| lu.solve_vector(b))
+            }
+        }
+
+        impl DeterminantSign for DMatrix<$t> {
+            fn determinant_sign(self) -> Option<i8> {
+                Some(self.lu().ok()?.determinant_sign())
+            }
+        }
+
+        impl ScaledDeterminant for DMatrix<$t> {
+            type N = $t;
+
+            fn determinant_scaled(self) -> Option<($t, i32)> {
+                Some(self.lu().ok()?.determinant_scaled())
+            }
+        }
+
+        impl DeterminantAndInverse for DMatrix<$t> {
+            type N = $t;
+
+            fn det_and_inverse(self) -> Option<($t, DMatrix<$t>)> {
+                let lu = self.lu().ok()?;
+                let det = lu.determinant();
+                Some((det, lu.inverse().ok()?))
+            }
+        }
+    );
+);
+
+macro_rules! lu_complex_impl(
+    ($t: ty, $getrf: path, $getrs: path, $getri: path, $gecon: path) => (
+        impl LU for DMatrix<Complex<$t>> {
+            type N = Complex<$t>;
+
+            fn lu(self) -> NalgebraLapackResult<LuFactorization<Complex<$t>>> {
+                let mut a = self;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let lda = ::std::cmp::max(1, n) as i32;
+
+                let mut ipiv: Vec<i32> = vec![0; n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($getrf), n, n, &[]);
+                $getrf(n as i32, n as i32, a.as_mut_vector(), lda, &mut ipiv, &mut info);
+                // A positive `info` means that `U` is exactly singular, which is still a valid
+                // factorization.
+                if info < 0 {
+                    return Err(Error::from(ErrorKind::LapackIllegalArgument(-info)));
+                }
+
+                Ok(LuFactorization { lu: a, ipiv, transposed: false })
+            }
+
+            fn lu_as_transpose(self) -> NalgebraLapackResult<LuFactorization<Complex<$t>>> {
+                let mut lu = self.lu()?;
+                lu.transposed = true;
+                Ok(lu)
+            }
+
+            fn lu_with_permutation_vector(self)
+                -> NalgebraLapackResult<(Vec<usize>, DMatrix<Complex<$t>>, DMatrix<Complex<$t>>)> {
+                let lu = self.lu()?;
+                let (l, u) = lu.unpack();
+                Ok((lu.permutation_vector(), l, u))
+            }
+
+            fn factor_lu_with_condition(self)
+                -> NalgebraLapackResult<(LuFactorization<Complex<$t>>, $t)> {
+                let norm = b'1';
+
+                // The 1-norm, the largest absolute column sum, of the original matrix.
+                let anorm = (0..self.ncols())
+                    .map(|j| (0..self.nrows()).fold(0.0, |acc, i| acc + self[(i, j)].norm()))
+                    .fold(0.0, |acc: $t, x| acc.max(x));
+                let lu = self.lu()?;
+                let n = lu.lu.nrows();
+                if lu.check_nonsingular().is_err() {
+                    return Ok((lu, 0.0));
+                }
+                if n == 0 {
+                    return Ok((lu, 1.0));
+                }
+
+                let mut rcond = 0.0;
+                let mut work = vec![Complex::new(0.0, 0.0); 2 * n];
+                let mut rwork: Vec<$t> = vec![0.0; 2 * n];
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($gecon), n, n, &[norm]);
+                $gecon(norm, n as i32, lu.lu.as_vector(), n as i32, anorm, &mut rcond, &mut work,
+                    &mut rwork, &mut info);
+                check_info!(info);
+
+                Ok((lu, rcond))
+            }
+        }
+
+        impl LuFactorization<Complex<$t>> {
+            /// return an error if `U` has an exactly zero diagonal element.
+            fn check_nonsingular(&self) -> NalgebraLapackResult<()> {
+                for i in 0..self.lu.nrows() {
+                    if self.lu[(i, i)] == Complex::new(0.0, 0.0) {
+                        return Err(Error::from(ErrorKind::LapackFailure((i + 1) as i32)));
+                    }
+                }
+                Ok(())
+            }
+
+            /// solve the linear matrix equation `ax=b` using the factorization of `a`.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `ax=b`.
+            pub fn solve(&self, b: DMatrix<Complex<$t>>)
+                -> NalgebraLapackResult<DMatrix<Complex<$t>>> {
+                let trans = if self.transposed { b'T' } else { b'N' };
+                self.solve_with(b, trans)
+            }
+
+            /// solve the linear matrix equation `a^T x=b` using the factorization of `a`.
+            ///
+            /// This is the plain transpose, not the conjugate transpose.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known matrix.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `a^T x=b`.
+            pub fn solve_transpose(&self, b: DMatrix<Complex<$t>>)
+                -> NalgebraLapackResult<DMatrix<Complex<$t>>> {
+                let trans = if self.transposed { b'N' } else { b'T' };
+                self.solve_with(b, trans)
+            }
+
+            /// solve the linear equation `ax=b` with a single right-hand side using the
+            /// factorization of `a`.
+            ///
+            /// The solution is computed in the storage of `b`, without allocating.
+            ///
+            /// # Arguments
+            ///
+            /// * `b` - The known vector.
+            ///
+            /// # Returns
+            ///
+            /// * `x` - The solution to the linear equation `ax=b`.
+            pub fn solve_vector(&self, b: DVector<Complex<$t>>)
+                -> NalgebraLapackResult<DVector<Complex<$t>>> {
+                let trans = if self.transposed { b'T' } else { b'N' };
+                let mut b = b;
+                if b.len() != self.lu.nrows() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                self.solve_in_place(&mut b.at, 1, trans)?;
+                Ok(b)
+            }
+
+            /// solve with the factorized matrix, transposed by LAPACK according to `trans`.
+            fn solve_with(&self, b: DMatrix<Complex<$t>>, trans: u8)
+                -> NalgebraLapackResult<DMatrix<Complex<$t>>> {
+                let mut b = b;
+                if b.nrows() != self.lu.nrows() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let nrhs = b.ncols();
+                self.solve_in_place(b.as_mut_vector(), nrhs, trans)?;
+                Ok(b)
+            }
+
+            /// overwrite the `n x nrhs` column-major `b` with the solution.
+            fn solve_in_place(&self, b: &mut [Complex<$t>], nrhs: usize, trans: u8)
+                -> NalgebraLapackResult<()> {
+                let n = self.lu.nrows();
+                self.check_nonsingular()?;
+                let lda = ::std::cmp::max(1, n) as i32;
+                let ldb = ::std::cmp::max(1, n) as i32;
+                let mut info = 0;
+
+                $getrs(trans, n as i32, nrhs as i32, self.lu.as_vector(), lda, &self.ipiv, b,
+                    ldb, &mut info);
+                check_info!(info);
+                Ok(())
+            }
+
+            /// compute the complex determinant of the logical matrix `a`.
+            ///
+            /// This is the product of the diagonal of `U`, with the sign changed for each row
+            /// interchange. It may overflow or underflow for large matrices.
+            pub fn determinant(&self) -> Complex<$t> {
+                let mut det = Complex::new(1.0, 0.0);
+                for i in 0..self.lu.nrows() {
+                    det *= self.lu[(i, i)];
+                    if self.ipiv[i] != (i + 1) as i32 {
+                        det = -det;
+                    }
+                }
+                det
+            }
+
+            /// compute the inverse of the logical matrix `a`.
+            ///
+            /// Forming the explicit inverse is discouraged: solving with `solve()` is both
+            /// faster and more accurate.
+            ///
+            /// Because the inverse is computed in place of the factorization, it is consumed.
+            ///
+            /// # Returns
+            ///
+            /// * `inverse` - The inverted matrix. An exactly singular matrix is reported as an
+            ///   error.
+            pub fn inverse(self) -> NalgebraLapackResult<DMatrix<Complex<$t>>> {
+                let mut a = self.lu;
+                let n = a.nrows();
+                let lda = ::std::cmp::max(1, n) as i32;
+
+                let mut work = vec![Complex::new(0.0, 0.0)];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $getri(n as i32, a.as_mut_vector(), lda, &self.ipiv, &mut work, lwork,
+                    &mut info);
+                check_info!(info);
+
+                lwork = ::std::cmp::max(1, work[0].re as i32);
+                let mut work = vec![Complex::new(0.0, 0.0); lwork as usize];
+
+                $getri(n as i32, a.as_mut_vector(), lda, &self.ipiv, &mut work, lwork,
+                    &mut info);
+                check_info!(info);
+
+                if self.transposed {
+                    a = a.transpose();
+                }
+                Ok(a)
+            }
+        }
+
+        impl DeterminantAndInverse for DMatrix<Complex<$t>> {
+            type N = Complex<$t>;
+
+            fn det_and_inverse(self) -> Option<(Complex<$t>, DMatrix<Complex<$t>>)> {
+                let lu = self.lu().ok()?;
+                let det = lu.determinant();
+                Some((det, lu.inverse().ok()?))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+lu_impl!(f32, interface::sgetrf, interface::sgetrs, interface::sgetri, interface::sgecon,
+        interface::slacn2);
+lu_impl!(f64, interface::dgetrf, interface::dgetrs, interface::dgetri, interface::dgecon,
+        interface::dlacn2);
+lu_complex_impl!(f32, interface::cgetrf, interface::cgetrs, interface::cgetri, interface::cgecon);
+lu_complex_impl!(f64, interface::zgetrf, interface::zgetrs, interface::zgetri, interface::zgecon);
@@ -0,0 +1,684 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::{DMatrix, Transpose};
+
+use errors::{Error, ErrorKind};
+use {EigenResult, Eigensystem, Inverse, IterOptions, NalgebraLapackResult, QrFactorize, SVD,
+     real_schur_to_complex};
+
+/// A type for which the matrix sign function can be computed.
+pub trait MatrixSign {
+    type N;
+
+    /// compute the matrix sign function.
+    ///
+    /// For a matrix `A = V diag(lambda) V^-1`, this is `V diag(sign(re(lambda))) V^-1`: the
+    /// matrix with the same invariant subspaces as `A` whose eigenvalues are `1` for the
+    /// eigenvalues of `A` in the right half-plane and `-1` for those in the left half-plane. It
+    /// is computed with the Newton iteration `X_{k+1} = (X_k + X_k^-1) / 2`, starting from `A`.
+    ///
+    /// The function is not defined if `A` has an eigenvalue on the imaginary axis, in which
+    /// case the iteration fails.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `sign` - The matrix sign function of `A`. `None` is returned if `A` is not square, if
+    ///   the iteration does not converge or if an iterate is exactly singular; use
+    ///   `matrix_sign_with()` to tell these apart.
+    fn matrix_sign(self) -> Option<DMatrix<Self::N>>;
+
+    /// compute the matrix sign function with explicit convergence control.
+    ///
+    /// This is `matrix_sign()`, which uses `IterOptions::default()`. The iteration stops when
+    /// the Frobenius norm of the step `X_{k+1} - X_k` is at most `options.tol` times that of
+    /// `X_{k+1}`. Convergence is quadratic, so once the step is below `sqrt(eps)` the iterate
+    /// is accurate to about `eps`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The tolerance and the maximum number of iterations.
+    ///
+    /// # Returns
+    ///
+    /// * `sign` - The matrix sign function of `A`. A `ConvergenceFailure` error is returned if
+    ///   `options.max_iters` is exceeded, and a `LapackFailure` error if an iterate is exactly
+    ///   singular.
+    fn matrix_sign_with(self, options: IterOptions<Self::N>)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// A type for which the inverse of the principal square root can be computed.
+pub trait InverseSqrt {
+    type N;
+
+    /// compute the inverse principal square root `A^(-1/2)` of a diagonalizable matrix.
+    ///
+    /// This is `V diag(lambda^(-1/2)) V^-1` from the eigendecomposition
+    /// `A = V diag(lambda) V^-1`, so unlike `WhiteningTransform::whitening_transform()` the
+    /// matrix need not be symmetric positive-definite. The principal square root is used, with
+    /// its branch cut on the negative real axis: each `lambda^(1/2)` has a positive real part.
+    /// It is not defined for an eigenvalue on the closed negative real axis, including zero.
+    /// For a real matrix, the result is real up to rounding errors.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The matrix with `X X A = I`, or `None` if the eigendecomposition fails, the
+    ///   matrix is (nearly) defective or it has an eigenvalue on the negative real axis or at
+    ///   zero.
+    fn inverse_sqrt(self) -> Option<DMatrix<Complex<Self::N>>>;
+}
+
+/// A type for which the group inverse can be computed.
+pub trait GroupInverse {
+    type N;
+
+    /// compute the group inverse `A^#` of a square matrix of index one.
+    ///
+    /// A matrix has index one if `rank(A) = rank(A^2)`, i.e. its zero eigenvalue, if any, is
+    /// not defective. The group inverse is then the unique `X` with `A X A = A`, `X A X = X`
+    /// and `A X = X A`, which for such matrices is also the Drazin inverse. It is used e.g. for
+    /// the fundamental matrix `(I - P)^#` of a Markov chain with transition matrix `P`. The
+    /// ranks are the numbers of singular values above `n eps s_max`, and `X` is computed as
+    /// `V diag(1/lambda) V^-1` from the eigendecomposition, where the `n - rank(A)` eigenvalues
+    /// of least magnitude are taken as zero and not inverted. For a nonsingular matrix this is
+    /// the inverse.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The group inverse, or `None` if the matrix is not square, its index is not one,
+    ///   a decomposition fails or the matrix is (nearly) defective.
+    fn group_inverse(self) -> Option<DMatrix<Self::N>>;
+}
+
+/// A type for which the polar decomposition can be computed by a Newton iteration.
+pub trait PolarNewton {
+    type N;
+
+    /// compute the polar decomposition `A = U H` of an `m x n` matrix with `m >= n` by the
+    /// scaled Newton iteration.
+    ///
+    /// `U` has orthonormal columns and `H` is symmetric positive-definite. The SVD gives them
+    /// directly as `U = P Q^T` and `H = Q S Q^T` from `A = P S Q^T`, but for a tall matrix a
+    /// full SVD is unnecessarily expensive. Instead, the thin QR factorization `A = Q_1 R` is
+    /// computed first, and the orthogonal polar factor of the small `n x n` `R` with the
+    /// Newton iteration `X_{k+1} = (z_k X_k + X_k^-T / z_k) / 2`, starting from `R`. The
+    /// scaling `z_k = (||X_k^-1||_F / ||X_k||_F)^(1/2)` makes convergence fast also for
+    /// ill-conditioned matrices; it is quadratic close to the solution. Then `U = Q_1 X` and
+    /// `H` is the symmetric part of `U^T A`. This uses `IterOptions::default()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `u` - The `m x n` orthogonal polar factor.
+    /// * `h` - The `n x n` symmetric positive-definite factor.
+    ///
+    /// A `DimensionMismatch` error is returned if `m < n`, a `LapackFailure` error if `A` is
+    /// exactly rank deficient and a `ConvergenceFailure` error if the iteration does not
+    /// converge.
+    fn polar_newton(self) -> NalgebraLapackResult<(DMatrix<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute the polar decomposition by the scaled Newton iteration with explicit
+    /// convergence control.
+    ///
+    /// This is `polar_newton()`. The iteration stops when the Frobenius norm of the step
+    /// `X_{k+1} - X_k` is at most `options.tol` times that of `X_{k+1}`. Since convergence is
+    /// quadratic, the default tolerance of `sqrt(eps)` gives an orthogonal factor accurate to
+    /// about `eps` times the condition number of `A`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The tolerance and the maximum number of iterations.
+    ///
+    /// # Returns
+    ///
+    /// The same as `polar_newton()`.
+    fn polar_newton_with(self, options: IterOptions<Self::N>)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DMatrix<Self::N>)>;
+}
+
+/// A type for which the matrix absolute value can be computed.
+pub trait MatrixAbs {
+    type N;
+
+    /// compute the matrix absolute value `|A| = (A^T A)^(1/2)` of an `m x n` matrix.
+    ///
+    /// With the SVD `A = U S V^T`, this is `V S V^T`, the symmetric positive-semidefinite `n x
+    /// n` factor `H` of the polar decomposition `A = U H`, computed without the iteration of
+    /// `PolarNewton`. It is positive definite if and only if `A` has full column rank, and for
+    /// a symmetric `A` it is `V diag(|lambda|) V^T` from the eigendecomposition. The result is
+    /// exactly symmetric.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `h` - The `n x n` matrix `|A|`, or `None` if the SVD fails.
+    fn matrix_abs(self) -> Option<DMatrix<Self::N>>;
+}
+
+/// A type for which a general function of a matrix can be evaluated.
+pub trait MatrixFunction {
+    type N;
+
+    /// evaluate the matrix function `f(A)` by the Schur-Parlett method.
+    ///
+    /// `A` is reduced to the complex Schur form `A = Z T Z^H`, `f` is applied to the
+    /// eigenvalues on the diagonal of `T`, and the rest of the upper triangular `f(T)` is
+    /// filled in superdiagonal by superdiagonal with the Parlett recurrence, which follows
+    /// from `f(T)` commuting with `T`. The result is `Z f(T) Z^H`. Unlike evaluating `f` on
+    /// an eigendecomposition, this only uses unitary transformations, so it stays accurate
+    /// for non-normal matrices with ill-conditioned eigenvectors.
+    ///
+    /// The recurrence divides by the differences between eigenvalues, so, as proposed by
+    /// Davies and Higham, the Schur form is first reordered to group eigenvalues that are
+    /// closer than `0.1` to each other (transitively) into diagonal blocks. `f` is evaluated on
+    /// each block by a Cauchy integral over a circle around its eigenvalues, which is the
+    /// block's Taylor series summed exactly, and the recurrence is only used between blocks.
+    /// `f` must therefore be analytic on a disk around each cluster, not just at the
+    /// eigenvalues; the radius is twice the spread of the cluster, and at least half its
+    /// distance to the origin or one half, whichever is smaller. When the disk contains a
+    /// singularity or branch cut of `f`, as for `ln` on eigenvalues close to zero or `sqrt`
+    /// on a cluster around the negative real axis, the integral no longer reproduces `f` at
+    /// the eigenvalues, and `None` is returned rather than a wrong result.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The scalar function, which must be analytic around the eigenvalues of `A`.
+    ///
+    /// # Returns
+    ///
+    /// * `f_a` - The matrix `f(A)`. `None` is returned if `A` is not square, if the Schur
+    ///   decomposition fails, if `f` is not analytic on the disk around a cluster, or if the
+    ///   result is not finite.
+    fn matrix_function<F>(self, f: F) -> Option<DMatrix<Complex<Self::N>>>
+        where F: Fn(Complex<Self::N>) -> Complex<Self::N>;
+}
+
+/// The real Schur decomposition behind `MatrixFunction`.
+trait RealSchur {
+    type N;
+
+    /// Compute `A = Z T Z^T` with `?gees`, returning `(T, Z)`.
+    fn real_schur(self) -> NalgebraLapackResult<(DMatrix<Self::N>, DMatrix<Self::N>)>;
+}
+
+/// The distance below which two eigenvalues are put in the same block by the blocked
+/// Schur-Parlett method (Davies and Higham, 2003).
+const PARLETT_BLOCK_DELTA: f64 = 0.1;
+
+/// The number of points of the trapezoidal rule for the Cauchy integral over a block.
+const CAUCHY_POINTS: usize = 64;
+
+/// Return the Givens rotation `(c, s)`, with `c` real, for which `[[c, s], [-conj(s), c]]`
+/// maps `[f, g]` to `[r, 0]`, as `?lartg`.
+fn complex_rotation<N: Float>(f: Complex<N>, g: Complex<N>) -> (N, Complex<N>) {
+    let (abs_f, abs_g) = (f.norm(), g.norm());
+    if abs_g == N::zero() {
+        return (N::one(), Complex::new(N::zero(), N::zero()));
+    }
+    if abs_f == N::zero() {
+        return (N::zero(), g.conj() / abs_g);
+    }
+    let norm = abs_f.hypot(abs_g);
+    (abs_f / norm, f / abs_f * g.conj() / norm)
+}
+
+/// reorder the complex Schur form `A = Z T Z^H` so that eigenvalues closer than
+/// `PARLETT_BLOCK_DELTA`, directly or through a chain of others, are contiguous on the
+/// diagonal of `T`, and return the end of each of the resulting diagonal blocks.
+///
+/// Adjacent diagonal elements are swapped with Givens rotations, as `?trexc` does.
+fn cluster_schur<N: Float>(t: &mut DMatrix<Complex<N>>, z: &mut DMatrix<Complex<N>>)
+    -> Vec<usize> {
+    let n = t.nrows();
+    let delta = N::from(PARLETT_BLOCK_DELTA).unwrap();
+    // Each eigenvalue is labelled with the smallest index in its cluster.
+    let mut cluster: Vec<usize> = (0..n).collect();
+    for i in 0..n {
+        for j in 0..i {
+            if (t[(i, i)] - t[(j, j)]).norm() <= delta && cluster[i] != cluster[j] {
+                let (old, new) = if cluster[i] > cluster[j] {
+                    (cluster[i], cluster[j])
+                } else {
+                    (cluster[j], cluster[i])
+                };
+                for c in cluster.iter_mut() {
+                    if *c == old {
+                        *c = new;
+                    }
+                }
+            }
+        }
+    }
+
+    // Bubble the clusters into place; eigenvalues of the same cluster are never swapped.
+    let mut swapped = true;
+    while swapped {
+        swapped = false;
+        for k in 0..n.saturating_sub(1) {
+            if cluster[k] <= cluster[k + 1] {
+                continue;
+            }
+            let (t11, t22) = (t[(k, k)], t[(k + 1, k + 1)]);
+            let (c, s) = complex_rotation(t[(k, k + 1)], t22 - t11);
+            let c = Complex::new(c, N::zero());
+            for j in (k + 2)..n {
+                let (x, y) = (t[(k, j)], t[(k + 1, j)]);
+                t[(k, j)] = c * x + s * y;
+                t[(k + 1, j)] = c * y - s.conj() * x;
+            }
+            for i in 0..k {
+                let (x, y) = (t[(i, k)], t[(i, k + 1)]);
+                t[(i, k)] = c * x + s.conj() * y;
+                t[(i, k + 1)] = c * y - s * x;
+            }
+            t[(k, k)] = t22;
+            t[(k + 1, k + 1)] = t11;
+            for i in 0..n {
+                let (x, y) = (z[(i, k)], z[(i, k + 1)]);
+                z[(i, k)] = c * x + s.conj() * y;
+                z[(i, k + 1)] = c * y - s * x;
+            }
+            cluster.swap(k, k + 1);
+            swapped = true;
+        }
+    }
+    (1..(n + 1)).filter(|&k| k == n || cluster[k] != cluster[k - 1]).collect()
+}
+
+/// evaluate `f` on the diagonal block `start..end` of the upper triangular `t`.
+///
+/// A single eigenvalue is mapped by `f`. For a cluster, this is the Cauchy integral
+/// `1/(2 pi i) \oint f(z) (z I - T)^-1 dz` over the circle of radius `rho` around the mean
+/// eigenvalue `sigma`, with the trapezoidal rule on `CAUCHY_POINTS` points. This sums the
+/// Taylor series of `f` about `sigma`, with the derivatives from Cauchy's integral formula,
+/// so `f` only needs to be evaluated at points. `rho` is twice the distance from `sigma` to
+/// the farthest eigenvalue of the cluster, and at least `min(|sigma|, 1) / 2` (or `1/2` if
+/// `sigma` is zero), so that the circle stays clear of a singularity of `f` at zero.
+///
+/// The circle may still enclose a singularity or cross a branch cut of `f`, e.g. for `ln` on
+/// a cluster close to zero, which changes the integral. The diagonal of the result is then
+/// compared with `f` at the eigenvalues, and `None` is returned if they differ by more than
+/// `sqrt(eps)` times the largest `|f|` on the circle.
+fn block_function<N, F>(t: &DMatrix<Complex<N>>, start: usize, end: usize, f: &F)
+    -> Option<DMatrix<Complex<N>>>
+    where N: Float,
+          F: Fn(Complex<N>) -> Complex<N>
+{
+    let b = end - start;
+    let zero = Complex::new(N::zero(), N::zero());
+    if b == 1 {
+        return Some(DMatrix::from_element(1, 1, f(t[(start, start)])));
+    }
+    let count = N::from(b).unwrap();
+    let sigma = (start..end).fold(zero, |acc, i| acc + t[(i, i)]) / count;
+    let spread = (start..end).fold(N::zero(), |acc, i| acc.max((t[(i, i)] - sigma).norm()));
+    let half = N::from(0.5).unwrap();
+    let floor = if sigma.norm() == N::zero() { half } else { half * sigma.norm().min(N::one()) };
+    let rho = (spread + spread).max(floor);
+
+    let points = N::from(CAUCHY_POINTS).unwrap();
+    let mut result = DMatrix::from_element(b, b, zero);
+    let mut f_max = N::zero();
+    for k in 0..CAUCHY_POINTS {
+        let theta = N::from(2.0 * ::std::f64::consts::PI * k as f64 / CAUCHY_POINTS as f64)
+            .unwrap();
+        let offset = Complex::new(rho * theta.cos(), rho * theta.sin());
+        let point = sigma + offset;
+        let f_point = f(point);
+        f_max = f_max.max(f_point.norm());
+        let weight = f_point * offset / points;
+        // `(z I - T)^-1` by back substitution, column by column.
+        let mut r = DMatrix::from_element(b, b, zero);
+        for j in 0..b {
+            for i in (0..(j + 1)).rev() {
+                let mut x = if i == j { Complex::new(N::one(), N::zero()) } else { zero };
+                for l in (i + 1)..(j + 1) {
+                    x = x + t[(start + i, start + l)] * r[(l, j)];
+                }
+                r[(i, j)] = x / (point - t[(start + i, start + i)]);
+            }
+        }
+        for j in 0..b {
+            for i in 0..(j + 1) {
+                result[(i, j)] = result[(i, j)] + weight * r[(i, j)];
+            }
+        }
+    }
+    let tol = N::epsilon().sqrt() * f_max;
+    if (0..b).all(|i| (result[(i, i)] - f(t[(start + i, start + i)])).norm() <= tol) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// evaluate `f` on the upper triangular `t` whose diagonal blocks end at `blocks`, with the
+/// block Parlett recurrence, or `None` if the result is not finite.
+///
+/// The diagonal blocks are evaluated by `block_function()`, and `None` is also returned if
+/// that fails. The other elements follow from `f(T)` commuting with `T`: element `(i, j)`
+/// with `i` and `j` in different blocks is found superdiagonal by superdiagonal from the
+/// scalar recurrence, which divides by `t_ii - t_jj`, at least `PARLETT_BLOCK_DELTA` in
+/// magnitude.
+fn parlett<N, F>(t: &DMatrix<Complex<N>>, blocks: &[usize], f: F) -> Option<DMatrix<Complex<N>>>
+    where N: Float,
+          F: Fn(Complex<N>) -> Complex<N>
+{
+    let n = t.nrows();
+    let zero = Complex::new(N::zero(), N::zero());
+    let mut block_of = vec![0; n];
+    let mut ft = DMatrix::from_element(n, n, zero);
+    let mut start = 0;
+    for (index, &end) in blocks.iter().enumerate() {
+        let fb = block_function(t, start, end, &f)?;
+        for j in start..end {
+            block_of[j] = index;
+            for i in start..(j + 1) {
+                ft[(i, j)] = fb[(i - start, j - start)];
+            }
+        }
+        start = end;
+    }
+    for d in 1..n {
+        for i in 0..(n - d) {
+            let j = i + d;
+            if block_of[i] == block_of[j] {
+                continue;
+            }
+            let mut s = t[(i, j)] * (ft[(j, j)] - ft[(i, i)]);
+            for k in (i + 1)..j {
+                s = s - ft[(i, k)] * t[(k, j)] + t[(i, k)] * ft[(k, j)];
+            }
+            ft[(i, j)] = s / (t[(j, j)] - t[(i, i)]);
+        }
+    }
+    if ft.as_vector().iter().all(|x| x.re.is_finite() && x.im.is_finite()) {
+        Some(ft)
+    } else {
+        None
+    }
+}
+
+impl<N> MatrixFunction for DMatrix<N>
+    where N: Float,
+          DMatrix<N>: RealSchur<N = N>
+{
+    type N = N;
+
+    fn matrix_function<F>(self, f: F) -> Option<DMatrix<Complex<N>>>
+        where F: Fn(Complex<N>) -> Complex<N>
+    {
+        let n = self.nrows();
+        if self.ncols() != n {
+            return None;
+        }
+        let (t, z) = match self.real_schur() {
+            Ok(tz) => tz,
+            Err(_) => return None,
+        };
+        let (mut t, mut z) = match real_schur_to_complex(t, z) {
+            Ok(tz) => tz,
+            Err(_) => return None,
+        };
+        let blocks = cluster_schur(&mut t, &mut z);
+        let ft = parlett(&t, &blocks, f)?;
+
+        let zero = Complex::new(N::zero(), N::zero());
+        let zf = DMatrix::from_fn(n, n, |i, j| {
+            (0..n).fold(zero, |acc, k| acc + z[(i, k)] * ft[(k, j)])
+        });
+        Some(DMatrix::from_fn(n, n, |i, j| {
+            (0..n).fold(zero, |acc, k| acc + zf[(i, k)] * z[(j, k)].conj())
+        }))
+    }
+}
+
+fn frobenius_norm<N: Float>(m: &DMatrix<N>) -> N {
+    m.as_vector().iter().fold(N::zero(), |acc, x| acc + *x * *x).sqrt()
+}
+
+macro_rules! matrix_sign_impl(
+    ($t: ty) => (
+        impl MatrixSign for DMatrix<$t> {
+            type N = $t;
+
+            fn matrix_sign(self) -> Option<DMatrix<$t>> {
+                self.matrix_sign_with(IterOptions::default()).ok()
+            }
+
+            fn matrix_sign_with(self, options: IterOptions<$t>)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let tol = options.tol;
+
+                // Without scaling, an eigenvalue of magnitude `x` takes about `log2(x)`
+                // iterations before convergence becomes quadratic.
+                let mut x = self;
+                for _ in 0..options.max_iters {
+                    let x_inv = x.clone().inv()?;
+                    let next = DMatrix::from_fn(n, n, |i, j| 0.5 * (x[(i, j)] + x_inv[(i, j)]));
+                    let step = frobenius_norm(&DMatrix::from_fn(n, n, |i, j| {
+                        next[(i, j)] - x[(i, j)]
+                    }));
+                    let converged = step <= tol * frobenius_norm(&next);
+                    x = next;
+                    if converged {
+                        return Ok(x);
+                    }
+                }
+                Err(Error::from(ErrorKind::ConvergenceFailure))
+            }
+        }
+    );
+);
+
+matrix_sign_impl!(f32);
+matrix_sign_impl!(f64);
+
+macro_rules! polar_newton_impl(
+    ($t: ty) => (
+        impl PolarNewton for DMatrix<$t> {
+            type N = $t;
+
+            fn polar_newton(self) -> NalgebraLapackResult<(DMatrix<$t>, DMatrix<$t>)> {
+                self.polar_newton_with(IterOptions::default())
+            }
+
+            fn polar_newton_with(self, options: IterOptions<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, DMatrix<$t>)> {
+                let (m, n) = (self.nrows(), self.ncols());
+                if m < n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let a = self.clone();
+                let qr = self.qr_compact()?;
+                let q = qr.q()?;
+
+                let mut x = qr.r();
+                let mut converged = n == 0;
+                for _ in 0..options.max_iters {
+                    if converged {
+                        break;
+                    }
+                    let x_inv = x.clone().inv()?;
+                    let z = (frobenius_norm(&x_inv) / frobenius_norm(&x)).sqrt();
+                    let next = DMatrix::from_fn(n, n, |i, j| {
+                        0.5 * (z * x[(i, j)] + x_inv[(j, i)] / z)
+                    });
+                    let step = frobenius_norm(&DMatrix::from_fn(n, n, |i, j| {
+                        next[(i, j)] - x[(i, j)]
+                    }));
+                    converged = step <= options.tol * frobenius_norm(&next);
+                    x = next;
+                }
+                if !converged {
+                    return Err(Error::from(ErrorKind::ConvergenceFailure));
+                }
+
+                let u = &q * &x;
+                let uta = &u.transpose() * &a;
+                let h = DMatrix::from_fn(n, n, |i, j| 0.5 * (uta[(i, j)] + uta[(j, i)]));
+                Ok((u, h))
+            }
+        }
+    );
+);
+
+polar_newton_impl!(f32);
+polar_newton_impl!(f64);
+
+macro_rules! matrix_abs_impl(
+    ($t: ty) => (
+        impl MatrixAbs for DMatrix<$t> {
+            type N = $t;
+
+            fn matrix_abs(self) -> Option<DMatrix<$t>> {
+                let n = self.ncols();
+                let (_, s, vt) = self.svd().ok()?;
+                let mut h = DMatrix::new_zeros(n, n);
+                for j in 0..n {
+                    for i in j..n {
+                        let sum = (0..s.len())
+                            .fold(0.0, |acc, k| acc + vt[(k, i)] * s[k] * vt[(k, j)]);
+                        h[(i, j)] = sum;
+                        h[(j, i)] = sum;
+                    }
+                }
+                Some(h)
+            }
+        }
+    );
+);
+
+matrix_abs_impl!(f32);
+matrix_abs_impl!(f64);
+
+macro_rules! inverse_sqrt_impl(
+    ($t: ty) => (
+        impl InverseSqrt for DMatrix<$t> {
+            type N = $t;
+
+            fn inverse_sqrt(self) -> Option<DMatrix<Complex<$t>>> {
+                let eigen = EigenResult::from(self.eigensystem().ok()?);
+                let on_branch_cut = eigen.eigen_values.at.iter()
+                    .any(|lambda| lambda.im == 0.0 && lambda.re <= 0.0);
+                if on_branch_cut {
+                    return None;
+                }
+                let one = Complex::new(1.0, 0.0);
+                eigen.map_eigenvalues(|lambda| one / lambda.sqrt())
+            }
+        }
+    );
+);
+
+inverse_sqrt_impl!(f32);
+inverse_sqrt_impl!(f64);
+
+macro_rules! group_inverse_impl(
+    ($t: ty) => (
+        impl GroupInverse for DMatrix<$t> {
+            type N = $t;
+
+            fn group_inverse(self) -> Option<DMatrix<$t>> {
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return None;
+                }
+                if n == 0 {
+                    return Some(self);
+                }
+                let numerical_rank = |a: DMatrix<$t>| -> Option<usize> {
+                    let (_, s, _) = a.svd().ok()?;
+                    let tol = n as $t * <$t as Float>::epsilon() * s[0];
+                    Some(s.at.iter().filter(|x| **x > tol).count())
+                };
+                let rank = numerical_rank(self.clone())?;
+                if numerical_rank(&self * &self)? != rank {
+                    return None;
+                }
+
+                let eigen = EigenResult::from(self.eigensystem().ok()?);
+                let mut magnitudes: Vec<$t> = eigen.eigen_values.at.iter().map(|x| x.norm())
+                    .collect();
+                magnitudes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+                let zero_tol = if rank < n { magnitudes[n - rank - 1] } else { -1.0 };
+                let zero = Complex::new(0.0, 0.0);
+                let one = Complex::new(1.0, 0.0);
+                let x = eigen.map_eigenvalues(|lambda| {
+                    if lambda.norm() <= zero_tol { zero } else { one / lambda }
+                })?;
+                Some(DMatrix::from_fn(n, n, |i, j| x[(i, j)].re))
+            }
+        }
+    );
+);
+
+group_inverse_impl!(f32);
+group_inverse_impl!(f64);
+
+macro_rules! real_schur_impl(
+    ($t: ty, $gees: path) => (
+        impl RealSchur for DMatrix<$t> {
+            type N = $t;
+
+            fn real_schur(self) -> NalgebraLapackResult<(DMatrix<$t>, DMatrix<$t>)> {
+                let jobvs = b'V';
+                let sort = b'N';
+                let mut a = self;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok((a, DMatrix::new_zeros(0, 0)));
+                }
+                let lda = n as i32;
+                let ldvs = n as i32;
+                let mut sdim = 0;
+                let mut wr = vec![0.0; n];
+                let mut wi = vec![0.0; n];
+                let mut vs = DMatrix::new_zeros(n, n);
+                let mut bwork = vec![0; n];
+                let mut info = 0;
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                $gees(jobvs, sort, None, n as i32, a.as_mut_vector(), lda, &mut sdim, &mut wr,
+                    &mut wi, vs.as_mut_vector(), ldvs, &mut work, lwork, &mut bwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+                ::diagnostics::record(stringify!($gees), n, n, &[jobvs, sort]);
+                $gees(jobvs, sort, None, n as i32, a.as_mut_vector(), lda, &mut sdim, &mut wr,
+                    &mut wi, vs.as_mut_vector(), ldvs, &mut work, lwork, &mut bwork, &mut info);
+                check_info!(info);
+
+                Ok((a, vs))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+real_schur_impl!(f32, interface::sgees);
+real_schur_impl!(f64, interface::dgees);
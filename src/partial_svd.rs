@@ -0,0 +1,94 @@
+use num::Float;
+
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector, Transpose};
+
+use errors::{Error, ErrorKind};
+use {LapackScalar, NalgebraLapackResult, Orthonormalize, SVD, SvdResult};
+
+/// A type for which the largest singular values and vectors can be computed without a full
+/// singular value decomposition.
+pub trait PartialSvd {
+    type N: LapackScalar;
+
+    /// compute the `k` largest singular values and their singular vectors approximately.
+    ///
+    /// This uses block subspace iteration: a block of a few more than `k` vectors is
+    /// alternately multiplied by `A` and `A^T` and orthonormalized, and the singular values
+    /// are estimated from the projection of `A` onto the block. Each iteration costs
+    /// `O(m n k)`, rather than the `O(m n min(m, n))` of a full SVD, so this is much faster
+    /// when `k` is small. The convergence rate depends on the gap between the `k`-th and the
+    /// following singular values; the result is approximate to the requested tolerance.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The number of singular values to compute.
+    /// * `tol` - The iteration stops when no estimated singular value changes by more than
+    ///   `tol` times the largest one between two iterations.
+    /// * `max_iters` - The maximum number of iterations. A `ConvergenceFailure` error is
+    ///   returned if the tolerance is not reached by then.
+    ///
+    /// # Returns
+    ///
+    /// * `svd` - The decomposition with `u` of size `m x k`, `s` of size `k` and `vt` of size
+    ///   `k x n`. Fewer than `k` singular values are returned if `min(m, n)` or the rank of the
+    ///   matrix is smaller than `k`.
+    fn partial_svd(self, k: usize, tol: <Self::N as LapackScalar>::Real, max_iters: usize)
+        -> NalgebraLapackResult<SvdResult<Self::N>>;
+}
+
+/// The number of extra vectors carried in the block to speed up convergence of the last
+/// requested singular values.
+const OVERSAMPLING: usize = 5;
+
+/// A deterministic, pseudo-random `n x p` starting block with entries in `[-0.5, 0.5)`.
+fn starting_block<N: Cast<f64>>(n: usize, p: usize) -> DMatrix<N> {
+    let mut state: u64 = 0x853c49e6748fea9b;
+    DMatrix::from_fn(n, p, |_, _| {
+        // A linear congruential generator (Knuth's MMIX constants).
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        <N as Cast<f64>>::from((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5)
+    })
+}
+
+impl<N> PartialSvd for DMatrix<N>
+    where N: BaseFloat + LapackScalar<Real = N>,
+          DMatrix<N>: Orthonormalize<N = N> + SVD<M = N, V = N>
+{
+    type N = N;
+
+    fn partial_svd(self, k: usize, tol: N, max_iters: usize)
+        -> NalgebraLapackResult<SvdResult<N>> {
+        let a = self;
+        let (m, n) = (a.nrows(), a.ncols());
+        let min_mn = if m <= n { m } else { n };
+        let k = if k <= min_mn { k } else { min_mn };
+        let p = if k + OVERSAMPLING <= min_mn { k + OVERSAMPLING } else { min_mn };
+        let at = a.transpose();
+
+        let mut v = starting_block::<N>(n, p).orthonormalize()?;
+        let mut previous: Vec<N> = vec![];
+        for _ in 0..max_iters {
+            let u = (&a * &v).orthonormalize()?;
+            v = (&at * &u).orthonormalize()?;
+
+            // The projection `u^T A v` has the singular value estimates.
+            let b = &(&u.transpose() * &a) * &v;
+            let (ub, s, vbt) = b.svd()?;
+            let r = if k <= s.len() { k } else { s.len() };
+
+            let converged = r == previous.len() &&
+                            (0..r).all(|i| Float::abs(s[i] - previous[i]) <= tol * s[0]);
+            if converged || r == 0 {
+                let u_k = &u * &DMatrix::from_fn(ub.nrows(), r, |i, j| ub[(i, j)]);
+                let vt_k = &DMatrix::from_fn(r, vbt.ncols(), |i, j| vbt[(i, j)]) * &v.transpose();
+                return Ok(SvdResult {
+                    u: Some(u_k),
+                    s: DVector { at: s.at[..r].to_vec() },
+                    vt: Some(vt_k),
+                });
+            }
+            previous = s.at[..r].to_vec();
+        }
+        Err(Error::from(ErrorKind::ConvergenceFailure))
+    }
+}
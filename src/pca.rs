@@ -0,0 +1,99 @@
+use nalgebra::{BaseFloat, Cast, DVector};
+
+/// select the number of principal components with Minka's maximum likelihood criterion.
+///
+/// Probabilistic PCA models the data as a `k`-dimensional signal plus isotropic noise. Minka
+/// (2000), "Automatic choice of dimensionality for PCA", gives a Laplace approximation of the
+/// evidence `p(data | k)` which only depends on the eigenvalues `lambda_i = s_i^2 / n_samples`
+/// of the sample covariance, and this returns the `k` maximizing it, as scikit-learn does for
+/// `n_components='mle'`. The eigenvalues below `k` are the signal; those from `k` on are
+/// replaced by their mean, the noise variance, which is bounded below by `eps lambda_0`.
+///
+/// # Arguments
+///
+/// * `singular_values` - The `d` singular values of the centered `n_samples x d` data matrix,
+///   in descending order, e.g. from `SVD::svd()`. With fewer samples than features, the
+///   spectrum is padded with zeros up to `d`.
+/// * `n_samples` - The number of samples (rows of the data matrix).
+///
+/// # Returns
+///
+/// * `k` - The selected dimension, between 1 and `d - 1`, or `d` if `d < 2` and there is
+///   nothing to select.
+pub fn pca_dimension_mle<N>(singular_values: &DVector<N>, n_samples: usize) -> usize
+    where N: BaseFloat + Cast<f64>
+{
+    let d = singular_values.len();
+    if d < 2 {
+        return d;
+    }
+    let n = <N as Cast<f64>>::from(n_samples as f64);
+    let spectrum: Vec<N> = singular_values.at.iter().map(|s| *s * *s / n).collect();
+
+    let mut best = (1, N::neg_infinity());
+    for k in 1..d {
+        let evidence = log_evidence(&spectrum, k, n);
+        if evidence > best.1 {
+            best = (k, evidence);
+        }
+    }
+    best.0
+}
+
+/// The approximate log-evidence of Minka's criterion for the dimension `k`, up to a constant.
+fn log_evidence<N>(spectrum: &[N], k: usize, n: N) -> N
+    where N: BaseFloat + Cast<f64>
+{
+    let d = spectrum.len();
+    let from = |x: f64| <N as Cast<f64>>::from(x);
+    let two = from(2.0);
+    let pi = from(::std::f64::consts::PI);
+
+    // The log of the prior probability of the subspace, a product of Stiefel volumes.
+    let mut pu = -from(k as f64) * two.ln();
+    for i in 1..(k + 1) {
+        let half = from((d - i + 1) as f64) / two;
+        pu = pu + ln_gamma_half(d - i + 1) - pi.ln() * half;
+    }
+    let pl = -spectrum[..k].iter().fold(N::zero(), |acc, x| acc + x.ln()) * n / two;
+    let noise = spectrum[k..].iter().fold(N::zero(), |acc, x| acc + *x) /
+                from((d - k) as f64);
+    let threshold = N::epsilon() * spectrum[0];
+    let v = if noise > threshold { noise } else { threshold };
+    let pv = -v.ln() * n * from((d - k) as f64) / two;
+    let m = from((d * k) as f64) - from((k * (k + 1)) as f64) / two;
+    let pp = (two * pi).ln() * (m + from(k as f64)) / two;
+
+    // The log-determinant of the Hessian of the Laplace approximation.
+    let mut pa = N::zero();
+    for i in 0..k {
+        if spectrum[i] < threshold {
+            break;
+        }
+        for j in (i + 1)..d {
+            let lambda_j = if j < k { spectrum[j] } else { v };
+            let gap = (spectrum[i] - spectrum[j]) * (lambda_j.recip() - spectrum[i].recip());
+            pa = pa + gap.ln() + n.ln();
+        }
+    }
+    pu + pl + pv + pp - pa / two - from(k as f64) * n.ln() / two
+}
+
+/// `ln Gamma(j / 2)` for a positive integer `j`, from `Gamma(1) = 1`, `Gamma(1/2) = sqrt(pi)`
+/// and `Gamma(x + 1) = x Gamma(x)`.
+fn ln_gamma_half<N>(j: usize) -> N
+    where N: BaseFloat + Cast<f64>
+{
+    let from = |x: f64| <N as Cast<f64>>::from(x);
+    let (mut x, mut acc) = if j % 2 == 0 {
+        (from(1.0), N::zero())
+    } else {
+        (from(0.5), from(::std::f64::consts::PI).sqrt().ln())
+    };
+    let target = from(j as f64) / from(2.0);
+    while x < target {
+        acc += x.ln();
+        x += N::one();
+    }
+    acc
+}
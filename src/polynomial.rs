@@ -0,0 +1,68 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::DMatrix;
+
+use {Eigensystem, NalgebraLapackResult};
+
+/// find the roots of a polynomial.
+///
+/// The roots of a polynomial of degree `n >= 3` are computed as the eigenvalues of its `n x n`
+/// companion matrix, whose first row is `-c_1 / c_0, ..., -c_n / c_0` and whose subdiagonal
+/// is all ones, as done by MATLAB's and NumPy's `roots`. Linear and quadratic polynomials are
+/// solved directly, the latter with the numerically stable form of the quadratic formula.
+///
+/// # Arguments
+///
+/// * `coeffs` - The coefficients `c_0, ..., c_n` of `c_0 x^n + ... + c_{n-1} x + c_n`, from
+///   the highest degree down. Leading zeros are ignored.
+///
+/// # Returns
+///
+/// * `roots` - The roots, repeated according to their multiplicity, in no particular order.
+///   A constant or zero polynomial has no roots.
+pub fn polynomial_roots<N>(coeffs: &[N]) -> NalgebraLapackResult<Vec<Complex<N>>>
+    where N: Float,
+          DMatrix<N>: Eigensystem<N = N>
+{
+    let zero = N::zero();
+    let two = N::one() + N::one();
+    let start = coeffs.iter().position(|c| *c != zero).unwrap_or(coeffs.len());
+    let c = &coeffs[start..];
+    let degree = if c.is_empty() { 0 } else { c.len() - 1 };
+
+    match degree {
+        0 => Ok(vec![]),
+        1 => Ok(vec![Complex::new(-c[1] / c[0], zero)]),
+        2 => {
+            let (a, b, c) = (c[0], c[1], c[2]);
+            let disc = b * b - two * two * a * c;
+            if disc < zero {
+                let re = -b / (two * a);
+                let im = (-disc).sqrt() / (two * a);
+                Ok(vec![Complex::new(re, im.abs()), Complex::new(re, -im.abs())])
+            } else {
+                // Avoids the cancellation in `-b + sqrt(disc)`.
+                let q = -(b + b.signum() * disc.sqrt()) / two;
+                if q == zero {
+                    Ok(vec![Complex::new(zero, zero), Complex::new(zero, zero)])
+                } else {
+                    Ok(vec![Complex::new(q / a, zero), Complex::new(c / q, zero)])
+                }
+            }
+        }
+        n => {
+            let companion = DMatrix::from_fn(n, n, |i, j| {
+                if i == 0 {
+                    -c[j + 1] / c[0]
+                } else if i == j + 1 {
+                    N::one()
+                } else {
+                    zero
+                }
+            });
+            let (values, _) = companion.eigensystem()?;
+            Ok(values.at)
+        }
+    }
+}
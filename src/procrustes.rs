@@ -0,0 +1,157 @@
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector, Transpose};
+
+use errors::{Error, ErrorKind};
+use {DeterminantSign, NalgebraLapackResult, SVD};
+
+/// solve the orthogonal Procrustes problem.
+///
+/// This finds the orthogonal `R` minimizing `||A R - B||_F`, e.g. the rotation (or
+/// reflection) that best maps a set of points onto another. With the SVD `A^T B = U S V^T`,
+/// the solution is `R = U V^T`.
+///
+/// # Arguments
+///
+/// * `a` - The `m x n` matrix to be transformed, e.g. `m` points in `n` dimensions as rows.
+/// * `b` - The `m x n` target matrix.
+///
+/// # Returns
+///
+/// * `r` - The `n x n` orthogonal matrix.
+pub fn orthogonal_procrustes<N>(a: DMatrix<N>, b: DMatrix<N>) -> NalgebraLapackResult<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: SVD<M = N, V = N>
+{
+    if a.nrows() != b.nrows() || a.ncols() != b.ncols() {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let (u, _, vt) = (&a.transpose() * &b).svd()?;
+    Ok(&u * &vt)
+}
+
+/// find the rigid motion that best aligns one point set with another (the Kabsch algorithm).
+///
+/// This finds the rotation `R` and translation `t` minimizing `sum_i ||R p_i + t - q_i||^2`.
+/// Both point sets are centered on their centroids, and `R` is found from the SVD
+/// `H = U S V^T` of the cross-covariance `H = P_c^T Q_c` as `R = V D U^T`. Here `D` is the
+/// identity, except that its last element is `-1` when `det(V U^T) < 0`, so that `R` is a
+/// proper rotation rather than a reflection. Then `t = q_mean - R p_mean`.
+///
+/// # Arguments
+///
+/// * `p` - The `m x d` points to be moved, one point per row.
+/// * `q` - The `m x d` target points, corresponding row by row to `p`.
+///
+/// # Returns
+///
+/// * `r` - The `d x d` rotation matrix, with determinant 1.
+/// * `t` - The translation, of length `d`.
+pub fn kabsch<N>(p: DMatrix<N>, q: DMatrix<N>) -> NalgebraLapackResult<(DMatrix<N>, DVector<N>)>
+    where N: BaseFloat,
+          DMatrix<N>: SVD<M = N, V = N> + DeterminantSign
+{
+    let (m, d) = (p.nrows(), p.ncols());
+    if q.nrows() != m || q.ncols() != d {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let count = <N as Cast<f64>>::from(m as f64);
+    let centroid = |x: &DMatrix<N>| -> Vec<N> {
+        (0..d).map(|j| (0..m).fold(N::zero(), |acc, i| acc + x[(i, j)]) / count).collect()
+    };
+    let (p_mean, q_mean) = (centroid(&p), centroid(&q));
+    let p_centered = DMatrix::from_fn(m, d, |i, j| p[(i, j)] - p_mean[j]);
+    let q_centered = DMatrix::from_fn(m, d, |i, j| q[(i, j)] - q_mean[j]);
+
+    let (u, _, vt) = (&p_centered.transpose() * &q_centered).svd()?;
+    let v = vt.transpose();
+    let ut = u.transpose();
+    let mut r = &v * &ut;
+    if d > 0 && r.clone().determinant_sign() == Some(-1) {
+        // Flip the singular vector of the smallest singular value.
+        let mut v_flipped = v;
+        for i in 0..d {
+            v_flipped[(i, d - 1)] = -v_flipped[(i, d - 1)];
+        }
+        r = &v_flipped * &ut;
+    }
+
+    let t = (0..d)
+        .map(|i| q_mean[i] - (0..d).fold(N::zero(), |acc, j| acc + r[(i, j)] * p_mean[j]))
+        .collect();
+    Ok((r, DVector { at: t }))
+}
+
+/// find the proper rotation nearest to a square matrix.
+///
+/// With the SVD `A = U S V^T`, the orthogonal polar factor `U V^T` is the orthogonal matrix
+/// nearest to `A` in the Frobenius norm, but it is a reflection when `det(A) < 0`. In that case
+/// the last column of `U`, of the smallest singular value, is negated, which gives the nearest
+/// matrix with determinant 1, e.g. to clean up a noisy rotation estimate in 3D registration.
+///
+/// # Arguments
+///
+/// * `a` - The `n x n` matrix.
+///
+/// # Returns
+///
+/// * `r` - The `n x n` rotation matrix, with determinant 1, or `None` if `a` is not square or
+///   the SVD fails.
+pub fn nearest_rotation<N>(a: DMatrix<N>) -> Option<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: SVD<M = N, V = N> + DeterminantSign
+{
+    let n = a.nrows();
+    if a.ncols() != n {
+        return None;
+    }
+    let (u, _, vt) = a.svd().ok()?;
+    let mut r = &u * &vt;
+    if n > 0 && r.clone().determinant_sign()? < 0 {
+        let mut u_flipped = u;
+        for i in 0..n {
+            u_flipped[(i, n - 1)] = -u_flipped[(i, n - 1)];
+        }
+        r = &u_flipped * &vt;
+    }
+    Some(r)
+}
+
+/// compute the rotation of an axis-angle vector, the exponential of its cross-product matrix.
+///
+/// The vector `w` is the rotation axis scaled by the angle `theta = ||w||`. Its cross-product
+/// matrix `[w]_x`, with `[w]_x v = w x v`, is skew-symmetric, and `exp([w]_x)` is the rotation.
+/// The exponential is evaluated with its closed form, the Rodrigues formula
+/// `I + (sin(theta) / theta) [w]_x + ((1 - cos(theta)) / theta^2) [w]_x^2`, whose coefficients
+/// are replaced by their Taylor series for small angles, so that it stays accurate as `w`
+/// tends to zero, where the general `MatrixFunction::matrix_function()` can not be used.
+///
+/// # Arguments
+///
+/// * `w` - The axis-angle vector, of length 3.
+///
+/// # Returns
+///
+/// * `r` - The 3x3 rotation matrix. A `DimensionMismatch` error is returned if `w` does not
+///   have length 3.
+pub fn rotation_from_skew<N: BaseFloat>(w: DVector<N>) -> NalgebraLapackResult<DMatrix<N>> {
+    if w.len() != 3 {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let k = DMatrix::from_row_vector(3, 3,
+        &[N::zero(), -w[2], w[1],
+          w[2], N::zero(), -w[0],
+          -w[1], w[0], N::zero()]);
+    let k2 = &k * &k;
+    let theta2 = w.at.iter().fold(N::zero(), |acc, x| acc + *x * *x);
+    let theta = theta2.sqrt();
+    let cast = |x: f64| <N as Cast<f64>>::from(x);
+    // Below `eps^(1/4)`, the next terms of the series are smaller than `eps`.
+    let (a, b) = if theta < N::epsilon().sqrt().sqrt() {
+        (N::one() - theta2 / cast(6.0), cast(0.5) - theta2 / cast(24.0))
+    } else {
+        (theta.sin() / theta, (N::one() - theta.cos()) / theta2)
+    };
+    Ok(DMatrix::from_fn(3, 3, |i, j| {
+        let identity = if i == j { N::one() } else { N::zero() };
+        identity + a * k[(i, j)] + b * k2[(i, j)]
+    }))
+}
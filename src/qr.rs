@@ -0,0 +1,522 @@
+use num::Float;
+
+use nalgebra::{DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use scratch::workspace;
+use {LapackScratch, NalgebraLapackResult};
+
+/// A set of vectors, stored as the columns of a matrix, that can be orthonormalized.
+pub trait Orthonormalize {
+    type N;
+
+    /// compute an orthonormal basis of the span of the columns.
+    ///
+    /// This is Gram-Schmidt orthonormalization done stably with the thin QR factorization. To
+    /// handle linearly dependent columns, the factorization uses column pivoting, and columns
+    /// whose diagonal element of `R` is at or below `max(m, n) * eps * |R_11|` are treated as
+    /// dependent and dropped.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `q` - An `m x rank` matrix with orthonormal columns spanning the same space as the
+    ///   input columns.
+    fn orthonormalize(self) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// compute an orthonormal basis of the span of the columns, borrowing the LAPACK workspace
+    /// from `scratch`.
+    ///
+    /// This is the same as `orthonormalize()`, but reuses `scratch` instead of allocating a
+    /// new workspace.
+    fn orthonormalize_with_workspace(self, scratch: &mut LapackScratch)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// A type for which the numerical rank can be estimated with a rank-revealing QR
+/// factorization.
+pub trait RankRevealingQr {
+    type N;
+
+    /// estimate the numerical rank using the QR factorization with column pivoting.
+    ///
+    /// With column pivoting (`?geqp3`), the diagonal of `R` is non-increasing in magnitude, and
+    /// the rank is the number of its elements above `tol * |R_11|`. This is much cheaper than
+    /// the SVD. It is reliable in practice, but not guaranteed: for some adversarial matrices,
+    /// such as Kahan's, no diagonal element of `R` is small although the matrix is nearly
+    /// singular, so the rank is overestimated. The SVD should be used when this matters.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The threshold relative to `|R_11|`.
+    ///
+    /// # Returns
+    ///
+    /// * `rank` - The estimated numerical rank, zero for an empty or zero matrix.
+    fn rank_revealing_qr(self, tol: Self::N) -> NalgebraLapackResult<usize>;
+}
+
+/// The QR factorization `A = Q R` of an `m x n` matrix, with `Q` kept as Householder
+/// reflectors.
+///
+/// This is computed with `QrFactorize::qr_compact()`. `Q = H_1 H_2 ... H_k`, with
+/// `k = min(m, n)`, is never formed: it is applied to a matrix with `?ormqr` in `O(m k)`
+/// operations per column, using only the storage of the factorization, which is much cheaper
+/// than forming the dense `m x m` matrix when it is applied to a few vectors.
+///
+/// Rows appended with `append_row()` are folded in with Givens rotations, which are kept
+/// alongside the reflectors and applied after them.
+#[derive(Clone)]
+pub struct QrCompact<N> {
+    qr: DMatrix<N>,
+    tau: Vec<N>,
+    /// The Givens rotations `(c, s)` of each appended row, oldest first. Rotation `j` of the
+    /// `i`-th appended row acts on rows `j` and `m + i` of the original `m` rows of `qr`.
+    rotations: Vec<Vec<(N, N)>>,
+}
+
+impl<N> QrCompact<N> {
+    /// return the factors in LAPACK's packed form, as computed by `?geqrf`.
+    ///
+    /// `R` is the upper triangle including the diagonal. Below the diagonal, column `i` holds
+    /// the Householder vector of `H_i`, whose leading unit element is not stored. After
+    /// `append_row()`, the triangle holds the updated `R`, while the reflectors still only
+    /// describe the original rows.
+    pub fn factors(&self) -> &DMatrix<N> {
+        &self.qr
+    }
+
+    /// return the scalar factors of the reflectors: `H_i = I - tau_i v_i v_i^T`.
+    pub fn tau(&self) -> &[N] {
+        &self.tau
+    }
+
+    /// the number of rows of the factorized matrix, including the appended ones.
+    pub fn nrows(&self) -> usize {
+        self.qr.nrows() + self.rotations.len()
+    }
+}
+
+impl<N: Float> QrCompact<N> {
+    /// update the factorization for a new row appended at the bottom of the matrix.
+    ///
+    /// This is the update of recursive least squares: `[A; w^T] = Q' R'` is obtained from
+    /// `A = Q R` with `n` Givens rotations, which zero `w` against the diagonal of `R` one
+    /// element at a time, in `O(n^2)` operations instead of the `O(m n^2)` of a new
+    /// factorization. `R` is updated in place and the rotations are kept to apply `Q'`, so
+    /// `apply_q()`, `apply_q_transpose()` and `q()` refer to the augmented matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The new row, with `n` elements.
+    ///
+    /// # Returns
+    ///
+    /// A `DimensionMismatch` error if `row` does not have `n` elements or if the factorized
+    /// matrix is wide (`m < n`), for which `R` would gain a row.
+    pub fn append_row(&mut self, row: DVector<N>) -> NalgebraLapackResult<()> {
+        let n = self.qr.ncols();
+        if row.len() != n || self.qr.nrows() < n {
+            return Err(Error::from(ErrorKind::DimensionMismatch));
+        }
+        let mut w = row.at;
+        let mut rotations = Vec::with_capacity(n);
+        for j in 0..n {
+            let (a, b) = (self.qr[(j, j)], w[j]);
+            let r = a.hypot(b);
+            let (c, s) = if r == N::zero() { (N::one(), N::zero()) } else { (a / r, b / r) };
+            for (l, wl) in w.iter_mut().enumerate().skip(j) {
+                let (x, y) = (self.qr[(j, l)], *wl);
+                self.qr[(j, l)] = c * x + s * y;
+                *wl = c * y - s * x;
+            }
+            rotations.push((c, s));
+        }
+        self.rotations.push(rotations);
+        Ok(())
+    }
+
+    /// Apply the Givens rotations of the appended rows to the rows of `x`: their product `G`
+    /// if `transpose` is false, which comes after the reflectors in `Q`, otherwise `G^T`.
+    fn rotate(&self, x: &mut DMatrix<N>, transpose: bool) {
+        let m = self.qr.nrows();
+        let apply = |x: &mut DMatrix<N>, j: usize, p: usize, c: N, s: N| {
+            for col in 0..x.ncols() {
+                let (a, b) = (x[(j, col)], x[(p, col)]);
+                if transpose {
+                    x[(j, col)] = c * a + s * b;
+                    x[(p, col)] = c * b - s * a;
+                } else {
+                    x[(j, col)] = c * a - s * b;
+                    x[(p, col)] = s * a + c * b;
+                }
+            }
+        };
+        if transpose {
+            for (i, rotations) in self.rotations.iter().enumerate() {
+                for (j, &(c, s)) in rotations.iter().enumerate() {
+                    apply(x, j, m + i, c, s);
+                }
+            }
+        } else {
+            for (i, rotations) in self.rotations.iter().enumerate().rev() {
+                for (j, &(c, s)) in rotations.iter().enumerate().rev() {
+                    apply(x, j, m + i, c, s);
+                }
+            }
+        }
+    }
+}
+
+/// A type for which the QR factorization can be computed, keeping `Q` in compact form.
+pub trait QrFactorize {
+    type N;
+
+    /// compute the QR factorization without forming `Q`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `qr` - The factorization.
+    fn qr_compact(self) -> NalgebraLapackResult<QrCompact<Self::N>>;
+}
+
+/// A matrix whose mutual coherence can be computed.
+pub trait MutualCoherence {
+    type N;
+
+    /// compute the mutual coherence, the largest absolute cosine between two distinct
+    /// columns.
+    ///
+    /// This is `max_(i != j) |a_i^T a_j| / (||a_i|| ||a_j||)`, the largest off-diagonal
+    /// element in magnitude of the Gram matrix of the normalized columns, as used in
+    /// compressed sensing to bound the sparsity of the signals a dictionary can recover. The
+    /// columns are normalized before their inner products are taken, with their norms
+    /// computed after scaling by their largest element so that they neither overflow nor
+    /// underflow, and rounding errors are kept from pushing the result above one. Zero
+    /// columns have no direction and are skipped.
+    ///
+    /// # Returns
+    ///
+    /// * `coherence` - The mutual coherence, in `[0, 1]`, or zero with fewer than two nonzero
+    ///   columns.
+    fn mutual_coherence(&self) -> Self::N;
+}
+
+impl<N: Float> MutualCoherence for DMatrix<N> {
+    type N = N;
+
+    fn mutual_coherence(&self) -> N {
+        let m = self.nrows();
+        let columns: Vec<Vec<N>> = (0..self.ncols())
+            .filter_map(|j| {
+                let scale = (0..m).fold(N::zero(), |acc, i| acc.max(self[(i, j)].abs()));
+                if scale == N::zero() {
+                    return None;
+                }
+                let scaled: Vec<N> = (0..m).map(|i| self[(i, j)] / scale).collect();
+                let norm = scaled.iter().fold(N::zero(), |acc, x| acc + *x * *x).sqrt();
+                Some(scaled.iter().map(|x| *x / norm).collect())
+            })
+            .collect();
+        let mut coherence = N::zero();
+        for j in 0..columns.len() {
+            for k in (j + 1)..columns.len() {
+                let dot = columns[j].iter().zip(columns[k].iter())
+                    .fold(N::zero(), |acc, (x, y)| acc + *x * *y);
+                coherence = coherence.max(dot.abs());
+            }
+        }
+        coherence.min(N::one())
+    }
+}
+
+/// The QR factorization with column pivoting behind `Orthonormalize` and `RankRevealingQr`.
+trait PivotedQr {
+    type N;
+
+    /// Compute the factorization with `?geqp3`, returning the factors in LAPACK's packed form
+    /// and the scalar factors `tau` of the reflectors. The workspace is borrowed from
+    /// `scratch`.
+    fn pivoted_qr(self, scratch: &mut LapackScratch)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, Vec<Self::N>)>;
+}
+
+macro_rules! orthonormalize_impl(
+    ($t: ty, $geqp3: path, $orgqr: path) => (
+        impl PivotedQr for DMatrix<$t> {
+            type N = $t;
+
+            fn pivoted_qr(self, scratch: &mut LapackScratch)
+                -> NalgebraLapackResult<(DMatrix<$t>, Vec<$t>)> {
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                let k = if m <= n { m } else { n };
+                let lda = m as i32;
+
+                let mut jpvt: Vec<i32> = vec![0; n];
+                let mut tau: Vec<$t> = vec![0.0; k];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $geqp3(m as i32, n as i32, a.as_mut_vector(), lda, &mut jpvt, &mut tau,
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let work = workspace::<$t>(scratch, lwork as usize);
+
+                ::diagnostics::record(stringify!($geqp3), m, n, &[]);
+                $geqp3(m as i32, n as i32, a.as_mut_vector(), lda, &mut jpvt, &mut tau,
+                    work, lwork, &mut info);
+                check_info!(info);
+
+                Ok((a, tau))
+            }
+        }
+
+        impl RankRevealingQr for DMatrix<$t> {
+            type N = $t;
+
+            fn rank_revealing_qr(self, tol: $t) -> NalgebraLapackResult<usize> {
+                let m = self.nrows();
+                let n = self.ncols();
+                let k = if m <= n { m } else { n };
+                if k == 0 {
+                    return Ok(0);
+                }
+                let (r, _) = self.pivoted_qr(&mut LapackScratch::new())?;
+                let threshold = tol * Float::abs(r[(0, 0)]);
+                Ok((0..k).take_while(|&i| Float::abs(r[(i, i)]) > threshold).count())
+            }
+        }
+
+        impl Orthonormalize for DMatrix<$t> {
+            type N = $t;
+
+            fn orthonormalize(self) -> NalgebraLapackResult<DMatrix<$t>> {
+                self.orthonormalize_with_workspace(&mut LapackScratch::new())
+            }
+
+            fn orthonormalize_with_workspace(self, scratch: &mut LapackScratch)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let m = self.nrows();
+                let n = self.ncols();
+                let k = if m <= n { m } else { n };
+                if k == 0 {
+                    return Ok(DMatrix::new_zeros(m, 0));
+                }
+                let lda = m as i32;
+                let (mut a, tau) = self.pivoted_qr(scratch)?;
+                let mut info = 0;
+
+                // With column pivoting the diagonal of R is non-increasing in magnitude.
+                let max_mn = if m <= n { n } else { m };
+                let tol = max_mn as $t * <$t as Float>::epsilon() * Float::abs(a[(0, 0)]);
+                let rank = (0..k).take_while(|&i| Float::abs(a[(i, i)]) > tol).count();
+                if rank == 0 {
+                    return Ok(DMatrix::new_zeros(m, 0));
+                }
+
+                let mut lwork = -1 as i32;
+                let mut work = vec![0.0];
+
+                $orgqr(m as i32, rank as i32, rank as i32, a.as_mut_vector(), lda, &tau,
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let work = workspace::<$t>(scratch, lwork as usize);
+
+                $orgqr(m as i32, rank as i32, rank as i32, a.as_mut_vector(), lda, &tau,
+                    work, lwork, &mut info);
+                check_info!(info);
+
+                Ok(DMatrix::from_fn(m, rank, |i, j| a[(i, j)]))
+            }
+        }
+    );
+);
+
+macro_rules! qr_compact_impl(
+    ($t: ty, $geqrf: path, $ormqr: path, $orgqr: path) => (
+        impl QrFactorize for DMatrix<$t> {
+            type N = $t;
+
+            fn qr_compact(self) -> NalgebraLapackResult<QrCompact<$t>> {
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                let k = if m <= n { m } else { n };
+                if k == 0 {
+                    return Ok(QrCompact { qr: a, tau: vec![], rotations: vec![] });
+                }
+                let lda = m as i32;
+
+                let mut tau: Vec<$t> = vec![0.0; k];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $geqrf(m as i32, n as i32, a.as_mut_vector(), lda, &mut tau, &mut work, lwork,
+                    &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                ::diagnostics::record(stringify!($geqrf), m, n, &[]);
+                $geqrf(m as i32, n as i32, a.as_mut_vector(), lda, &mut tau, &mut work, lwork,
+                    &mut info);
+                check_info!(info);
+
+                Ok(QrCompact { qr: a, tau, rotations: vec![] })
+            }
+        }
+
+        impl QrCompact<$t> {
+            /// compute `Q x` without forming `Q`.
+            ///
+            /// # Arguments
+            ///
+            /// * `x` - The matrix to multiply, with `nrows()` rows.
+            ///
+            /// # Returns
+            ///
+            /// * `qx` - The product `Q x`.
+            pub fn apply_q(&self, x: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                self.apply(x, b'N')
+            }
+
+            /// compute `Q^T x` without forming `Q`.
+            ///
+            /// # Arguments
+            ///
+            /// * `x` - The matrix to multiply, with `nrows()` rows.
+            ///
+            /// # Returns
+            ///
+            /// * `qtx` - The product `Q^T x`.
+            pub fn apply_q_transpose(&self, x: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                self.apply(x, b'T')
+            }
+
+            /// form the thin `nrows() x k` factor `Q`, the first `k` columns of the full one.
+            ///
+            /// This is meant for when the explicit matrix is needed; `apply_q()` is cheaper
+            /// for products.
+            pub fn q(&self) -> NalgebraLapackResult<DMatrix<$t>> {
+                let m = self.qr.nrows();
+                let k = self.tau.len();
+                if k == 0 {
+                    return Ok(DMatrix::new_zeros(self.nrows(), 0));
+                }
+                if !self.rotations.is_empty() {
+                    let identity = DMatrix::from_fn(self.nrows(), k, |i, j| {
+                        if i == j { 1.0 } else { 0.0 }
+                    });
+                    return self.apply_q(identity);
+                }
+                let lda = m as i32;
+                let mut q = DMatrix::from_fn(m, k, |i, j| self.qr[(i, j)]);
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $orgqr(m as i32, k as i32, k as i32, q.as_mut_vector(), lda, &self.tau,
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                $orgqr(m as i32, k as i32, k as i32, q.as_mut_vector(), lda, &self.tau,
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                Ok(q)
+            }
+
+            /// return the upper trapezoidal `k x n` factor `R`.
+            pub fn r(&self) -> DMatrix<$t> {
+                let k = self.tau.len();
+                DMatrix::from_fn(k, self.qr.ncols(), |i, j| {
+                    if i <= j { self.qr[(i, j)] } else { 0.0 }
+                })
+            }
+
+            /// Apply `Q` to `x` from the left, transposed according to `trans`.
+            fn apply(&self, x: DMatrix<$t>, trans: u8) -> NalgebraLapackResult<DMatrix<$t>> {
+                if x.nrows() != self.nrows() {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                if self.rotations.is_empty() {
+                    return self.apply_reflectors(x, trans);
+                }
+                // `Q = diag(H_1 ... H_k, I) G`, with the reflectors on the original rows.
+                let mut x = x;
+                if trans == b'N' {
+                    self.rotate(&mut x, false);
+                }
+                let m = self.qr.nrows();
+                let top = DMatrix::from_fn(m, x.ncols(), |i, j| x[(i, j)]);
+                let top = self.apply_reflectors(top, trans)?;
+                for i in 0..m {
+                    for j in 0..x.ncols() {
+                        x[(i, j)] = top[(i, j)];
+                    }
+                }
+                if trans == b'T' {
+                    self.rotate(&mut x, true);
+                }
+                Ok(x)
+            }
+
+            /// Apply the reflectors to the `m` rows of `x` from the left, transposed by LAPACK
+            /// according to `trans`.
+            fn apply_reflectors(&self, x: DMatrix<$t>, trans: u8)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let side = b'L';
+                let m = self.qr.nrows();
+                let k = self.tau.len();
+                let mut c = x;
+                let nrhs = c.ncols();
+                if k == 0 || nrhs == 0 {
+                    return Ok(c);
+                }
+                let lda = m as i32;
+                let ldc = m as i32;
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $ormqr(side, trans, m as i32, nrhs as i32, k as i32, self.qr.as_vector(), lda,
+                    &self.tau, c.as_mut_vector(), ldc, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                $ormqr(side, trans, m as i32, nrhs as i32, k as i32, self.qr.as_vector(), lda,
+                    &self.tau, c.as_mut_vector(), ldc, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                Ok(c)
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+orthonormalize_impl!(f32, interface::sgeqp3, interface::sorgqr);
+orthonormalize_impl!(f64, interface::dgeqp3, interface::dorgqr);
+
+qr_compact_impl!(f32, interface::sgeqrf, interface::sormqr, interface::sorgqr);
+qr_compact_impl!(f64, interface::dgeqrf, interface::dormqr, interface::dorgqr);
@@ -0,0 +1,50 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::{DMatrix, DVector};
+
+/// A complex matrix or vector that can be converted to a real one when its imaginary parts
+/// are negligible.
+pub trait ToRealChecked {
+    type N;
+    type Real;
+
+    /// return the real part, if all imaginary parts are negligible.
+    ///
+    /// LAPACK returns some results, such as eigenvalues and eigenvectors, as complex numbers
+    /// even when they are known to be real. This drops the imaginary parts after checking that
+    /// none of them exceeds `tol` in magnitude.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The largest magnitude of an imaginary part that is considered zero.
+    ///
+    /// # Returns
+    ///
+    /// * `real` - The real part, or `None` if an imaginary part exceeds `tol`.
+    fn to_real_checked(self, tol: Self::N) -> Option<Self::Real>;
+}
+
+impl<N: Float> ToRealChecked for DMatrix<Complex<N>> {
+    type N = N;
+    type Real = DMatrix<N>;
+
+    fn to_real_checked(self, tol: N) -> Option<DMatrix<N>> {
+        if self.as_vector().iter().any(|x| x.im.abs() > tol) {
+            return None;
+        }
+        Some(DMatrix::from_fn(self.nrows(), self.ncols(), |i, j| self[(i, j)].re))
+    }
+}
+
+impl<N: Float> ToRealChecked for DVector<Complex<N>> {
+    type N = N;
+    type Real = DVector<N>;
+
+    fn to_real_checked(self, tol: N) -> Option<DVector<N>> {
+        if self.at.iter().any(|x| x.im.abs() > tol) {
+            return None;
+        }
+        Some(DVector { at: self.at.iter().map(|x| x.re).collect() })
+    }
+}
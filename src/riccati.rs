@@ -0,0 +1,72 @@
+use nalgebra::{BaseFloat, DMatrix, Transpose};
+
+use errors::{Error, ErrorKind};
+use {IterOptions, LeastSquares, MatrixSign, NalgebraLapackResult, Solve};
+
+/// solve the continuous-time algebraic Riccati equation (CARE).
+///
+/// This finds the symmetric `X` satisfying `A^T X + X A - X B R^-1 B^T X + Q = 0` for which
+/// `A - B R^-1 B^T X` is stable, as needed for example for the linear-quadratic regulator. It
+/// uses the matrix sign function of the Hamiltonian matrix
+/// `H = [[A, -B R^-1 B^T], [-Q, -A^T]]`: with `W = sign(H)`, the solution satisfies
+/// `[W_12; W_22 + I] X = -[W_11 + I; W_21]`, which is solved in the least-squares sense.
+///
+/// A stabilizing solution exists if `(A, B)` is stabilizable and `H` has no eigenvalues on
+/// the imaginary axis, which holds for example if `Q` is positive definite.
+///
+/// # Arguments
+///
+/// * `a` - The `n x n` state matrix.
+/// * `b` - The `n x m` input matrix.
+/// * `q` - The `n x n` symmetric state weight.
+/// * `r` - The `m x m` symmetric positive-definite input weight.
+///
+/// # Returns
+///
+/// * `x` - The stabilizing symmetric solution.
+pub fn solve_care<N>(a: DMatrix<N>, b: DMatrix<N>, q: DMatrix<N>, r: DMatrix<N>)
+    -> NalgebraLapackResult<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: MatrixSign<N = N> + LeastSquares<N = N> + Solve<N = N>
+{
+    let n = a.nrows();
+    let m = b.ncols();
+    if a.ncols() != n || q.nrows() != q.ncols() || r.nrows() != r.ncols() {
+        return Err(Error::from(ErrorKind::MatrixNotSquare));
+    }
+    if b.nrows() != n || q.nrows() != n || r.nrows() != m {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+
+    let g = &b * &r.solve(b.transpose())?;
+    let h = DMatrix::from_fn(2 * n, 2 * n, |i, j| {
+        match (i < n, j < n) {
+            (true, true) => a[(i, j)],
+            (true, false) => -g[(i, j - n)],
+            (false, true) => -q[(i - n, j)],
+            (false, false) => -a[(j - n, i - n)],
+        }
+    });
+    let w = h.matrix_sign_with(IterOptions::default())?;
+
+    let delta = |i: usize, j: usize| if i == j { N::one() } else { N::zero() };
+    let lhs = DMatrix::from_fn(2 * n, n, |i, j| {
+        if i < n {
+            w[(i, n + j)]
+        } else {
+            w[(i, n + j)] + delta(i - n, j)
+        }
+    });
+    let rhs = DMatrix::from_fn(2 * n, n, |i, j| {
+        if i < n {
+            -(w[(i, j)] + delta(i, j))
+        } else {
+            -w[(i, j)]
+        }
+    });
+    let x = lhs.least_squares(rhs)?;
+
+    // Remove the rounding errors that make `x` slightly asymmetric.
+    let two = N::one() + N::one();
+    Ok(DMatrix::from_fn(n, n, |i, j| (x[(i, j)] + x[(j, i)]) / two))
+}
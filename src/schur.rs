@@ -0,0 +1,183 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::DMatrix;
+
+use errors::{Error, ErrorKind};
+use NalgebraLapackResult;
+
+/// convert a real Schur decomposition into a complex one.
+///
+/// In the real Schur decomposition `A = Z T Z^T`, `T` is quasi upper triangular: a complex
+/// conjugate pair of eigenvalues appears as a 2x2 block on its diagonal. Each such block is
+/// triangularized by a unitary rotation that is also applied to `Z`, giving `A = Z_c T_c
+/// Z_c^H` with `T_c` truly upper triangular and `Z_c` unitary. The eigenvalue with the
+/// non-negative imaginary part of each pair comes first on the diagonal.
+///
+/// # Arguments
+///
+/// * `t` - The `n x n` quasi upper triangular factor. A nonzero subdiagonal element marks
+///   a 2x2 block.
+/// * `z` - The `n x n` orthogonal factor.
+///
+/// # Returns
+///
+/// * `t_c` - The complex upper triangular factor, with the eigenvalues on its diagonal.
+/// * `z_c` - The complex unitary factor.
+pub fn real_schur_to_complex<N: Float>(t: DMatrix<N>, z: DMatrix<N>)
+    -> NalgebraLapackResult<(DMatrix<Complex<N>>, DMatrix<Complex<N>>)> {
+    let n = t.nrows();
+    if t.ncols() != n || z.nrows() != z.ncols() {
+        return Err(Error::from(ErrorKind::MatrixNotSquare));
+    }
+    if z.nrows() != n {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let zero = N::zero();
+    let two = N::one() + N::one();
+    let mut tc = DMatrix::from_fn(n, n, |i, j| Complex::new(t[(i, j)], zero));
+    let mut zc = DMatrix::from_fn(n, n, |i, j| Complex::new(z[(i, j)], zero));
+
+    let mut k = 0;
+    while k + 1 < n {
+        if t[(k + 1, k)] == zero {
+            k += 1;
+            continue;
+        }
+        let (a, b, c, d) = (t[(k, k)], t[(k, k + 1)], t[(k + 1, k)], t[(k + 1, k + 1)]);
+
+        // The eigenvalue `lambda` of the block with non-negative imaginary part, whose
+        // eigenvector is `(lambda - d, c)`.
+        let half_diff = (a - d) / two;
+        let disc = half_diff * half_diff + b * c;
+        let lambda = if disc < zero {
+            Complex::new((a + d) / two, (-disc).sqrt())
+        } else {
+            Complex::new((a + d) / two + disc.sqrt(), zero)
+        };
+        let mu = lambda - Complex::new(d, zero);
+        let r = (mu.norm_sqr() + c * c).sqrt();
+        // The unitary rotation `Q = [[p, -conj(q)], [q, conj(p)]]`, whose first column is the
+        // normalized eigenvector.
+        let p = mu.unscale(r);
+        let q = Complex::new(c / r, zero);
+
+        // `T <- Q^H T` on rows `k` and `k + 1`.
+        for j in 0..n {
+            let (x, y) = (tc[(k, j)], tc[(k + 1, j)]);
+            tc[(k, j)] = p.conj() * x + q.conj() * y;
+            tc[(k + 1, j)] = -q * x + p * y;
+        }
+        // `T <- T Q` and `Z <- Z Q` on columns `k` and `k + 1`.
+        for m in [&mut tc, &mut zc].iter_mut() {
+            for i in 0..n {
+                let (x, y) = (m[(i, k)], m[(i, k + 1)]);
+                m[(i, k)] = x * p + y * q;
+                m[(i, k + 1)] = -x * q.conj() + y * p.conj();
+            }
+        }
+        tc[(k + 1, k)] = Complex::new(zero, zero);
+        k += 2;
+    }
+    Ok((tc, zc))
+}
+
+/// A real matrix for which the stable invariant subspace can be computed.
+pub trait StableSubspace {
+    type N;
+
+    /// compute an orthonormal basis of the stable invariant subspace.
+    ///
+    /// The stable invariant subspace is spanned by the (generalized) eigenvectors of the
+    /// eigenvalues with negative real part, and `A` maps it into itself. The real Schur
+    /// decomposition `A = Z T Z^T` is computed with `?gees` and reordered with `?trsen` so that
+    /// these eigenvalues come first on the diagonal of `T`; the leading columns of `Z` are then
+    /// the basis. Unlike a basis of eigenvectors, it stays well conditioned for non-normal
+    /// matrices, which makes it the key step of Schur-method Riccati solvers.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `basis` - The `n x k` matrix with orthonormal columns, where `k` is the number of
+    ///   eigenvalues with negative real part. A `LapackFailure` error is returned if the
+    ///   eigenvalues could not be reordered because they are too close together.
+    fn stable_subspace(self) -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+macro_rules! stable_subspace_impl(
+    ($t: ty, $gees: path, $trsen: path) => (
+        impl StableSubspace for DMatrix<$t> {
+            type N = $t;
+
+            fn stable_subspace(self) -> NalgebraLapackResult<DMatrix<$t>> {
+                let jobvs = b'V';
+                let sort = b'N';
+                let job = b'N';
+                let compq = b'V';
+                let mut a = self;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok(a);
+                }
+                let lda = n as i32;
+                let ldvs = n as i32;
+                let mut sdim = 0;
+                let mut wr = vec![0.0; n];
+                let mut wi = vec![0.0; n];
+                let mut vs: DMatrix<$t> = DMatrix::new_zeros(n, n);
+                let mut bwork = vec![0; n];
+                let mut info = 0;
+
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                $gees(jobvs, sort, None, n as i32, a.as_mut_vector(), lda, &mut sdim, &mut wr,
+                    &mut wi, vs.as_mut_vector(), ldvs, &mut work, lwork, &mut bwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+                ::diagnostics::record(stringify!($gees), n, n, &[jobvs, sort]);
+                $gees(jobvs, sort, None, n as i32, a.as_mut_vector(), lda, &mut sdim, &mut wr,
+                    &mut wi, vs.as_mut_vector(), ldvs, &mut work, lwork, &mut bwork, &mut info);
+                check_info!(info);
+
+                // Both eigenvalues of a complex conjugate pair have the same real part, so
+                // they are selected together as `?trsen` requires.
+                let select: Vec<i32> = wr.iter().map(|x| (*x < 0.0) as i32).collect();
+                let mut m = 0;
+                // `s` and `sep` are not referenced for `job = 'N'`.
+                let mut s = [0.0];
+                let mut sep = [0.0];
+                let mut work = vec![0.0];
+                let mut iwork = vec![0];
+                lwork = -1;
+                let mut liwork = -1 as i32;
+                // The binding takes `q` as a reference to its first element.
+                $trsen(job, compq, &select, n as i32, a.as_mut_vector(), lda,
+                    &mut vs.as_mut_vector()[0], ldvs, &mut wr, &mut wi, &mut m, &mut s, &mut sep,
+                    &mut work, lwork, &mut iwork, liwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                liwork = iwork[0];
+                let mut work = vec![0.0; lwork as usize];
+                let mut iwork = vec![0; liwork as usize];
+                ::diagnostics::record(stringify!($trsen), n, n, &[job, compq]);
+                $trsen(job, compq, &select, n as i32, a.as_mut_vector(), lda,
+                    &mut vs.as_mut_vector()[0], ldvs, &mut wr, &mut wi, &mut m, &mut s, &mut sep,
+                    &mut work, lwork, &mut iwork, liwork, &mut info);
+                check_info!(info);
+
+                Ok(DMatrix::from_fn(n, m as usize, |i, j| vs[(i, j)]))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+stable_subspace_impl!(f32, interface::sgees, interface::strsen);
+stable_subspace_impl!(f64, interface::dgees, interface::dtrsen);
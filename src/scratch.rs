@@ -0,0 +1,63 @@
+use std::mem;
+use std::slice;
+
+use num::complex::Complex;
+
+/// A scratch buffer which can be reused as the LAPACK workspace of several operations.
+///
+/// LAPACK routines need a workspace whose optimal size each routine reports through a
+/// workspace query. The allocating methods, such as `Orthonormalize::orthonormalize()`,
+/// allocate a new workspace of that size on every call. The `*_with_workspace` methods, such as
+/// `Orthonormalize::orthonormalize_with_workspace()`, instead borrow it from a
+/// `LapackScratch`, which is only grown when a query asks for more than its capacity. The same
+/// buffer can be used for routines with different scalar types.
+///
+/// To avoid allocating at all in a fixed pipeline, run it once and `reserve()` the resulting
+/// `capacity()` in the buffers used afterwards: the capacity is then the largest workspace,
+/// in bytes, that any of the operations asked for.
+#[derive(Clone, Debug, Default)]
+pub struct LapackScratch {
+    // `u64` words, so that the buffer is aligned for every LAPACK scalar type.
+    buffer: Vec<u64>,
+}
+
+impl LapackScratch {
+    /// create an empty scratch buffer.
+    pub fn new() -> LapackScratch {
+        LapackScratch { buffer: Vec::new() }
+    }
+
+    /// grow the buffer, if needed, so that it holds at least `bytes` bytes.
+    pub fn reserve(&mut self, bytes: usize) {
+        let words = (bytes + mem::size_of::<u64>() - 1) / mem::size_of::<u64>();
+        if words > self.buffer.len() {
+            self.buffer.resize(words, 0);
+        }
+    }
+
+    /// the size of the buffer in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len() * mem::size_of::<u64>()
+    }
+}
+
+/// A LAPACK scalar type which can be stored in a `LapackScratch`.
+///
+/// # Safety
+///
+/// Every bit pattern must be a valid value of the type, and its alignment must not exceed
+/// that of `u64`, because `workspace()` reinterprets the `u64` buffer as a slice of it.
+pub unsafe trait ScratchElement: Copy {}
+
+unsafe impl ScratchElement for f32 {}
+unsafe impl ScratchElement for f64 {}
+unsafe impl ScratchElement for Complex<f32> {}
+unsafe impl ScratchElement for Complex<f64> {}
+
+/// Borrow a workspace of `len` elements from `scratch`, growing it if needed.
+pub fn workspace<N: ScratchElement>(scratch: &mut LapackScratch, len: usize) -> &mut [N] {
+    scratch.reserve(len * mem::size_of::<N>());
+    // The buffer is initialized, large enough and suitably aligned, and any bits are a valid
+    // `N`, as required by `ScratchElement`.
+    unsafe { slice::from_raw_parts_mut(scratch.buffer.as_mut_ptr() as *mut N, len) }
+}
@@ -0,0 +1,58 @@
+use nalgebra::{BaseFloat, DMatrix};
+
+use errors::{Error, ErrorKind};
+use {NalgebraLapackResult, SymmetricEigensystem};
+
+/// compute a spectral embedding of the nodes of a graph from its normalized Laplacian.
+///
+/// With the weighted adjacency matrix `W` and the diagonal degree matrix `D` of its row sums,
+/// the symmetric normalized Laplacian is `L = I - D^-1/2 W D^-1/2`. Its smallest eigenvalue
+/// is zero, with the trivial eigenvector `D^1/2 1` for a connected graph, and the next
+/// eigenvectors give coordinates in which strongly connected nodes lie close together, as
+/// used by Laplacian eigenmaps and spectral clustering. In particular, the signs of the first
+/// coordinate, the Fiedler vector, split the graph in two. Only the requested eigenpairs are
+/// computed, with `SymmetricEigensystem::symmetric_eigensystem_range()`. A node without
+/// edges is given a degree of one, so that it only contributes an eigenvalue of one.
+///
+/// The coordinates of the Laplacian eigenmaps of Belkin and Niyogi, which solve
+/// `(D - W) y = lambda D y`, are obtained by multiplying row `i` of the embedding by
+/// `d_i^-1/2`.
+///
+/// # Arguments
+///
+/// * `adjacency` - The symmetric `n x n` matrix of nonnegative edge weights.
+/// * `k` - The number of embedding dimensions, at most `n - 1`.
+///
+/// # Returns
+///
+/// * `embedding` - The `n x k` matrix whose columns are the orthonormal eigenvectors of `L`
+///   for its second to `(k + 1)`-th smallest eigenvalues, in ascending order. Row `i` holds
+///   the coordinates of node `i`. A `DimensionMismatch` error is returned if `k` is too
+///   large.
+pub fn laplacian_eigenmaps<N>(adjacency: DMatrix<N>, k: usize) -> NalgebraLapackResult<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    let n = adjacency.nrows();
+    if adjacency.ncols() != n {
+        return Err(Error::from(ErrorKind::MatrixNotSquare));
+    }
+    if k + 1 > n {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    if k == 0 {
+        return Ok(DMatrix::new_zeros(n, 0));
+    }
+    let scale: Vec<N> = (0..n)
+        .map(|i| {
+            let degree = (0..n).fold(N::zero(), |acc, j| acc + adjacency[(i, j)]);
+            if degree > N::zero() { N::one() / degree.sqrt() } else { N::one() }
+        })
+        .collect();
+    let laplacian = DMatrix::from_fn(n, n, |i, j| {
+        let identity = if i == j { N::one() } else { N::zero() };
+        identity - scale[i] * adjacency[(i, j)] * scale[j]
+    });
+    let (_, vectors) = laplacian.symmetric_eigensystem_range(1, k)?;
+    Ok(vectors)
+}
@@ -0,0 +1,559 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::{BaseFloat, DMatrix, DVector};
+
+use errors::{Error, ErrorKind};
+use {NalgebraLapackResult, Solve, Triangle};
+
+/// The method used by `StructuredSolve::solve_auto()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveMethod {
+    /// Elementwise division by the diagonal of a diagonal matrix, without calling LAPACK.
+    Diagonal,
+    /// Substitution with an upper or lower triangular matrix (`?trtrs`).
+    Triangular,
+    /// The Cholesky factorization of a symmetric positive-definite matrix (`?posv`).
+    Cholesky,
+    /// The Bunch-Kaufman factorization of a symmetric indefinite matrix (`?sysv`).
+    SymmetricIndefinite,
+    /// The LU factorization with partial pivoting of a general matrix (`?gesv`).
+    General,
+}
+
+/// A type for which linear equations can be solved with a driver chosen from the structure of
+/// the matrix.
+pub trait StructuredSolve {
+    type N;
+
+    /// solve a linear matrix equation using the fastest applicable method.
+    ///
+    /// A matrix whose elements off the diagonal are all exactly zero is solved by dividing by
+    /// its diagonal, and one whose elements below or above the diagonal are all exactly zero
+    /// by triangular substitution, both in `O(n^2)` or less instead of `O(n^3)`. Otherwise,
+    /// the matrix `a` is considered symmetric if `|a_ij - a_ji| <= 100 eps max_kl |a_kl|` for
+    /// all `i` and `j`; only its upper triangle is then used. A symmetric matrix with a
+    /// positive diagonal is first solved with the Cholesky factorization. If that fails
+    /// because `a` is not positive definite, or if the diagonal is not positive, the symmetric
+    /// indefinite factorization of `solve_symmetric()` is used. Any other matrix is solved
+    /// with the general LU factorization, as in `Solve::solve()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`.
+    fn solve_auto(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a linear matrix equation using the fastest applicable method, also returning
+    /// which one was used.
+    ///
+    /// This is the same as `solve_auto()`, but also reports the structure that was detected.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`.
+    /// * `method` - The method that computed the solution.
+    fn solve_auto_reporting_method(self, b: DMatrix<Self::N>)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, SolveMethod)>;
+
+    /// solve a linear matrix equation with a symmetric, possibly indefinite, matrix.
+    ///
+    /// This uses the Bunch-Kaufman factorization `a = U D U^T`, with `D` block diagonal with
+    /// 1x1 and 2x2 blocks. It is about twice as fast as the general LU factorization and,
+    /// unlike the Cholesky factorization, handles indefinite matrices such as those of
+    /// saddle-point (KKT) systems. Only the upper triangle of `a` is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`. An error is returned if `a` is
+    ///   exactly singular.
+    fn solve_symmetric(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// A type for which linear equations can be solved after scaling the matrix.
+pub trait ScaledSolve {
+    type N;
+
+    /// solve a linear matrix equation after equilibrating the matrix.
+    ///
+    /// Row and column scale factors `R` and `C` are computed with `?geequ` so that the
+    /// largest element of each row and column of `R a C` has magnitude one. The scaled system
+    /// `(R a C) y = R b` is solved with `Solve::solve()`, and the solution is un-scaled as
+    /// `x = C y`. For a badly scaled `a`, e.g. with rows in different units, this lets partial
+    /// pivoting choose better pivots and gives a more accurate solution.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`. An error is returned if `a` has an
+    ///   exactly zero row or column, or is otherwise exactly singular.
+    fn solve_equilibrated(self, b: DMatrix<Self::N>) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// solve a linear matrix equation after scaling the columns of the matrix.
+    ///
+    /// With `C = diag(col_scales)`, the system `(a C) y = b` is solved with `Solve::solve()`
+    /// and the solution is rescaled as `x = C y`. Choosing `C` to bring unknowns in very
+    /// different units to a common scale keeps the elements of `a C` away from overflow and
+    /// underflow, where precision is lost. Partial pivoting chooses the same pivots for any
+    /// column scaling, so unlike `solve_equilibrated()` this does not change the pivoting.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    /// * `col_scales` - The nonzero scale factor of each column of `a`, e.g. the typical
+    ///   magnitude of each unknown.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`.
+    fn solve_column_scaled(self, b: DMatrix<Self::N>, col_scales: DVector<Self::N>)
+        -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// A complex matrix for which Hermitian positive-definite linear equations can be solved.
+pub trait HermitianSolve {
+    type N;
+
+    /// solve a linear matrix equation with a Hermitian positive-definite matrix.
+    ///
+    /// This uses the Cholesky factorization `a = L L^H` with `?posv`, which is about twice as
+    /// fast as the general LU factorization, as for the impedance and mass matrices of
+    /// electromagnetic and quantum problems. Only the lower triangle of `a` is read; the upper
+    /// triangle is taken to be its conjugate transpose.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    ///
+    /// # Returns
+    ///
+    /// * `x` - The solution to the linear equation `ax=b`, or `None` if `a` is not positive
+    ///   definite or the dimensions do not match.
+    fn solve_hpd(self, b: DMatrix<Complex<Self::N>>) -> Option<DMatrix<Complex<Self::N>>>;
+
+    /// solve a linear matrix equation with a Hermitian positive-definite matrix, reading only
+    /// the given triangle.
+    ///
+    /// This is `solve_hpd()` for a matrix of which only `triangle` has been filled in.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `b` - The known matrix.
+    /// * `triangle` - The triangle of `a` that is read.
+    ///
+    /// # Returns
+    ///
+    /// The same as `solve_hpd()`.
+    fn solve_hpd_with_triangle(self, b: DMatrix<Complex<Self::N>>, triangle: Triangle)
+        -> Option<DMatrix<Complex<Self::N>>>;
+}
+
+/// The factor of machine epsilon times the largest element within which `a_ij` and `a_ji` are
+/// considered equal.
+const SYMMETRY_TOLERANCE: f64 = 100.0;
+
+/// Return `true` if the square matrix `a` is numerically symmetric.
+fn is_symmetric<N: Float>(a: &DMatrix<N>, tol_factor: N) -> bool {
+    let n = a.nrows();
+    let max = a.as_vector().iter().fold(N::zero(), |acc, x| acc.max(x.abs()));
+    let tol = tol_factor * N::epsilon() * max;
+    for j in 0..n {
+        for i in 0..j {
+            if (a[(i, j)] - a[(j, i)]).abs() > tol {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// The triangle, `b'U'` or `b'L'`, of a square matrix outside of which all elements are
+/// exactly zero, or `None` if there are nonzero elements on both sides of the diagonal.
+fn triangular_part<N: Float>(a: &DMatrix<N>) -> Option<u8> {
+    let n = a.nrows();
+    let zero_below = (0..n).all(|j| ((j + 1)..n).all(|i| a[(i, j)] == N::zero()));
+    let zero_above = (0..n).all(|j| (0..j).all(|i| a[(i, j)] == N::zero()));
+    if zero_below {
+        Some(b'U')
+    } else if zero_above {
+        Some(b'L')
+    } else {
+        None
+    }
+}
+
+/// solve a linear matrix equation with a block tridiagonal matrix.
+///
+/// The matrix has the square blocks `diag[i]` on its diagonal, `sub[i]` below `diag[i]` and
+/// `sup[i]` to the right of it, as for coupled one-dimensional systems such as vector ODEs
+/// discretized in space. This is the block Thomas algorithm: in a forward sweep, each
+/// modified diagonal block `D'_i = D_i - A_(i-1) D'_(i-1)^-1 C_(i-1)` is solved with its LU
+/// factorization, once for the next superdiagonal block and the right-hand side together, and
+/// the solution is then formed by back substitution. With `p` blocks of size `k`, this costs
+/// `O(p k^3)` instead of the `O(p^3 k^3)` of the dense solver. As for the scalar Thomas
+/// algorithm, there is no pivoting between blocks, so it is stable for e.g. block diagonally
+/// dominant matrices but may fail on others.
+///
+/// # Arguments
+///
+/// * `diag` - The `p` square diagonal blocks, where block `i` is `k_i x k_i`.
+/// * `sub` - The `p - 1` subdiagonal blocks, where block `i` is `k_(i+1) x k_i`.
+/// * `sup` - The `p - 1` superdiagonal blocks, where block `i` is `k_i x k_(i+1)`.
+/// * `b` - The known matrix, with `k_0 + ... + k_(p-1)` rows.
+///
+/// # Returns
+///
+/// * `x` - The solution to the linear equation `ax=b`. A `MatrixNotSquare` error is returned
+///   if a diagonal block is not square, and a `DimensionMismatch` error if the numbers or
+///   shapes of the blocks are inconsistent.
+pub fn solve_block_tridiagonal<N>(diag: &[DMatrix<N>], sub: &[DMatrix<N>], sup: &[DMatrix<N>],
+                                  b: DMatrix<N>)
+    -> NalgebraLapackResult<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: Solve<N = N>
+{
+    let p = diag.len();
+    let off = if p == 0 { 0 } else { p - 1 };
+    if sub.len() != off || sup.len() != off {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    if diag.iter().any(|d| d.nrows() != d.ncols()) {
+        return Err(Error::from(ErrorKind::MatrixNotSquare));
+    }
+    let sizes: Vec<usize> = diag.iter().map(|d| d.nrows()).collect();
+    for i in 0..off {
+        let (k, next) = (sizes[i], sizes[i + 1]);
+        if sub[i].nrows() != next || sub[i].ncols() != k ||
+           sup[i].nrows() != k || sup[i].ncols() != next {
+            return Err(Error::from(ErrorKind::DimensionMismatch));
+        }
+    }
+    let mut offsets = vec![0];
+    for k in sizes.iter() {
+        let last = offsets[offsets.len() - 1];
+        offsets.push(last + k);
+    }
+    if b.nrows() != offsets[p] {
+        return Err(Error::from(ErrorKind::DimensionMismatch));
+    }
+    let nrhs = b.ncols();
+
+    // The forward sweep keeps `G_i = D'_i^-1 C_i` and `y_i = D'_i^-1 (b_i - A_(i-1) y_(i-1))`.
+    let mut g: Vec<DMatrix<N>> = Vec::with_capacity(off);
+    let mut y: Vec<DMatrix<N>> = Vec::with_capacity(p);
+    for i in 0..p {
+        let k = sizes[i];
+        let mut d = diag[i].clone();
+        let mut rhs = DMatrix::from_fn(k, nrhs, |r, c| b[(offsets[i] + r, c)]);
+        if i > 0 {
+            d -= &sub[i - 1] * &g[i - 1];
+            rhs -= &sub[i - 1] * &y[i - 1];
+        }
+        let next = if i < off { sup[i].ncols() } else { 0 };
+        let combined = DMatrix::from_fn(k, next + nrhs, |r, c| {
+            if c < next { sup[i][(r, c)] } else { rhs[(r, c - next)] }
+        });
+        let solved = d.solve(combined)?;
+        if i < off {
+            g.push(DMatrix::from_fn(k, next, |r, c| solved[(r, c)]));
+        }
+        y.push(DMatrix::from_fn(k, nrhs, |r, c| solved[(r, next + c)]));
+    }
+
+    // Back substitution: `x_i = y_i - G_i x_(i+1)`.
+    let mut x = DMatrix::new_zeros(offsets[p], nrhs);
+    let mut below: Option<DMatrix<N>> = None;
+    for i in (0..p).rev() {
+        let xi = match below {
+            Some(ref next) => y[i].clone() - &g[i] * next,
+            None => y[i].clone(),
+        };
+        for r in 0..sizes[i] {
+            for c in 0..nrhs {
+                x[(offsets[i] + r, c)] = xi[(r, c)];
+            }
+        }
+        below = Some(xi);
+    }
+    Ok(x)
+}
+
+/// A square matrix for which the Schur complement of a leading block can be computed.
+pub trait SchurComplement {
+    type N;
+
+    /// compute the Schur complement `D - C A^-1 B` of the leading block `A` of the partitioned
+    /// matrix `[[A, B], [C, D]]`, e.g. to eliminate the first block of unknowns of a
+    /// saddle-point problem.
+    ///
+    /// `A^-1 B` is computed by solving `A X = B` with a single LU factorization of `A`, never
+    /// by forming the inverse. The determinant of the matrix is `det(A)` times that of the
+    /// complement, and for a symmetric matrix its inertia is that of `A` plus that of the
+    /// complement.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `block_size` - The order `k` of the leading block `A`.
+    ///
+    /// # Returns
+    ///
+    /// * `s` - The `(n - k) x (n - k)` Schur complement, or `None` if the matrix is not
+    ///   square, `block_size` exceeds its order or `A` is exactly singular.
+    fn schur_complement(self, block_size: usize) -> Option<DMatrix<Self::N>>;
+}
+
+impl<N> SchurComplement for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: Solve<N = N>
+{
+    type N = N;
+
+    fn schur_complement(self, block_size: usize) -> Option<DMatrix<N>> {
+        let n = self.nrows();
+        let k = block_size;
+        if self.ncols() != n || k > n {
+            return None;
+        }
+        let d = DMatrix::from_fn(n - k, n - k, |i, j| self[(k + i, k + j)]);
+        if k == 0 {
+            return Some(d);
+        }
+        let a = DMatrix::from_fn(k, k, |i, j| self[(i, j)]);
+        let b = DMatrix::from_fn(k, n - k, |i, j| self[(i, k + j)]);
+        let c = DMatrix::from_fn(n - k, k, |i, j| self[(k + i, j)]);
+        let x = a.solve(b).ok()?;
+        Some(d - &c * &x)
+    }
+}
+
+macro_rules! structured_solve_impl(
+    ($t: ty, $posv: path, $sysv: path, $trtrs: path) => (
+        impl StructuredSolve for DMatrix<$t> {
+            type N = $t;
+
+            fn solve_auto(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                let (x, _) = self.solve_auto_reporting_method(b)?;
+                Ok(x)
+            }
+
+            fn solve_auto_reporting_method(self, b: DMatrix<$t>)
+                -> NalgebraLapackResult<(DMatrix<$t>, SolveMethod)> {
+                let uplo = b'U';
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if b.nrows() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, n) as i32;
+                let ldb = lda;
+                let mut info = 0;
+
+                let is_diagonal = (0..n).all(|j| (0..n).all(|i| i == j || self[(i, j)] == 0.0));
+                if is_diagonal {
+                    if let Some(i) = (0..n).position(|i| self[(i, i)] == 0.0) {
+                        return Err(Error::from(ErrorKind::LapackFailure((i + 1) as i32)));
+                    }
+                    let x = DMatrix::from_fn(n, b.ncols(), |i, j| b[(i, j)] / self[(i, i)]);
+                    return Ok((x, SolveMethod::Diagonal));
+                }
+                if let Some(uplo) = triangular_part(&self) {
+                    let trans = b'N';
+                    let diag = b'N';
+                    let mut x = b;
+                    $trtrs(uplo, trans, diag, n as i32, nrhs, self.as_vector(), lda,
+                        x.as_mut_vector(), ldb, &mut info);
+                    // A positive `info` means that `a` is exactly singular.
+                    check_info!(info);
+                    return Ok((x, SolveMethod::Triangular));
+                }
+
+                if !is_symmetric(&self, SYMMETRY_TOLERANCE as $t) {
+                    return Ok((self.solve(b)?, SolveMethod::General));
+                }
+
+                if (0..n).all(|i| self[(i, i)] > 0.0) {
+                    let mut a = self.clone();
+                    let mut x = b.clone();
+                    $posv(uplo, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(), ldb,
+                        &mut info);
+                    // A positive `info` means that `a` is not positive definite.
+                    if info < 0 {
+                        return Err(Error::from(ErrorKind::LapackIllegalArgument(-info)));
+                    }
+                    if info == 0 {
+                        return Ok((x, SolveMethod::Cholesky));
+                    }
+                }
+
+                Ok((self.solve_symmetric(b)?, SolveMethod::SymmetricIndefinite))
+            }
+
+            fn solve_symmetric(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                let uplo = b'U';
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if b.nrows() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, n) as i32;
+                let ldb = lda;
+                let mut info = 0;
+
+                let mut a = self;
+                let mut x = b;
+                let mut ipiv: Vec<i32> = vec![0; n];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+
+                $sysv(uplo, n as i32, nrhs, a.as_mut_vector(), lda, &mut ipiv, x.as_mut_vector(),
+                    ldb, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                $sysv(uplo, n as i32, nrhs, a.as_mut_vector(), lda, &mut ipiv, x.as_mut_vector(),
+                    ldb, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                Ok(x)
+            }
+        }
+    );
+);
+
+macro_rules! hermitian_solve_impl(
+    ($t: ty, $posv: path) => (
+        impl HermitianSolve for DMatrix<Complex<$t>> {
+            type N = $t;
+
+            fn solve_hpd(self, b: DMatrix<Complex<$t>>) -> Option<DMatrix<Complex<$t>>> {
+                self.solve_hpd_with_triangle(b, Triangle::Lower)
+            }
+
+            fn solve_hpd_with_triangle(self, b: DMatrix<Complex<$t>>, triangle: Triangle)
+                -> Option<DMatrix<Complex<$t>>> {
+                let uplo = triangle.uplo();
+                let n = self.nrows();
+                if self.ncols() != n || b.nrows() != n {
+                    return None;
+                }
+                let nrhs = b.ncols() as i32;
+                let lda = ::std::cmp::max(1, n) as i32;
+                let ldb = lda;
+                let mut info = 0;
+
+                let mut a = self;
+                let mut x = b;
+                ::diagnostics::record(stringify!($posv), n, n, &[uplo]);
+                $posv(uplo, n as i32, nrhs, a.as_mut_vector(), lda, x.as_mut_vector(), ldb,
+                    &mut info);
+                // A positive `info` means that `a` is not positive definite.
+                if info != 0 {
+                    return None;
+                }
+
+                Some(x)
+            }
+        }
+    );
+);
+
+macro_rules! scaled_solve_impl(
+    ($t: ty, $geequ: path) => (
+        impl ScaledSolve for DMatrix<$t> {
+            type N = $t;
+
+            fn solve_equilibrated(self, b: DMatrix<$t>) -> NalgebraLapackResult<DMatrix<$t>> {
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if b.nrows() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let lda = ::std::cmp::max(1, n) as i32;
+
+                let mut r: Vec<$t> = vec![0.0; n];
+                let mut c: Vec<$t> = vec![0.0; n];
+                let mut rowcnd = 0.0;
+                let mut colcnd = 0.0;
+                let mut amax = 0.0;
+                let mut info = 0;
+
+                ::diagnostics::record(stringify!($geequ), n, n, &[]);
+                $geequ(n as i32, n as i32, self.as_vector(), lda, &mut r, &mut c, &mut rowcnd,
+                    &mut colcnd, &mut amax, &mut info);
+                // A positive `info` means that a row or column is exactly zero.
+                check_info!(info);
+
+                let scaled = DMatrix::from_fn(n, n, |i, j| r[i] * self[(i, j)] * c[j]);
+                let rb = DMatrix::from_fn(n, b.ncols(), |i, j| r[i] * b[(i, j)]);
+                let y = scaled.solve(rb)?;
+                Ok(DMatrix::from_fn(n, y.ncols(), |i, j| c[i] * y[(i, j)]))
+            }
+
+            fn solve_column_scaled(self, b: DMatrix<$t>, col_scales: DVector<$t>)
+                -> NalgebraLapackResult<DMatrix<$t>> {
+                let n = self.nrows();
+                if self.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if b.nrows() != n || col_scales.len() != n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let c = &col_scales;
+                let scaled = DMatrix::from_fn(n, n, |i, j| self[(i, j)] * c[j]);
+                let y = scaled.solve(b)?;
+                Ok(DMatrix::from_fn(n, y.ncols(), |i, j| c[i] * y[(i, j)]))
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+structured_solve_impl!(f32, interface::sposv, interface::ssysv, interface::strtrs);
+structured_solve_impl!(f64, interface::dposv, interface::dsysv, interface::dtrtrs);
+
+hermitian_solve_impl!(f32, interface::cposv);
+hermitian_solve_impl!(f64, interface::zposv);
+
+scaled_solve_impl!(f32, interface::sgeequ);
+scaled_solve_impl!(f64, interface::dgeequ);
@@ -0,0 +1,1453 @@
+use std::ops::Range;
+
+use num::complex::Complex;
+use num::{Float, One, Zero};
+
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector, Iterable, Transpose};
+
+use errors::{Error, ErrorKind};
+use {LapackScalar, NalgebraLapackResult, SymmetricEigensystem};
+
+/// A type for which orthonormal bases of the column space and its complement can be computed.
+pub trait ColumnSpace {
+    type N: LapackScalar;
+
+    /// compute orthonormal bases for the column space (range) and its orthogonal complement
+    /// (the left null space).
+    ///
+    /// Both bases are taken from the left-singular vectors `u` of the SVD, split at the
+    /// numerical rank: the number of singular values greater than `tol`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Singular values at or below this are treated as zero.
+    ///
+    /// # Returns
+    ///
+    /// * `range` - An `m x rank` matrix whose columns are an orthonormal basis of the column
+    ///   space.
+    /// * `complement` - An `m x (m - rank)` matrix whose columns are an orthonormal basis of
+    ///   the orthogonal complement of the column space.
+    fn column_space_split(self, tol: <Self::N as LapackScalar>::Real)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DMatrix<Self::N>)>;
+}
+
+impl<N: LapackScalar> ColumnSpace for DMatrix<N>
+    where N::Real: PartialOrd
+{
+    type N = N;
+
+    fn column_space_split(self, tol: N::Real)
+        -> NalgebraLapackResult<(DMatrix<N>, DMatrix<N>)> {
+        let m = self.nrows();
+        let (u, s, _) = N::svd(self)?;
+        let rank = s.iter().filter(|x| **x > tol).count();
+
+        let range = DMatrix::from_fn(m, rank, |i, j| u[(i, j)]);
+        let complement = DMatrix::from_fn(m, m - rank, |i, j| u[(i, rank + j)]);
+        Ok((range, complement))
+    }
+}
+
+/// A type for which a truncated singular value decomposition can be computed.
+pub trait TruncatedSvd {
+    type N: LapackScalar;
+
+    /// compute the singular value decomposition truncated to the fewest components that
+    /// capture a given fraction of the energy.
+    ///
+    /// The energy is the squared Frobenius norm, `sum s_i^2`. This keeps the smallest `k` such
+    /// that the `k` largest singular values have `sum_{i < k} s_i^2 >= fraction * sum s_i^2`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `fraction` - The fraction of the energy to keep, between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// * `svd` - The truncated decomposition, with `u` of size `m x k`, `s` of size `k` and
+    ///   `vt` of size `k x n`, or `None` if the SVD fails.
+    fn truncated_svd_energy(self, fraction: <Self::N as LapackScalar>::Real)
+        -> Option<SvdResult<Self::N>>;
+}
+
+impl<N: LapackScalar> TruncatedSvd for DMatrix<N>
+    where N::Real: Float
+{
+    type N = N;
+
+    fn truncated_svd_energy(self, fraction: N::Real) -> Option<SvdResult<N>> {
+        let (m, n) = (self.nrows(), self.ncols());
+        let (u, s, vt) = N::svd(self).ok()?;
+
+        let total = s.iter().fold(N::Real::zero(), |acc, x| acc + *x * *x);
+        let target = fraction * total;
+        let mut energy = N::Real::zero();
+        let mut k = 0;
+        while k < s.len() && energy < target {
+            energy = energy + s[k] * s[k];
+            k += 1;
+        }
+
+        Some(SvdResult {
+            u: Some(DMatrix::from_fn(m, k, |i, j| u[(i, j)])),
+            s: DVector { at: s.at[..k].to_vec() },
+            vt: Some(DMatrix::from_fn(k, n, |i, j| vt[(i, j)])),
+        })
+    }
+}
+
+/// A square matrix whose distance to the nearest singular matrix can be computed.
+pub trait DistanceToSingularity {
+    type N: LapackScalar;
+
+    /// compute the distance to the nearest singular matrix in the 2-norm.
+    ///
+    /// By the Eckart-Young theorem this is exactly the smallest singular value: removing it
+    /// from the SVD gives the nearest singular matrix. Relative to the largest singular value,
+    /// it is the reciprocal of the 2-norm condition number. Only the singular values are
+    /// computed.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `distance` - The smallest singular value. This is infinite for an empty matrix, which
+    ///   has no singular neighbor. `None` is returned if the matrix is not square or the SVD
+    ///   fails.
+    fn distance_to_singularity(self) -> Option<<Self::N as LapackScalar>::Real>;
+}
+
+impl<N: LapackScalar> DistanceToSingularity for DMatrix<N>
+    where N::Real: Float,
+          DMatrix<N>: SvdWith<N = N>
+{
+    type N = N;
+
+    fn distance_to_singularity(self) -> Option<N::Real> {
+        if self.nrows() != self.ncols() {
+            return None;
+        }
+        let options = SvdOptions { compute_u: false, compute_vt: false, ..SvdOptions::default() };
+        let s = self.svd_with(options).ok()?.s;
+        Some(s.at.last().cloned().unwrap_or(N::Real::infinity()))
+    }
+}
+
+/// A square matrix for which the 2-norm of its inverse can be computed.
+pub trait InverseOperatorNorm {
+    type N: LapackScalar;
+
+    /// compute the 2-norm `||A^-1||_2` of the inverse without forming it.
+    ///
+    /// This is the reciprocal of the smallest singular value, which is found with
+    /// `DistanceToSingularity::distance_to_singularity()`. It bounds the sensitivity of the
+    /// solution of `A x = b`: a perturbation `db` of the right-hand side changes `x` by at most
+    /// `||A^-1||_2 ||db||_2`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `norm` - The 2-norm of the inverse, or `None` if the matrix is not square, the SVD
+    ///   fails or the matrix is exactly singular.
+    fn inverse_operator_norm(self) -> Option<<Self::N as LapackScalar>::Real>;
+}
+
+impl<N: LapackScalar> InverseOperatorNorm for DMatrix<N>
+    where N::Real: Float,
+          DMatrix<N>: DistanceToSingularity<N = N>
+{
+    type N = N;
+
+    fn inverse_operator_norm(self) -> Option<N::Real> {
+        let s_min = self.distance_to_singularity()?;
+        if s_min == N::Real::zero() {
+            return None;
+        }
+        Some(s_min.recip())
+    }
+}
+
+/// A matrix whose pseudo-determinant can be computed.
+pub trait PseudoDeterminant {
+    type N: LapackScalar;
+
+    /// compute the pseudo-determinant, the product of the nonzero singular values.
+    ///
+    /// This is the normalization constant of a degenerate multivariate normal distribution,
+    /// whose covariance matrix is singular: its density on the support involves the
+    /// pseudo-determinant in place of the determinant. For a nonsingular square matrix it is
+    /// the absolute value of the determinant. Only the singular values are computed.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The singular values greater than `tol` are taken as nonzero.
+    ///
+    /// # Returns
+    ///
+    /// * `pdet` - The product of the singular values greater than `tol`, or one if there is
+    ///   none.
+    fn pseudo_determinant(self, tol: <Self::N as LapackScalar>::Real)
+        -> NalgebraLapackResult<<Self::N as LapackScalar>::Real>;
+}
+
+impl<N: LapackScalar> PseudoDeterminant for DMatrix<N>
+    where N::Real: Float,
+          DMatrix<N>: SvdWith<N = N>
+{
+    type N = N;
+
+    fn pseudo_determinant(self, tol: N::Real) -> NalgebraLapackResult<N::Real> {
+        let options = SvdOptions { compute_u: false, compute_vt: false, ..SvdOptions::default() };
+        let s = self.svd_with(options)?.s;
+        Ok(s.at.iter().filter(|x| **x > tol).fold(N::Real::one(), |acc, x| acc * *x))
+    }
+}
+
+/// A matrix for which the singular value decomposition of a block can be computed.
+pub trait BlockSvd {
+    type N: LapackScalar;
+
+    /// compute the singular value decomposition (SVD) of a block of this matrix.
+    ///
+    /// LAPACK needs the block in contiguous column-major storage, so only the requested block
+    /// is copied out of the larger matrix, column by column, and decomposed with `SVD::svd()`.
+    /// The matrix itself is left untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The rows of the block.
+    /// * `cols` - The columns of the block.
+    ///
+    /// # Returns
+    ///
+    /// The full SVD `(u, s, vt)` of the block, as from `SVD::svd()`. A `DimensionMismatch`
+    /// error is returned if a range extends past the matrix.
+    fn svd_of_block(&self, rows: Range<usize>, cols: Range<usize>)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DVector<<Self::N as LapackScalar>::Real>,
+                                 DMatrix<Self::N>)>;
+}
+
+impl<N: LapackScalar> BlockSvd for DMatrix<N> {
+    type N = N;
+
+    fn svd_of_block(&self, rows: Range<usize>, cols: Range<usize>)
+        -> NalgebraLapackResult<(DMatrix<N>, DVector<N::Real>, DMatrix<N>)> {
+        if rows.start > rows.end || rows.end > self.nrows() ||
+           cols.start > cols.end || cols.end > self.ncols() {
+            return Err(Error::from(ErrorKind::DimensionMismatch));
+        }
+        let (m, n) = (rows.end - rows.start, cols.end - cols.start);
+        let block = DMatrix::from_fn(m, n, |i, j| self[(rows.start + i, cols.start + j)]);
+        N::svd(block)
+    }
+}
+
+/// A double precision matrix whose singular value decomposition can be computed in single
+/// precision.
+pub trait MixedPrecisionSvd {
+    type N: LapackScalar;
+
+    /// compute the singular value decomposition (SVD) in single precision.
+    ///
+    /// The matrix is rounded to single precision, decomposed with `SVD::svd()`, and the
+    /// factors are converted back. This halves the memory and is typically much faster, at
+    /// the cost of accuracy: the singular values have an absolute error of about
+    /// `f32::EPSILON` times the largest one, rather than `f64::EPSILON`, and the singular
+    /// vectors lose correspondingly more when the gaps between singular values are small.
+    /// Matrices with entries outside the range of `f32` overflow.
+    ///
+    /// Because the input matrix is converted, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// The full SVD `(u, s, vt)`, as from `SVD::svd()`, accurate to single precision.
+    fn svd_mixed_precision(self)
+        -> NalgebraLapackResult<(DMatrix<Self::N>, DVector<<Self::N as LapackScalar>::Real>,
+                                 DMatrix<Self::N>)>;
+}
+
+impl MixedPrecisionSvd for DMatrix<f64> {
+    type N = f64;
+
+    fn svd_mixed_precision(self)
+        -> NalgebraLapackResult<(DMatrix<f64>, DVector<f64>, DMatrix<f64>)> {
+        let low = DMatrix::from_fn(self.nrows(), self.ncols(), |i, j| self[(i, j)] as f32);
+        let (u, s, vt) = f32::svd(low)?;
+        Ok((DMatrix::from_fn(u.nrows(), u.ncols(), |i, j| u[(i, j)] as f64),
+            DVector { at: s.at.iter().map(|x| *x as f64).collect() },
+            DMatrix::from_fn(vt.nrows(), vt.ncols(), |i, j| vt[(i, j)] as f64)))
+    }
+}
+
+impl MixedPrecisionSvd for DMatrix<Complex<f64>> {
+    type N = Complex<f64>;
+
+    fn svd_mixed_precision(self)
+        -> NalgebraLapackResult<(DMatrix<Complex<f64>>, DVector<f64>, DMatrix<Complex<f64>>)> {
+        let down = |x: Complex<f64>| Complex::new(x.re as f32, x.im as f32);
+        let up = |x: Complex<f32>| Complex::new(x.re as f64, x.im as f64);
+        let low = DMatrix::from_fn(self.nrows(), self.ncols(), |i, j| down(self[(i, j)]));
+        let (u, s, vt) = Complex::<f32>::svd(low)?;
+        Ok((DMatrix::from_fn(u.nrows(), u.ncols(), |i, j| up(u[(i, j)])),
+            DVector { at: s.at.iter().map(|x| *x as f64).collect() },
+            DMatrix::from_fn(vt.nrows(), vt.ncols(), |i, j| up(vt[(i, j)]))))
+    }
+}
+
+/// The LAPACK driver used to compute a singular value decomposition.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SvdAlgorithm {
+    /// The QR-iteration based driver `?gesvd`. This is what `SVD::svd()` uses.
+    Standard,
+    /// The divide-and-conquer driver `?gesdd`, which is usually faster for large matrices
+    /// when singular vectors are requested.
+    DivideAndConquer,
+}
+
+/// Options controlling which parts of a singular value decomposition are computed, and how.
+///
+/// The default computes full `u` and `vt` with `SvdAlgorithm::Standard`, as `SVD::svd()` does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SvdOptions {
+    /// Compute only the first `min(m, n)` singular vectors (the "thin" or economy SVD).
+    pub economy: bool,
+    /// The LAPACK driver to use.
+    pub algorithm: SvdAlgorithm,
+    /// Compute the left-singular vectors `u`.
+    pub compute_u: bool,
+    /// Compute the right-singular vectors `vt`.
+    pub compute_vt: bool,
+    /// Compute one set of singular vectors in the storage of the input matrix rather than in
+    /// a separate allocation: `u` if `m >= n`, otherwise `vt`. The returned matrix for that set
+    /// is the input storage itself, and has the economy shape. This selects `jobz = 'O'` for
+    /// `?gesdd` and `jobu = 'O'` or `jobvt = 'O'` for `?gesvd`.
+    pub overwrite: bool,
+}
+
+impl Default for SvdOptions {
+    fn default() -> SvdOptions {
+        SvdOptions {
+            economy: false,
+            algorithm: SvdAlgorithm::Standard,
+            compute_u: true,
+            compute_vt: true,
+            overwrite: false,
+        }
+    }
+}
+
+/// A singular value decomposition `a = u diag(s) vt` computed with `SvdWith`.
+#[derive(Clone)]
+pub struct SvdResult<N: LapackScalar> {
+    /// The left-singular vectors as columns, if they were requested.
+    pub u: Option<DMatrix<N>>,
+    /// The singular values, in descending order.
+    pub s: DVector<N::Real>,
+    /// The right-singular vectors as rows, if they were requested.
+    pub vt: Option<DMatrix<N>>,
+}
+
+/// A type for which a singular value decomposition can be computed with explicit options.
+pub trait SvdWith {
+    type N: LapackScalar;
+
+    /// compute the singular value decomposition (SVD) with the given options.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Which parts of the decomposition to compute, and how.
+    ///
+    /// # Returns
+    ///
+    /// * `svd` - The decomposition. With `economy`, `u` is `m x min(m, n)` and `vt` is
+    ///   `min(m, n) x n`; otherwise they are square.
+    fn svd_with(self, options: SvdOptions) -> NalgebraLapackResult<SvdResult<Self::N>>;
+
+    /// return a builder to configure and compute the singular value decomposition.
+    fn svd_builder(self) -> SvdBuilder<Self::N>;
+
+    /// compute the singular value decomposition, choosing the economy or the full shape from
+    /// the aspect ratio of the matrix.
+    ///
+    /// The full `u` of a tall matrix, or `vt` of a wide one, is mostly made of singular vectors
+    /// of zero singular values, and can be huge: `1000 x 1000` for a `1000 x 10` matrix. The
+    /// economy SVD is computed when `max(m, n)` exceeds `SMART_SVD_ASPECT_RATIO` (2) times
+    /// `min(m, n)`, and the full SVD otherwise. Both sets of singular vectors are computed
+    /// with `SvdAlgorithm::Standard`.
+    ///
+    /// LAPACK's bidiagonalization works from the tall side, so a wide matrix is decomposed as
+    /// its conjugate transpose: if `A^H = U' S V'^H`, then `A = V' S U'^H`, and the factors are
+    /// swapped and conjugate-transposed back. The result is mathematically identical to that
+    /// of `svd_with()`, up to the usual phase of each pair of singular vectors.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `svd` - The decomposition. The shapes of `u` and `vt` tell which one was computed.
+    fn svd_smart(self) -> NalgebraLapackResult<SvdResult<Self::N>>;
+}
+
+/// The aspect ratio `max(m, n) / min(m, n)` above which `SvdWith::svd_smart()` computes the
+/// economy SVD.
+pub const SMART_SVD_ASPECT_RATIO: usize = 2;
+
+/// Whether `SvdWith::svd_smart()` computes the economy SVD of an `m x n` matrix.
+fn smart_economy(m: usize, n: usize) -> bool {
+    let (small, large) = if m <= n { (m, n) } else { (n, m) };
+    large > SMART_SVD_ASPECT_RATIO * small
+}
+
+/// The conjugate transpose `A^H` of a complex matrix.
+fn conjugate_transpose<T: Float>(a: &DMatrix<Complex<T>>) -> DMatrix<Complex<T>> {
+    DMatrix::from_fn(a.ncols(), a.nrows(), |i, j| a[(j, i)].conj())
+}
+
+/// Compute the SVD of a 2x2 or 3x3 real matrix without LAPACK, or `None` for other sizes.
+///
+/// The right singular vectors `V` are the eigenvectors of `A^T A`, from the closed-form
+/// symmetric eigensolver, and the columns `b_i` of `B = A V` are `s_i u_i`. Rather than
+/// taking square roots of the eigenvalues, which loses the small singular values, `u_1` is
+/// `b_1` normalized and the later `u_i` are the components of `b_i` orthogonal to the
+/// previous ones, completed to an orthonormal basis when `b_i` vanishes. Each `s_i = u_i . b_i`
+/// is then accurate to about `eps s_1^2 / s_{n-1}`, since `A^T A` only separates the right
+/// singular vectors to about `eps s_1^2`. That is `eps s_1` for a 2x2 matrix, but a 3x3 matrix
+/// with `s_2 < s_1 / 10` returns `None`, for the caller to fall back to LAPACK, which keeps the
+/// error within `10 eps s_1`. Forming `A^T A` squares the dynamic range, so `A` is first divided
+/// by its largest entry in magnitude, and the singular values are scaled back. `None` is also
+/// returned for non-finite entries or a non-finite result.
+pub fn closed_form_svd<N>(a: &DMatrix<N>) -> Option<(DMatrix<N>, DVector<N>, DMatrix<N>)>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    let n = a.nrows();
+    if a.ncols() != n || (n != 2 && n != 3) {
+        return None;
+    }
+    let scale = a.as_vector().iter().fold(N::zero(), |acc, x| Float::max(acc, Float::abs(*x)));
+    if scale == N::zero() || !Float::is_finite(scale) {
+        return None;
+    }
+    let a = &DMatrix::from_fn(n, n, |i, j| a[(i, j)] / scale);
+    let ata = DMatrix::from_fn(n, n, |i, j| {
+        (0..n).fold(N::zero(), |acc, k| acc + a[(k, i)] * a[(k, j)])
+    });
+    let (lambda, ascending) = ata.symmetric_eigensystem_closed_form()?;
+    if n == 3 && lambda[1] * <N as Cast<f64>>::from(100.0) < lambda[2] {
+        return None;
+    }
+    // The eigenvalues are ascending, the singular values descending.
+    let v = DMatrix::from_fn(n, n, |i, j| ascending[(i, n - 1 - j)]);
+    let b: Vec<Vec<N>> = (0..n)
+        .map(|j| {
+            (0..n).map(|i| (0..n).fold(N::zero(), |acc, k| acc + a[(i, k)] * v[(k, j)])).collect()
+        })
+        .collect();
+
+    let dot = |x: &[N], y: &[N]| {
+        x.iter().zip(y.iter()).fold(N::zero(), |acc, (p, q)| acc + *p * *q)
+    };
+    let norm = |x: &[N]| dot(x, x).sqrt();
+    let s1 = norm(&b[0]);
+    if s1 == N::zero() {
+        return None;
+    }
+    let tiny = <N as Float>::epsilon() * s1;
+    let mut u: Vec<Vec<N>> = vec![b[0].iter().map(|x| *x / s1).collect()];
+    for (j, bj) in b.iter().enumerate().skip(1) {
+        // The component of `b_j` orthogonal to the previous `u` (once reorthogonalized).
+        let mut w = bj.clone();
+        for _ in 0..2 {
+            for q in u.iter() {
+                let c = dot(&w, q);
+                for (wi, qi) in w.iter_mut().zip(q.iter()) {
+                    *wi -= c * *qi;
+                }
+            }
+        }
+        let w_norm = norm(&w);
+        let mut uj: Vec<N> = if w_norm > tiny {
+            w.iter().map(|x| *x / w_norm).collect()
+        } else if j == 1 && n == 3 {
+            // Excluded above, `s_2` is at least `s_1 / 10`.
+            return None;
+        } else if n == 2 {
+            vec![-u[0][1], u[0][0]]
+        } else {
+            vec![u[0][1] * u[1][2] - u[0][2] * u[1][1],
+                 u[0][2] * u[1][0] - u[0][0] * u[1][2],
+                 u[0][0] * u[1][1] - u[0][1] * u[1][0]]
+        };
+        if dot(&uj, bj) < N::zero() {
+            for x in uj.iter_mut() {
+                *x = -*x;
+            }
+        }
+        u.push(uj);
+    }
+
+    let s: Vec<N> = (0..n).map(|j| dot(&u[j], &b[j]) * scale).collect();
+    let u = DMatrix::from_fn(n, n, |i, j| u[j][i]);
+    let finite = s.iter()
+        .chain(u.as_vector().iter())
+        .chain(v.as_vector().iter())
+        .all(|x| Float::is_finite(*x));
+    if !finite {
+        return None;
+    }
+    Some((u, DVector { at: s }, v.transpose()))
+}
+
+/// A builder for the options of a singular value decomposition, obtained from
+/// `SvdWith::svd_builder()`.
+///
+/// # Examples
+///
+/// ```rust
+/// extern crate nalgebra_lapack;
+/// extern crate nalgebra as na;
+///
+/// use nalgebra_lapack::{SvdWith, SvdAlgorithm};
+/// use na::DMatrix;
+///
+/// fn main() {
+///     let m = DMatrix::from_row_vector(3, 2, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+///     let svd = m.svd_builder()
+///         .economy()
+///         .algorithm(SvdAlgorithm::DivideAndConquer)
+///         .compute_vt(false)
+///         .run()
+///         .unwrap();
+///     assert_eq!(svd.u.unwrap().ncols(), 2);
+///     assert!(svd.vt.is_none());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SvdBuilder<N> {
+    m: DMatrix<N>,
+    options: SvdOptions,
+}
+
+impl<N> SvdBuilder<N> {
+    /// compute only the first `min(m, n)` singular vectors.
+    pub fn economy(mut self) -> SvdBuilder<N> {
+        self.options.economy = true;
+        self
+    }
+
+    /// select the LAPACK driver.
+    pub fn algorithm(mut self, algorithm: SvdAlgorithm) -> SvdBuilder<N> {
+        self.options.algorithm = algorithm;
+        self
+    }
+
+    /// select whether the left-singular vectors are computed.
+    pub fn compute_u(mut self, compute_u: bool) -> SvdBuilder<N> {
+        self.options.compute_u = compute_u;
+        self
+    }
+
+    /// select whether the right-singular vectors are computed.
+    pub fn compute_vt(mut self, compute_vt: bool) -> SvdBuilder<N> {
+        self.options.compute_vt = compute_vt;
+        self
+    }
+
+    /// compute one set of singular vectors in the storage of the input matrix.
+    ///
+    /// See `SvdOptions::overwrite`.
+    pub fn overwrite(mut self) -> SvdBuilder<N> {
+        self.options.overwrite = true;
+        self
+    }
+
+    /// return the options selected so far.
+    pub fn options(&self) -> SvdOptions {
+        self.options
+    }
+}
+
+impl<N: LapackScalar> SvdBuilder<N>
+    where DMatrix<N>: SvdWith<N = N>
+{
+    /// compute the singular value decomposition with the selected options.
+    pub fn run(self) -> NalgebraLapackResult<SvdResult<N>> {
+        self.m.svd_with(self.options)
+    }
+}
+
+/// Return the decomposition of a matrix that is empty or all zero, for which the singular
+/// vectors are arbitrary, or `None` if the matrix has a nonzero element.
+fn trivial_svd_with<N>(a: &DMatrix<N>, options: &SvdOptions) -> Option<SvdResult<N>>
+    where N: LapackScalar + Zero + One + PartialEq,
+          N::Real: Zero
+{
+    if a.as_vector().iter().any(|x| *x != N::zero()) {
+        return None;
+    }
+    let (m, n) = (a.nrows(), a.ncols());
+    let k = if m <= n { m } else { n };
+    let thin = options.economy || options.overwrite;
+    let eye = |r, c| DMatrix::from_fn(r, c, |i, j| if i == j { N::one() } else { N::zero() });
+    Some(SvdResult {
+        u: if options.compute_u { Some(eye(m, if thin { k } else { m })) } else { None },
+        s: DVector { at: (0..k).map(|_| N::Real::zero()).collect() },
+        vt: if options.compute_vt { Some(eye(if thin { k } else { n }, n)) } else { None },
+    })
+}
+
+/// How the singular vectors are requested from LAPACK and where they are returned.
+struct SvdJobs {
+    /// `jobu` for `?gesvd`, or `jobz` for `?gesdd`.
+    jobu: u8,
+    /// `jobvt` for `?gesvd`; the same as `jobu` for `?gesdd`.
+    jobvt: u8,
+    /// The number of columns of the separately allocated `u`, or 0 if it is not referenced.
+    ucols: usize,
+    /// The number of rows of the separately allocated `vt`, or 0 if it is not referenced.
+    vtrows: usize,
+    /// `u` is returned in the storage of the input matrix.
+    u_in_a: bool,
+    /// `vt` is returned in the storage of the input matrix.
+    vt_in_a: bool,
+}
+
+impl SvdJobs {
+    fn new(options: &SvdOptions, m: usize, n: usize) -> SvdJobs {
+        let k = if m <= n { m } else { n };
+        let full_or_economy = |full| if options.economy { (b'S', k) } else { (b'A', full) };
+        match options.algorithm {
+            SvdAlgorithm::Standard => {
+                let u_in_a = options.overwrite && options.compute_u && m >= n;
+                let vt_in_a = options.overwrite && options.compute_vt && m < n;
+                let (jobu, ucols) = if !options.compute_u {
+                    (b'N', 0)
+                } else if u_in_a {
+                    (b'O', 0)
+                } else {
+                    full_or_economy(m)
+                };
+                let (jobvt, vtrows) = if !options.compute_vt {
+                    (b'N', 0)
+                } else if vt_in_a {
+                    (b'O', 0)
+                } else {
+                    full_or_economy(n)
+                };
+                SvdJobs {
+                    jobu,
+                    jobvt,
+                    ucols,
+                    vtrows,
+                    u_in_a,
+                    vt_in_a,
+                }
+            }
+            SvdAlgorithm::DivideAndConquer => {
+                // Either both sets of singular vectors are computed or neither.
+                let (jobz, ucols, vtrows, u_in_a, vt_in_a) = if !options.compute_u &&
+                                                                !options.compute_vt {
+                    (b'N', 0, 0, false, false)
+                } else if options.overwrite && m >= n {
+                    (b'O', 0, n, true, false)
+                } else if options.overwrite {
+                    (b'O', m, 0, false, true)
+                } else if options.economy {
+                    (b'S', k, k, false, false)
+                } else {
+                    (b'A', m, n, false, false)
+                };
+                SvdJobs {
+                    jobu: jobz,
+                    jobvt: jobz,
+                    ucols,
+                    vtrows,
+                    u_in_a,
+                    vt_in_a,
+                }
+            }
+        }
+    }
+}
+
+macro_rules! svd_with_impl(
+    ($t: ty, $gesvd: path, $gesdd: path) => (
+        impl SvdWith for DMatrix<$t> {
+            type N = $t;
+
+            fn svd_with(self, options: SvdOptions) -> NalgebraLapackResult<SvdResult<$t>> {
+                if let Some(result) = trivial_svd_with(&self, &options) {
+                    return Ok(result);
+                }
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                let k = if m <= n { m } else { n };
+                let lda = m as i32;
+
+                let jobs = SvdJobs::new(&options, m, n);
+                let (jobu, jobvt, ucols, vtrows) = (jobs.jobu, jobs.jobvt, jobs.ucols, jobs.vtrows);
+                let ldu = if ucols == 0 { 1 } else { m };
+                let ldvt = if vtrows == 0 { 1 } else { vtrows };
+
+                let mut s: Vec<$t> = vec![0.0; k];
+                let mut u: Vec<$t> = vec![0.0; ::std::cmp::max(1, ldu * ucols)];
+                let mut vt: Vec<$t> = vec![0.0; ::std::cmp::max(1, ldvt * n)];
+                let mut work: Vec<$t> = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                match options.algorithm {
+                    SvdAlgorithm::Standard => {
+                        $gesvd(jobu, jobvt, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut info);
+                        check_info!(info);
+
+                        lwork = work[0] as i32;
+                        work = vec![0.0; lwork as usize];
+
+                        ::diagnostics::record(stringify!($gesvd), m, n, &[jobu, jobvt]);
+                        $gesvd(jobu, jobvt, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut info);
+                        check_info!(info);
+                    }
+                    SvdAlgorithm::DivideAndConquer => {
+                        let mut iwork: Vec<i32> = vec![0; 8 * k];
+
+                        $gesdd(jobu, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut iwork, &mut info);
+                        check_info!(info);
+
+                        lwork = work[0] as i32;
+                        work = vec![0.0; lwork as usize];
+
+                        ::diagnostics::record(stringify!($gesdd), m, n, &[jobu]);
+                        $gesdd(jobu, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut iwork, &mut info);
+                        check_info!(info);
+                    }
+                }
+
+                // When one set of singular vectors overwrites the input, the input is exactly
+                // `m x k` (for `u`) or `k x n` (for `vt`) and is returned as is.
+                let mut a = Some(a);
+                Ok(SvdResult {
+                    u: if !options.compute_u {
+                        None
+                    } else if jobs.u_in_a {
+                        a.take()
+                    } else {
+                        Some(DMatrix::from_column_vector(m, ucols, &u[..ldu * ucols]))
+                    },
+                    s: DVector { at: s },
+                    vt: if !options.compute_vt {
+                        None
+                    } else if jobs.vt_in_a {
+                        a.take()
+                    } else {
+                        Some(DMatrix::from_column_vector(vtrows, n, &vt[..ldvt * n]))
+                    },
+                })
+            }
+
+            fn svd_builder(self) -> SvdBuilder<$t> {
+                SvdBuilder { m: self, options: SvdOptions::default() }
+            }
+
+            fn svd_smart(self) -> NalgebraLapackResult<SvdResult<$t>> {
+                let (m, n) = (self.nrows(), self.ncols());
+                let options = SvdOptions { economy: smart_economy(m, n), ..SvdOptions::default() };
+                if m >= n {
+                    return self.svd_with(options);
+                }
+                let svd = self.transpose().svd_with(options)?;
+                Ok(SvdResult {
+                    u: svd.vt.map(|vt| vt.transpose()),
+                    s: svd.s,
+                    vt: svd.u.map(|u| u.transpose()),
+                })
+            }
+        }
+    );
+);
+
+macro_rules! svd_with_complex_impl(
+    ($t: ty, $gesvd: path, $gesdd: path) => (
+        impl SvdWith for DMatrix<Complex<$t>> {
+            type N = Complex<$t>;
+
+            fn svd_with(self, options: SvdOptions)
+                -> NalgebraLapackResult<SvdResult<Complex<$t>>> {
+                if let Some(result) = trivial_svd_with(&self, &options) {
+                    return Ok(result);
+                }
+                let mut a = self;
+                let m = a.nrows();
+                let n = a.ncols();
+                let k = if m <= n { m } else { n };
+                let lda = m as i32;
+
+                let jobs = SvdJobs::new(&options, m, n);
+                let (jobu, jobvt, ucols, vtrows) = (jobs.jobu, jobs.jobvt, jobs.ucols, jobs.vtrows);
+                let ldu = if ucols == 0 { 1 } else { m };
+                let ldvt = if vtrows == 0 { 1 } else { vtrows };
+
+                let zero = Complex { re: 0.0, im: 0.0 };
+                let mut s: Vec<$t> = vec![0.0; k];
+                let mut u: Vec<Complex<$t>> = vec![zero; ::std::cmp::max(1, ldu * ucols)];
+                let mut vt: Vec<Complex<$t>> = vec![zero; ::std::cmp::max(1, ldvt * n)];
+                let mut work: Vec<Complex<$t>> = vec![zero];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                match options.algorithm {
+                    SvdAlgorithm::Standard => {
+                        let mut rwork: Vec<$t> = vec![0.0; 5 * k];
+
+                        $gesvd(jobu, jobvt, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut rwork, &mut info);
+                        check_info!(info);
+
+                        lwork = work[0].re as i32;
+                        work = vec![zero; lwork as usize];
+
+                        ::diagnostics::record(stringify!($gesvd), m, n, &[jobu, jobvt]);
+                        $gesvd(jobu, jobvt, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut rwork, &mut info);
+                        check_info!(info);
+                    }
+                    SvdAlgorithm::DivideAndConquer => {
+                        let mx = if m <= n { n } else { m };
+                        let lrwork = if jobu == b'N' {
+                            7 * k
+                        } else {
+                            ::std::cmp::max(5 * k * k + 5 * k, 2 * mx * k + 2 * k * k + k)
+                        };
+                        let mut rwork: Vec<$t> = vec![0.0; lrwork];
+                        let mut iwork: Vec<i32> = vec![0; 8 * k];
+
+                        $gesdd(jobu, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut rwork, &mut iwork, &mut info);
+                        check_info!(info);
+
+                        lwork = work[0].re as i32;
+                        work = vec![zero; lwork as usize];
+
+                        ::diagnostics::record(stringify!($gesdd), m, n, &[jobu]);
+                        $gesdd(jobu, m as i32, n as i32, a.as_mut_vector(), lda, &mut s,
+                            &mut u, ldu as i32, &mut vt, ldvt as i32, &mut work, lwork,
+                            &mut rwork, &mut iwork, &mut info);
+                        check_info!(info);
+                    }
+                }
+
+                // When one set of singular vectors overwrites the input, the input is exactly
+                // `m x k` (for `u`) or `k x n` (for `vt`) and is returned as is.
+                let mut a = Some(a);
+                Ok(SvdResult {
+                    u: if !options.compute_u {
+                        None
+                    } else if jobs.u_in_a {
+                        a.take()
+                    } else {
+                        Some(DMatrix::from_column_vector(m, ucols, &u[..ldu * ucols]))
+                    },
+                    s: DVector { at: s },
+                    vt: if !options.compute_vt {
+                        None
+                    } else if jobs.vt_in_a {
+                        a.take()
+                    } else {
+                        Some(DMatrix::from_column_vector(vtrows, n, &vt[..ldvt * n]))
+                    },
+                })
+            }
+
+            fn svd_builder(self) -> SvdBuilder<Complex<$t>> {
+                SvdBuilder { m: self, options: SvdOptions::default() }
+            }
+
+            fn svd_smart(self) -> NalgebraLapackResult<SvdResult<Complex<$t>>> {
+                let (m, n) = (self.nrows(), self.ncols());
+                let options = SvdOptions { economy: smart_economy(m, n), ..SvdOptions::default() };
+                if m >= n {
+                    return self.svd_with(options);
+                }
+                let svd = conjugate_transpose(&self).svd_with(options)?;
+                Ok(SvdResult {
+                    u: svd.vt.map(|vt| conjugate_transpose(&vt)),
+                    s: svd.s,
+                    vt: svd.u.map(|u| conjugate_transpose(&u)),
+                })
+            }
+        }
+    );
+);
+
+impl<N: LapackScalar> SvdResult<N> {
+    /// convert into flat buffers, for handing the decomposition to code that does not use
+    /// nalgebra.
+    ///
+    /// # Returns
+    ///
+    /// * `u` - The left-singular vectors in column-major order, or an empty buffer if they were
+    ///   not computed.
+    /// * `s` - The singular values.
+    /// * `vt` - The right-singular vectors in column-major order, or an empty buffer if they
+    ///   were not computed.
+    /// * `m` - The number of rows of the decomposed matrix, which is also that of `u`.
+    /// * `n` - The number of columns of the decomposed matrix, which is also that of `vt`.
+    pub fn into_raw(self) -> (Vec<N>, Vec<N::Real>, Vec<N>, usize, usize) {
+        let m = self.u.as_ref().map(|u| u.nrows());
+        let n = self.vt.as_ref().map(|vt| vt.ncols());
+        let k = self.s.len();
+        // Without the singular vectors, the economy shape is the best guess of the shape.
+        let (m, n) = (m.unwrap_or(k), n.unwrap_or(k));
+        (self.u.map(|u| u.into_vector()).unwrap_or(vec![]),
+         self.s.at,
+         self.vt.map(|vt| vt.into_vector()).unwrap_or(vec![]),
+         m,
+         n)
+    }
+
+    /// build from the flat buffers returned by `into_raw()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `u` - The left-singular vectors in column-major order, with `m` rows. An empty buffer
+    ///   means that they were not computed.
+    /// * `s` - The singular values.
+    /// * `vt` - The right-singular vectors in column-major order, with `n` columns. An empty
+    ///   buffer means that they were not computed.
+    /// * `m` - The number of rows of `u`.
+    /// * `n` - The number of columns of `vt`.
+    ///
+    /// # Returns
+    ///
+    /// * `svd` - The decomposition. A `DimensionMismatch` error is returned if the buffer
+    ///   lengths are not multiples of `m` and `n`, or do not have at least `s.len()` columns of
+    ///   `u` and rows of `vt`.
+    pub fn from_raw(u: Vec<N>, s: Vec<N::Real>, vt: Vec<N>, m: usize, n: usize)
+        -> NalgebraLapackResult<SvdResult<N>> {
+        let k = s.len();
+        let shape = |len: usize, dim: usize| {
+            if len == 0 {
+                Ok(None)
+            } else if dim == 0 || len % dim != 0 || len / dim < k {
+                Err(Error::from(ErrorKind::DimensionMismatch))
+            } else {
+                Ok(Some(len / dim))
+            }
+        };
+        let ucols = shape(u.len(), m)?;
+        let vtrows = shape(vt.len(), n)?;
+        Ok(SvdResult {
+            u: ucols.map(|c| DMatrix::from_column_iter(m, c, u)),
+            s: DVector { at: s },
+            vt: vtrows.map(|r| DMatrix::from_column_iter(r, n, vt)),
+        })
+    }
+}
+
+impl<N: LapackScalar> SvdResult<N>
+    where N::Real: Float
+{
+    /// compute the error of the best rank-`k` approximation in the Frobenius norm.
+    ///
+    /// By the Eckart-Young theorem, keeping the `k` largest singular values gives the best
+    /// rank-`k` approximation `A_k`, and `||A - A_k||_F` is exactly the square root of the sum
+    /// of squares of the discarded singular values.
+    ///
+    /// # Arguments
+    ///
+    /// * `k` - The rank of the approximation.
+    ///
+    /// # Returns
+    ///
+    /// * `error` - The Frobenius norm of the error; zero if `k` is at least the number of
+    ///   singular values.
+    pub fn truncation_error(&self, k: usize) -> N::Real {
+        let discarded = if k < self.s.len() { &self.s.at[k..] } else { &[] };
+        discarded.iter().fold(N::Real::zero(), |acc, x| acc + *x * *x).sqrt()
+    }
+
+    /// suggest the numerical rank from the largest relative gap in the singular values.
+    ///
+    /// This automates looking for the "elbow" of the spectrum, e.g. to separate signal from
+    /// noise before denoising: the suggested rank is the `k` maximizing `s[k - 1] / s[k]`. A
+    /// singular value followed by an exact zero is an infinite gap.
+    ///
+    /// # Returns
+    ///
+    /// * `rank` - The suggested rank. Without any gap to compare, i.e. with fewer than two
+    ///   singular values or all of them zero, this is the number of nonzero singular values.
+    /// * `ratio` - The gap ratio `s[rank - 1] / s[rank]`, or one without any gap.
+    pub fn rank_gap(&self) -> (usize, N::Real) {
+        let s = &self.s.at;
+        let mut best: Option<(usize, N::Real)> = None;
+        for k in 1..s.len() {
+            if s[k - 1] > N::Real::zero() {
+                let ratio = s[k - 1] / s[k];
+                if best.map_or(true, |(_, r)| ratio > r) {
+                    best = Some((k, ratio));
+                }
+            }
+        }
+        best.unwrap_or_else(|| {
+            (s.iter().filter(|x| **x > N::Real::zero()).count(), N::Real::one())
+        })
+    }
+}
+
+impl<N> SvdResult<N>
+    where N: LapackScalar<Real = N> + Float
+{
+    /// make the singular vectors of repeated singular values reproducible.
+    ///
+    /// The singular vectors of a repeated singular value are only determined up to a rotation
+    /// within their subspace, so different LAPACK implementations may return different ones.
+    /// This replaces them by a basis chosen by a deterministic rule, applying the same rotation
+    /// to `u` and `vt` so that the decomposition still reconstructs the same matrix. The rows of
+    /// the `m x g` block of `u` are selected by pivoted Gram-Schmidt (a rank-revealing QR) and
+    /// the basis is rotated so that, in those rows, the block is lower triangular with a
+    /// positive diagonal.
+    ///
+    /// Singular vectors of simple singular values are left unchanged. Nothing is done if `u`
+    /// or `vt` was not computed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Consecutive singular values that differ by at most `tol` times the largest
+    ///   one are treated as equal.
+    pub fn canonicalize_degenerate(&mut self, tol: N) {
+        let (u, vt) = match (self.u.as_mut(), self.vt.as_mut()) {
+            (Some(u), Some(vt)) => (u, vt),
+            _ => return,
+        };
+        let k = self.s.len();
+        let mut start = 0;
+        while start < k {
+            let mut end = start + 1;
+            while end < k && Float::abs(self.s[end - 1] - self.s[end]) <= tol * self.s[0] {
+                end += 1;
+            }
+            if end - start > 1 {
+                let q = canonical_rotation(u, start, end);
+                rotate_columns(u, start, &q);
+                let mut v = vt.transpose();
+                rotate_columns(&mut v, start, &q);
+                *vt = v.transpose();
+            }
+            start = end;
+        }
+    }
+
+    /// flip the signs of the singular vectors to a fixed convention, which eases comparison
+    /// with results from NumPy or MATLAB.
+    ///
+    /// Each left singular vector is negated if its largest-magnitude element (the first one in
+    /// case of ties) is negative, and so is the corresponding right singular vector so that the
+    /// decomposition still reconstructs the same matrix. This removes the sign ambiguity of the
+    /// singular vectors of simple singular values; see `canonicalize_degenerate()` for repeated
+    /// ones.
+    ///
+    /// Nothing is done if `u` was not computed.
+    pub fn apply_sign_convention(&mut self) {
+        let u = match self.u.as_mut() {
+            Some(u) => u,
+            None => return,
+        };
+        for j in 0..self.s.len() {
+            let mut largest = N::zero();
+            for i in 0..u.nrows() {
+                if Float::abs(u[(i, j)]) > Float::abs(largest) {
+                    largest = u[(i, j)];
+                }
+            }
+            if largest < N::zero() {
+                for i in 0..u.nrows() {
+                    u[(i, j)] = -u[(i, j)];
+                }
+                if let Some(vt) = self.vt.as_mut() {
+                    for k in 0..vt.ncols() {
+                        vt[(j, k)] = -vt[(j, k)];
+                    }
+                }
+            }
+        }
+    }
+
+    /// measure how far the computed singular vectors are from being orthonormal.
+    ///
+    /// A backward-stable SVD has singular vectors that are orthonormal to about `eps` times a
+    /// modest multiple of the dimension, so a larger value indicates a degraded decomposition,
+    /// e.g. one that was assembled by hand or went through a lossy conversion.
+    ///
+    /// # Returns
+    ///
+    /// * `u_error` - `||U^T U - I||` in the Frobenius norm, or zero if `u` was not computed.
+    /// * `v_error` - `||V^T V - I||` in the Frobenius norm, or zero if `vt` was not computed.
+    pub fn orthogonality_error(&self) -> (N, N) {
+        let u_error = self.u.as_ref().map_or(N::zero(), |u| column_orthonormality_error(u));
+        let v_error = self.vt.as_ref()
+            .map_or(N::zero(), |vt| column_orthonormality_error(&vt.transpose()));
+        (u_error, v_error)
+    }
+}
+
+/// `||Q^T Q - I||` in the Frobenius norm.
+fn column_orthonormality_error<N: Float>(q: &DMatrix<N>) -> N {
+    let k = q.ncols();
+    let mut sum = N::zero();
+    for i in 0..k {
+        for j in 0..k {
+            let dot = (0..q.nrows()).fold(N::zero(), |acc, r| acc + q[(r, i)] * q[(r, j)]);
+            let d = if i == j { dot - N::one() } else { dot };
+            sum = sum + d * d;
+        }
+    }
+    sum.sqrt()
+}
+
+/// The `g x g` rotation `Q` that makes the columns `start..end` of `u`, in the rows selected by
+/// pivoted Gram-Schmidt, lower triangular with a positive diagonal.
+fn canonical_rotation<N: Float>(u: &DMatrix<N>, start: usize, end: usize) -> Vec<Vec<N>> {
+    let g = end - start;
+    let mut residuals: Vec<Vec<N>> = (0..u.nrows())
+        .map(|i| (start..end).map(|j| u[(i, j)]).collect())
+        .collect();
+    let norm2 = |x: &Vec<N>| x.iter().fold(N::zero(), |acc, y| acc + *y * *y);
+
+    let mut q: Vec<Vec<N>> = Vec::with_capacity(g);
+    for _ in 0..g {
+        // The row with the largest residual, the first one in case of ties.
+        let mut pivot = 0;
+        for i in 1..residuals.len() {
+            if norm2(&residuals[i]) > norm2(&residuals[pivot]) {
+                pivot = i;
+            }
+        }
+        let norm = norm2(&residuals[pivot]).sqrt();
+        let direction: Vec<N> = residuals[pivot].iter().map(|x| *x / norm).collect();
+        for r in residuals.iter_mut() {
+            let dot = r.iter().zip(direction.iter()).fold(N::zero(), |acc, (a, b)| acc + *a * *b);
+            for (x, d) in r.iter_mut().zip(direction.iter()) {
+                *x = *x - dot * *d;
+            }
+        }
+        q.push(direction);
+    }
+    q
+}
+
+/// Replace the columns `start..start + g` of `m` by their product with `Q`, whose columns are
+/// `q[0], ..., q[g - 1]`.
+fn rotate_columns<N: Float>(m: &mut DMatrix<N>, start: usize, q: &[Vec<N>]) {
+    let g = q.len();
+    for i in 0..m.nrows() {
+        let row: Vec<N> = (0..g).map(|j| m[(i, start + j)]).collect();
+        for c in 0..g {
+            m[(i, start + c)] = (0..g).fold(N::zero(), |acc, j| acc + row[j] * q[c][j]);
+        }
+    }
+}
+
+/// The product `x * y` as an unevaluated sum `p + e` of two floats, using a fused multiply-add.
+fn two_product<T: Float>(x: T, y: T) -> (T, T) {
+    let p = x * y;
+    (p, x.mul_add(y, -p))
+}
+
+/// Compute `sum_k (x_k + ex_k) * y_k`, where `ex_k` is the (small) rounding error of `x_k`.
+///
+/// The products and the running sum are kept as unevaluated sums of two floats (the `Dot2`
+/// algorithm of Ogita, Rump and Oishi, 2005), so the result is about as accurate as if it had
+/// been computed in twice the working precision and then rounded.
+fn accurate_dot<T: Float>(terms: &[(T, T, T)]) -> T {
+    let mut sum = T::zero();
+    let mut err = T::zero();
+    for &(x, ex, y) in terms {
+        let (p, ep) = two_product(x, y);
+        let s = sum + p;
+        let z = s - sum;
+        let es = (sum - (s - z)) + (p - z);
+        sum = s;
+        err = err + es + ep + ex * y;
+    }
+    sum + err
+}
+
+/// The indices of `s` ordered from the largest value to the smallest.
+fn largest_first<T: Float>(s: &DVector<T>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..s.len()).collect();
+    order.sort_by(|&a, &b| s[b].partial_cmp(&s[a]).unwrap_or(::std::cmp::Ordering::Equal));
+    order
+}
+
+macro_rules! svd_result_impl(
+    ($t: ty) => (
+        impl SvdResult<$t> {
+            /// reconstruct the matrix `u diag(s) vt` from its decomposition.
+            ///
+            /// Each element is accumulated from the largest singular value to the smallest,
+            /// with the products and partial sums carried in about twice the working
+            /// precision. Unlike a plain matrix product, this keeps the contribution of small
+            /// singular values when the singular values span many orders of magnitude.
+            ///
+            /// The decomposition is only borrowed, so its factors can still be used, and the
+            /// matrix reconstructed again, afterwards.
+            ///
+            /// # Returns
+            ///
+            /// `None` if `u` or `vt` was not computed, otherwise the reconstructed matrix.
+            pub fn reconstruct(&self) -> Option<DMatrix<$t>> {
+                let (u, vt) = match (self.u.as_ref(), self.vt.as_ref()) {
+                    (Some(u), Some(vt)) => (u, vt),
+                    _ => return None,
+                };
+                let order = largest_first(&self.s);
+                let mut terms = Vec::with_capacity(order.len());
+                Some(DMatrix::from_fn(u.nrows(), vt.ncols(), |i, j| {
+                    terms.clear();
+                    for &k in &order {
+                        let (x, ex) = two_product(u[(i, k)], self.s[k]);
+                        terms.push((x, ex, vt[(k, j)]));
+                    }
+                    accurate_dot(&terms)
+                }))
+            }
+
+            /// compute the elementwise error `u diag(s) vt - original` of the reconstruction.
+            ///
+            /// The reconstruction is that of `reconstruct()`. Unlike a single norm, the error
+            /// matrix shows where the decomposition is inaccurate, e.g. in a few rows or in a
+            /// block of large elements.
+            ///
+            /// # Arguments
+            ///
+            /// * `original` - The matrix that was decomposed.
+            ///
+            /// # Returns
+            ///
+            /// `None` if `u` or `vt` was not computed or `original` does not have the shape of
+            /// the reconstruction, otherwise the error matrix.
+            pub fn reconstruction_error(&self, original: &DMatrix<$t>) -> Option<DMatrix<$t>> {
+                let product = self.reconstruct()?;
+                if product.nrows() != original.nrows() || product.ncols() != original.ncols() {
+                    return None;
+                }
+                Some(product - original.clone())
+            }
+
+            /// compute the Frobenius norm of the error `u diag(s) vt - original` of the
+            /// reconstruction.
+            ///
+            /// This is the norm of `reconstruction_error()`.
+            ///
+            /// # Arguments
+            ///
+            /// * `original` - The matrix that was decomposed.
+            ///
+            /// # Returns
+            ///
+            /// `None` under the same conditions as `reconstruction_error()`, otherwise the norm.
+            pub fn reconstruction_error_norm(&self, original: &DMatrix<$t>) -> Option<$t> {
+                let error = self.reconstruction_error(original)?;
+                Some(error.as_vector().iter().fold(0.0, |acc: $t, x| acc + x * x).sqrt())
+            }
+
+            /// shrink the singular values with the optimal hard threshold of Gavish and
+            /// Donoho, for denoising a low-rank matrix observed with white noise of unknown
+            /// level.
+            ///
+            /// Gavish and Donoho (2014), "The optimal hard threshold for singular values is
+            /// 4/sqrt(3)", show that for an `m x n` matrix with `beta = m / n`, keeping the
+            /// singular values above `tau = omega(beta) median(s)` and zeroing the others is
+            /// asymptotically optimal in the mean squared error among hard thresholds. The
+            /// factor is approximated by `omega(beta) = 0.56 beta^3 - 0.95 beta^2 + 1.82 beta +
+            /// 1.43`, which is about `2.858` for a square matrix. The median estimates the noise
+            /// level, so all `min(m, n)` singular values must be present.
+            ///
+            /// # Arguments
+            ///
+            /// * `aspect_ratio` - The ratio `beta = m / n` of the matrix dimensions. A ratio
+            ///   above one is replaced by its reciprocal, as the threshold only depends on the
+            ///   ratio of the smaller dimension to the larger.
+            ///
+            /// # Returns
+            ///
+            /// * `shrunk` - The singular values above the threshold, and zeros in place of the
+            ///   others.
+            pub fn optimal_shrinkage(&self, aspect_ratio: $t) -> DVector<$t> {
+                let beta = if aspect_ratio > 1.0 { 1.0 / aspect_ratio } else { aspect_ratio };
+                let omega = ((0.56 * beta - 0.95) * beta + 1.82) * beta + 1.43;
+                let mut sorted = self.s.at.clone();
+                sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(::std::cmp::Ordering::Equal));
+                let k = sorted.len();
+                let median = if k == 0 {
+                    0.0
+                } else if k % 2 == 1 {
+                    sorted[k / 2]
+                } else {
+                    0.5 * (sorted[k / 2 - 1] + sorted[k / 2])
+                };
+                let tau = omega * median;
+                DVector { at: self.s.iter().map(|x| if *x > tau { *x } else { 0.0 }).collect() }
+            }
+
+            /// denoise the matrix by reconstructing it from the singular values shrunk by
+            /// `optimal_shrinkage()`.
+            ///
+            /// # Arguments
+            ///
+            /// * `aspect_ratio` - The ratio `beta = m / n` of the matrix dimensions.
+            ///
+            /// # Returns
+            ///
+            /// `None` if `u` or `vt` was not computed, otherwise the denoised matrix.
+            pub fn denoise(&self, aspect_ratio: $t) -> Option<DMatrix<$t>> {
+                SvdResult {
+                    u: self.u.clone(),
+                    s: self.optimal_shrinkage(aspect_ratio),
+                    vt: self.vt.clone(),
+                }.reconstruct()
+            }
+        }
+    );
+);
+
+macro_rules! svd_result_complex_impl(
+    ($t: ty) => (
+        impl SvdResult<Complex<$t>> {
+            /// reconstruct the matrix `u diag(s) vt` from its decomposition.
+            ///
+            /// Each element is accumulated from the largest singular value to the smallest,
+            /// with the products and partial sums carried in about twice the working
+            /// precision. Unlike a plain matrix product, this keeps the contribution of small
+            /// singular values when the singular values span many orders of magnitude.
+            ///
+            /// The decomposition is only borrowed, so its factors can still be used, and the
+            /// matrix reconstructed again, afterwards.
+            ///
+            /// # Returns
+            ///
+            /// `None` if `u` or `vt` was not computed, otherwise the reconstructed matrix.
+            pub fn reconstruct(&self) -> Option<DMatrix<Complex<$t>>> {
+                let (u, vt) = match (self.u.as_ref(), self.vt.as_ref()) {
+                    (Some(u), Some(vt)) => (u, vt),
+                    _ => return None,
+                };
+                let order = largest_first(&self.s);
+                let mut re_terms = Vec::with_capacity(2 * order.len());
+                let mut im_terms = Vec::with_capacity(2 * order.len());
+                Some(DMatrix::from_fn(u.nrows(), vt.ncols(), |i, j| {
+                    re_terms.clear();
+                    im_terms.clear();
+                    for &k in &order {
+                        let (xr, exr) = two_product(u[(i, k)].re, self.s[k]);
+                        let (xi, exi) = two_product(u[(i, k)].im, self.s[k]);
+                        let v = vt[(k, j)];
+                        re_terms.push((xr, exr, v.re));
+                        re_terms.push((-xi, -exi, v.im));
+                        im_terms.push((xr, exr, v.im));
+                        im_terms.push((xi, exi, v.re));
+                    }
+                    Complex { re: accurate_dot(&re_terms), im: accurate_dot(&im_terms) }
+                }))
+            }
+
+            /// compute the elementwise error `u diag(s) vt - original` of the reconstruction.
+            ///
+            /// The reconstruction is that of `reconstruct()`. Unlike a single norm, the error
+            /// matrix shows where the decomposition is inaccurate, e.g. in a few rows or in a
+            /// block of large elements.
+            ///
+            /// # Arguments
+            ///
+            /// * `original` - The matrix that was decomposed.
+            ///
+            /// # Returns
+            ///
+            /// `None` if `u` or `vt` was not computed or `original` does not have the shape of
+            /// the reconstruction, otherwise the error matrix.
+            pub fn reconstruction_error(&self, original: &DMatrix<Complex<$t>>)
+                -> Option<DMatrix<Complex<$t>>> {
+                let product = self.reconstruct()?;
+                if product.nrows() != original.nrows() || product.ncols() != original.ncols() {
+                    return None;
+                }
+                Some(product - original.clone())
+            }
+
+            /// compute the Frobenius norm of the error `u diag(s) vt - original` of the
+            /// reconstruction.
+            ///
+            /// This is the norm of `reconstruction_error()`.
+            ///
+            /// # Arguments
+            ///
+            /// * `original` - The matrix that was decomposed.
+            ///
+            /// # Returns
+            ///
+            /// `None` under the same conditions as `reconstruction_error()`, otherwise the norm.
+            pub fn reconstruction_error_norm(&self, original: &DMatrix<Complex<$t>>)
+                -> Option<$t> {
+                let error = self.reconstruction_error(original)?;
+                Some(error.as_vector().iter().fold(0.0, |acc: $t, x| acc + x.norm_sqr()).sqrt())
+            }
+
+            /// split the left singular vectors into the magnitudes and phases of their
+            /// elements.
+            ///
+            /// Each element of `u` is `magnitude * e^(i phase)`. The phases are in `(-pi, pi]`,
+            /// and are zero for zero elements. Note that each singular vector is only
+            /// determined up to a common phase factor.
+            ///
+            /// # Returns
+            ///
+            /// `None` if `u` was not computed, otherwise:
+            ///
+            /// * `magnitudes` - The absolute values of the elements of `u`.
+            /// * `phases` - The arguments of the elements of `u`, in radians.
+            pub fn vector_phases(&self) -> Option<(DMatrix<$t>, DMatrix<$t>)> {
+                let u = match self.u.as_ref() {
+                    Some(u) => u,
+                    None => return None,
+                };
+                let (m, k) = (u.nrows(), u.ncols());
+                Some((DMatrix::from_fn(m, k, |i, j| u[(i, j)].norm()),
+                      DMatrix::from_fn(m, k, |i, j| u[(i, j)].arg())))
+            }
+        }
+    );
+);
+
+svd_result_impl!(f32);
+svd_result_impl!(f64);
+svd_result_complex_impl!(f32);
+svd_result_complex_impl!(f64);
+
+use lapack::fortran as interface;
+
+svd_with_impl!(f32, interface::sgesvd, interface::sgesdd);
+svd_with_impl!(f64, interface::dgesvd, interface::dgesdd);
+svd_with_complex_impl!(f32, interface::cgesvd, interface::cgesdd);
+svd_with_complex_impl!(f64, interface::zgesvd, interface::zgesdd);
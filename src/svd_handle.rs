@@ -0,0 +1,187 @@
+use std::cell::RefCell;
+
+use num::Float;
+
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector};
+
+use {NalgebraLapackResult, SVD};
+
+/// A matrix whose singular value decomposition is computed on first use and then cached.
+///
+/// Each query, such as `rank()` or `pseudoinverse()`, is answered from the same SVD, which
+/// is computed by the first query that needs it, so exploring several properties of a matrix
+/// costs a single decomposition. The matrix is kept, and a copy of it is decomposed, so a
+/// failed SVD is retried by the next query.
+///
+/// The numerical rank used by the queries is the number of singular values greater than
+/// `max(m, n) eps s_max`.
+pub struct SvdHandle<N> {
+    a: DMatrix<N>,
+    svd: RefCell<Option<(DMatrix<N>, DVector<N>, DMatrix<N>)>>,
+}
+
+/// Where a tolerance splits the singular values into retained and discarded ones, to audit
+/// the choice of the tolerance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SvdCutoff<N> {
+    /// The index of the smallest retained singular value, or `None` if all of them are
+    /// discarded.
+    pub last_retained: Option<usize>,
+    /// The largest discarded singular value, or `None` if all of them are retained.
+    pub first_discarded: Option<N>,
+}
+
+impl<N> SvdHandle<N> {
+    /// create a handle for `a`, without computing anything yet.
+    pub fn new(a: DMatrix<N>) -> SvdHandle<N> {
+        SvdHandle { a, svd: RefCell::new(None) }
+    }
+
+    /// return the matrix.
+    pub fn matrix(&self) -> &DMatrix<N> {
+        &self.a
+    }
+
+    /// return `true` if the SVD has been computed already.
+    pub fn is_computed(&self) -> bool {
+        self.svd.borrow().is_some()
+    }
+}
+
+impl<N> SvdHandle<N>
+    where N: BaseFloat,
+          DMatrix<N>: SVD<M = N, V = N>
+{
+    /// Call `f` with the `(u, s, vt)` of the cached SVD, computing it first if needed.
+    fn with_svd<R, F>(&self, f: F) -> NalgebraLapackResult<R>
+        where F: FnOnce(&DMatrix<N>, &DVector<N>, &DMatrix<N>) -> R
+    {
+        if !self.is_computed() {
+            let svd = self.a.clone().svd()?;
+            *self.svd.borrow_mut() = Some(svd);
+        }
+        let svd = self.svd.borrow();
+        let (u, s, vt) = svd.as_ref().unwrap();
+        Ok(f(u, s, vt))
+    }
+
+    /// The numerical rank for the singular values `s`.
+    fn rank_of(&self, s: &DVector<N>) -> usize {
+        if s.len() == 0 {
+            return 0;
+        }
+        let (m, n) = (self.a.nrows(), self.a.ncols());
+        let dim = if m >= n { m } else { n };
+        let tol = <N as Cast<f64>>::from(dim as f64) * <N as Float>::epsilon() * s[0];
+        s.at.iter().filter(|x| **x > tol).count()
+    }
+
+    /// return the singular values, in descending order.
+    pub fn singular_values(&self) -> NalgebraLapackResult<DVector<N>> {
+        self.with_svd(|_, s, _| s.clone())
+    }
+
+    /// compute the numerical rank.
+    pub fn rank(&self) -> NalgebraLapackResult<usize> {
+        self.with_svd(|_, s, _| self.rank_of(s))
+    }
+
+    /// compute the rank for a given tolerance, and report where the tolerance cuts the
+    /// singular values.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Singular values at or below this are treated as zero.
+    ///
+    /// # Returns
+    ///
+    /// * `rank` - The number of singular values greater than `tol`.
+    /// * `cutoff` - The smallest retained and the largest discarded singular values.
+    pub fn rank_with_cutoff(&self, tol: N) -> NalgebraLapackResult<(usize, SvdCutoff<N>)> {
+        self.with_svd(|_, s, _| {
+            let r = s.at.iter().filter(|x| **x > tol).count();
+            (r, cutoff_at(s, r))
+        })
+    }
+
+    /// compute the Moore-Penrose pseudoinverse.
+    ///
+    /// This is `V diag(1/s) U^T`, where the singular values beyond the numerical rank are
+    /// treated as zero and not inverted.
+    ///
+    /// # Returns
+    ///
+    /// * `pseudoinverse` - The `n x m` pseudoinverse.
+    pub fn pseudoinverse(&self) -> NalgebraLapackResult<DMatrix<N>> {
+        self.with_svd(|u, s, vt| self.pseudoinverse_of(u, s, vt, self.rank_of(s)))
+    }
+
+    /// compute the Moore-Penrose pseudoinverse for a given tolerance, and report where the
+    /// tolerance cuts the singular values.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Singular values at or below this are treated as zero and not inverted.
+    ///
+    /// # Returns
+    ///
+    /// * `pseudoinverse` - The `n x m` pseudoinverse.
+    /// * `cutoff` - The smallest retained and the largest discarded singular values.
+    pub fn pseudoinverse_with_cutoff(&self, tol: N)
+        -> NalgebraLapackResult<(DMatrix<N>, SvdCutoff<N>)> {
+        self.with_svd(|u, s, vt| {
+            let r = s.at.iter().filter(|x| **x > tol).count();
+            (self.pseudoinverse_of(u, s, vt, r), cutoff_at(s, r))
+        })
+    }
+
+    /// `V diag(1/s) U^T` restricted to the `r` largest singular values.
+    fn pseudoinverse_of(&self, u: &DMatrix<N>, s: &DVector<N>, vt: &DMatrix<N>, r: usize)
+        -> DMatrix<N> {
+        let (m, n) = (self.a.nrows(), self.a.ncols());
+        DMatrix::from_fn(n, m, |i, j| {
+            (0..r).fold(N::zero(), |acc, k| acc + vt[(k, i)] * u[(j, k)] / s[k])
+        })
+    }
+
+    /// compute an orthonormal basis of the null space.
+    ///
+    /// # Returns
+    ///
+    /// * `basis` - The `n x (n - rank)` matrix whose columns are the right-singular vectors
+    ///   beyond the numerical rank.
+    pub fn null_space(&self) -> NalgebraLapackResult<DMatrix<N>> {
+        let n = self.a.ncols();
+        self.with_svd(|_, s, vt| {
+            let r = self.rank_of(s);
+            DMatrix::from_fn(n, n - r, |i, j| vt[(r + j, i)])
+        })
+    }
+
+    /// compute the 2-norm condition number `s_max / s_min`.
+    ///
+    /// # Returns
+    ///
+    /// * `cond` - The condition number, infinite if the smallest singular value is zero and
+    ///   one for an empty matrix.
+    pub fn condition_number(&self) -> NalgebraLapackResult<N> {
+        self.with_svd(|_, s, _| {
+            let k = s.len();
+            if k == 0 {
+                N::one()
+            } else if s[k - 1] == N::zero() {
+                <N as Float>::infinity()
+            } else {
+                s[0] / s[k - 1]
+            }
+        })
+    }
+}
+
+/// The cutoff of the singular values `s`, in descending order, that retains the `r` largest.
+fn cutoff_at<N: Copy>(s: &DVector<N>, r: usize) -> SvdCutoff<N> {
+    SvdCutoff {
+        last_retained: if r > 0 { Some(r - 1) } else { None },
+        first_discarded: s.at.get(r).cloned(),
+    }
+}
@@ -0,0 +1,1124 @@
+use num::complex::Complex;
+use num::Float;
+
+use nalgebra::{BaseFloat, Cast, DMatrix, DVector, Eye};
+
+use errors::{Error, ErrorKind};
+use scratch::workspace;
+use {Eigensystem, LapackScratch, NalgebraLapackResult, Solve, Triangle};
+
+/// A real symmetric matrix for which eigenvalues and eigenvectors can be computed.
+pub trait SymmetricEigensystem {
+    type N;
+
+    /// compute eigenvalues and eigenvectors of a real symmetric matrix.
+    ///
+    /// Only the lower triangle of the matrix is read. For 2x2 and 3x3 matrices a closed-form
+    /// solver is used, which avoids the overhead of calling into LAPACK for tiny matrices.
+    /// Larger matrices use `symmetric_eigensystem_lapack()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues, in ascending order.
+    /// * `eigen_vectors` - The orthonormal eigenvectors. They are contained as columns of this
+    ///   matrix, in the same order as the eigenvalues.
+    fn symmetric_eigensystem(self) -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute eigenvalues and eigenvectors of a real symmetric matrix using LAPACK.
+    ///
+    /// This is the same as `symmetric_eigensystem()` except that LAPACK is always used, even
+    /// for 2x2 and 3x3 matrices.
+    fn symmetric_eigensystem_lapack(self)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute eigenvalues and eigenvectors of a real symmetric matrix, borrowing the LAPACK
+    /// workspace from `scratch`.
+    ///
+    /// This is the same as `symmetric_eigensystem()`, but reuses `scratch` instead of
+    /// allocating a new workspace.
+    fn symmetric_eigensystem_with_workspace(self, scratch: &mut LapackScratch)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute eigenvalues and eigenvectors of a real symmetric matrix, after checking that it
+    /// is symmetric.
+    ///
+    /// `symmetric_eigensystem()` only reads the lower triangle, so for a nonsymmetric matrix it
+    /// silently returns the eigensystem of a different matrix. This first verifies that
+    /// `||A - A^T|| <= tol ||A||` in the Frobenius norm.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The relative tolerance on the asymmetry of `A`.
+    ///
+    /// # Returns
+    ///
+    /// The same as `symmetric_eigensystem()`, or a `MatrixNotSymmetric` error if the check
+    /// fails.
+    fn symmetric_eigensystem_checked(self, tol: Self::N)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute eigenvalues and eigenvectors of a 2x2 or 3x3 real symmetric matrix in closed
+    /// form.
+    ///
+    /// This is the solver used by `symmetric_eigensystem()` and `symmetric_eigenvalues()` for
+    /// small matrices. The matrix is scaled by its largest entry first, so the squares formed
+    /// by the formulas neither overflow nor underflow. Only the lower triangle of the matrix is
+    /// read.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the matrix is not 2x2 or 3x3, or if an entry or the result is not finite, in
+    /// which case the callers fall back to `?syev`. Otherwise the eigenvalues in ascending
+    /// order and the corresponding orthonormal eigenvectors as columns.
+    fn symmetric_eigensystem_closed_form(&self) -> Option<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute the eigenvalues of a real symmetric matrix without its eigenvectors.
+    ///
+    /// This asks LAPACK not to compute eigenvectors, which is significantly faster when only
+    /// the spectrum is needed, e.g. to check positive-definiteness. 2x2 and 3x3 matrices use
+    /// the closed-form solver of `symmetric_eigensystem()` instead, so both return the same
+    /// eigenvalues. Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues, in ascending order, or `None` if the matrix is not
+    ///   square or LAPACK fails.
+    fn symmetric_eigenvalues(self) -> Option<DVector<Self::N>>;
+
+    /// compute eigenvalues and eigenvectors of a real symmetric matrix, reading only the
+    /// given triangle.
+    ///
+    /// This is `symmetric_eigensystem()` for a matrix of which only `triangle` has been filled
+    /// in.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `triangle` - The triangle of the matrix that is read.
+    ///
+    /// # Returns
+    ///
+    /// The same as `symmetric_eigensystem()`.
+    fn symmetric_eigensystem_with_triangle(self, triangle: Triangle)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// compute the eigenvalues of a real symmetric matrix without its eigenvectors, reading
+    /// only the given triangle.
+    ///
+    /// This is `symmetric_eigenvalues()` for a matrix of which only `triangle` has been filled
+    /// in.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `triangle` - The triangle of the matrix that is read.
+    ///
+    /// # Returns
+    ///
+    /// The same as `symmetric_eigenvalues()`.
+    fn symmetric_eigenvalues_with_triangle(self, triangle: Triangle)
+        -> Option<DVector<Self::N>>;
+
+    /// compute the smallest eigenvalue of a real symmetric matrix.
+    ///
+    /// This uses LAPACK's selected eigensolver to compute only the first eigenvalue, which is
+    /// much cheaper than the full spectrum. A positive result certifies that the matrix is
+    /// positive-definite. Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// The smallest eigenvalue, or `None` if the matrix is not square, is empty or LAPACK
+    /// fails.
+    fn smallest_eigenvalue(self) -> Option<Self::N>;
+
+    /// compute the eigenpairs of a real symmetric matrix with indices from `il` to `iu`.
+    ///
+    /// The indices count from zero in ascending order of the eigenvalues. Like
+    /// `smallest_eigenvalue()`, this uses LAPACK's selected eigensolver `?syevr`, which only
+    /// computes the requested eigenpairs and is much cheaper than the full eigensystem when
+    /// few are needed. Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `il` - The index of the smallest eigenvalue to compute.
+    /// * `iu` - The index of the largest eigenvalue to compute, inclusive.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The `iu - il + 1` selected eigenvalues, in ascending order.
+    /// * `eigen_vectors` - The orthonormal eigenvectors. They are contained as columns of this
+    ///   matrix, in the same order as the eigenvalues.
+    ///
+    /// A `DimensionMismatch` error is returned if the indices are out of range.
+    fn symmetric_eigensystem_range(self, il: usize, iu: usize)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+}
+
+/// copy the upper triangle of a square matrix into its lower triangle if it is the one that
+/// was filled in, so that the routines reading the lower triangle can be used.
+fn lower_from_triangle<N: Copy>(mut a: DMatrix<N>, triangle: Triangle) -> DMatrix<N> {
+    if triangle == Triangle::Upper && a.nrows() == a.ncols() {
+        for j in 0..a.ncols() {
+            for i in (j + 1)..a.nrows() {
+                a[(i, j)] = a[(j, i)];
+            }
+        }
+    }
+    a
+}
+
+/// A real symmetric matrix whose eigenvalues can be computed with `?syev` and `?syevr`.
+trait Syev {
+    type N;
+
+    /// Call `?syev` on the lower triangle, computing eigenvectors if `jobz` is `b'V'`, and
+    /// return the eigenvalues and the overwritten matrix. The workspace is borrowed from
+    /// `scratch`.
+    fn syev(self, jobz: u8, scratch: &mut LapackScratch)
+        -> NalgebraLapackResult<(DVector<Self::N>, DMatrix<Self::N>)>;
+
+    /// Call `?syevr` on the lower triangle for the smallest eigenvalue only, without
+    /// eigenvectors. A `DimensionMismatch` error is returned if the matrix is empty.
+    fn syevr_smallest(self) -> NalgebraLapackResult<Self::N>;
+}
+
+/// A real symmetric positive-definite matrix from which a whitening transform can be computed.
+pub trait WhiteningTransform {
+    type N;
+
+    /// compute the whitening transform `C^(-1/2)` of a covariance matrix `C`.
+    ///
+    /// This is `V diag(1/sqrt(lambda)) V^T` from the symmetric eigendecomposition `C = V
+    /// diag(lambda) V^T`. Applying it to data with covariance `C` gives data with the identity
+    /// covariance, i.e. `W C W^T = I`. Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `w` - The symmetric whitening matrix. `None` is returned if any eigenvalue of the
+    ///   matrix is not positive, or if the eigendecomposition fails.
+    fn whitening_transform(self) -> Option<DMatrix<Self::N>>;
+}
+
+/// A real symmetric matrix for which the pseudoinverse can be computed.
+pub trait SymmetricPseudoinverse {
+    type N;
+
+    /// compute the Moore-Penrose pseudoinverse of a real symmetric, possibly singular, matrix.
+    ///
+    /// This is `V diag(1/lambda) V^T` from the symmetric eigendecomposition, where eigenvalues
+    /// with magnitude at or below `tol` are treated as zero and not inverted. The result is
+    /// exactly symmetric. Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - Eigenvalues with magnitude at or below this are treated as zero.
+    ///
+    /// # Returns
+    ///
+    /// * `pseudoinverse` - The pseudoinverse.
+    fn symmetric_pseudoinverse(self, tol: Self::N) -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// A real symmetric matrix which can be reduced to tridiagonal form.
+pub trait SymmetricTridiagonalize {
+    type N;
+
+    /// reduce a real symmetric matrix to symmetric tridiagonal form.
+    ///
+    /// This computes the orthogonal `Q` for which `T = Q^T A Q` is tridiagonal, as the first
+    /// step of LAPACK's symmetric eigensolvers, using `?sytrd` and `?orgtr`. It is meant for
+    /// implementing other tridiagonal eigensolvers. Only the lower triangle of the matrix is
+    /// read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `d` - The diagonal of `T`, of length `n`.
+    /// * `e` - The off-diagonal of `T`, of length `n - 1`.
+    /// * `q` - The orthogonal `n x n` matrix `Q`.
+    fn symmetric_tridiagonalize(self)
+        -> NalgebraLapackResult<(DVector<Self::N>, DVector<Self::N>, DMatrix<Self::N>)>;
+}
+
+/// A real symmetric matrix for which the trace of a matrix function can be computed.
+pub trait TraceOfFunction {
+    type N;
+
+    /// compute `trace(f(A))` for a real symmetric matrix without forming `f(A)`.
+    ///
+    /// With the eigenvalues `lambda_i` of `A`, this is `sum_i f(lambda_i)`, which is exact and
+    /// avoids assembling `V diag(f(lambda)) V^T`. For example, `f = ln` gives the logarithm of
+    /// the determinant of a positive-definite `A`, and `f(x) = x^2` the squared Frobenius norm.
+    /// Only the lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The scalar function, which must be defined at all the eigenvalues of `A`.
+    ///
+    /// # Returns
+    ///
+    /// * `trace` - The trace of `f(A)`.
+    fn trace_of_function<F>(self, f: F) -> NalgebraLapackResult<Self::N>
+        where F: Fn(Self::N) -> Self::N;
+}
+
+impl<N> TraceOfFunction for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    type N = N;
+
+    fn trace_of_function<F>(self, f: F) -> NalgebraLapackResult<N>
+        where F: Fn(N) -> N
+    {
+        if self.ncols() != self.nrows() {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        if self.nrows() == 0 {
+            return Ok(N::zero());
+        }
+        let (values, _) = self.symmetric_eigensystem()?;
+        Ok(values.at.iter().fold(N::zero(), |acc, lambda| acc + f(*lambda)))
+    }
+}
+
+/// A real square matrix for which the trace of the inverse can be computed.
+pub trait TraceOfInverse {
+    type N;
+
+    /// compute `trace(A^-1)` without forming the inverse, e.g. for the effective degrees of
+    /// freedom of a smoother or for generalized cross-validation.
+    ///
+    /// A matrix that is symmetric to working precision, i.e. with `||A - A^T||_F <= eps
+    /// ||A||_F`, is reduced to its eigenvalues `lambda_i` only, about `4/3 n^3` operations, and
+    /// the trace is `sum_i 1 / lambda_i`. Any other matrix is LU-factorized, and `A X = I` is
+    /// solved for the diagonal of `X`, about `8/3 n^3` operations in all.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `trace` - The trace of the inverse, or `None` if the matrix is not square, is
+    ///   singular (a zero eigenvalue or pivot) or LAPACK fails.
+    fn trace_of_inverse(self) -> Option<Self::N>;
+}
+
+impl<N> TraceOfInverse for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N> + Solve<N = N>
+{
+    type N = N;
+
+    fn trace_of_inverse(self) -> Option<N> {
+        let n = self.nrows();
+        if self.ncols() != n {
+            return None;
+        }
+        if relative_asymmetry(&self) <= <N as Float>::epsilon() {
+            let values = self.symmetric_eigenvalues()?;
+            if values.at.iter().any(|lambda| *lambda == N::zero()) {
+                return None;
+            }
+            return Some(values.at.iter().fold(N::zero(), |acc, lambda| acc + N::one() / *lambda));
+        }
+        let x = self.solve(DMatrix::new_identity(n)).ok()?;
+        Some((0..n).fold(N::zero(), |acc, i| acc + x[(i, i)]))
+    }
+}
+
+/// compute the geometric mean `A # B` of two symmetric positive-definite matrices, e.g. to
+/// interpolate diffusion tensors.
+///
+/// This is `A^(1/2) (A^(-1/2) B A^(-1/2))^(1/2) A^(1/2)`, the midpoint of the geodesic between
+/// `A` and `B` in the affine-invariant metric. It is symmetric positive definite, equal to
+/// `(A B)^(1/2)` when `A` and `B` commute, and `(A^-1 # B^-1)^-1 = A # B`. The square roots are
+/// taken from two symmetric eigendecompositions, and the result is symmetrized.
+///
+/// # Arguments
+///
+/// * `a` - The first symmetric positive-definite matrix.
+/// * `b` - The second symmetric positive-definite matrix, of the same size.
+///
+/// # Returns
+///
+/// * `mean` - The geometric mean, or `None` if the sizes differ, if either matrix is not
+///   symmetric to working precision (`||A - A^T||_F <= eps ||A||_F`) or has an eigenvalue that
+///   is not positive, or if LAPACK fails.
+pub fn spd_geometric_mean<N>(a: DMatrix<N>, b: DMatrix<N>) -> Option<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    let n = a.nrows();
+    if a.ncols() != n || b.nrows() != n || b.ncols() != n {
+        return None;
+    }
+    if relative_asymmetry(&b) > <N as Float>::epsilon() {
+        return None;
+    }
+    let (a_sqrt, a_inv_sqrt) = spd_sqrt_and_inverse_sqrt(a)?;
+    // `C` is congruent to `B`, so it is positive definite if and only if `B` is.
+    let c = symmetric_part(&(&(&a_inv_sqrt * &b) * &a_inv_sqrt));
+    let (c_sqrt, _) = spd_sqrt_and_inverse_sqrt(c)?;
+    Some(symmetric_part(&(&(&a_sqrt * &c_sqrt) * &a_sqrt)))
+}
+
+/// Return `(M + M^T) / 2`.
+fn symmetric_part<N: BaseFloat>(m: &DMatrix<N>) -> DMatrix<N> {
+    let two = N::one() + N::one();
+    DMatrix::from_fn(m.nrows(), m.ncols(), |i, j| (m[(i, j)] + m[(j, i)]) / two)
+}
+
+/// Return `(A^(1/2), A^(-1/2))` for a symmetric positive-definite `A`, or `None` if it is not
+/// symmetric to working precision or not positive definite.
+fn spd_sqrt_and_inverse_sqrt<N>(a: DMatrix<N>) -> Option<(DMatrix<N>, DMatrix<N>)>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    if relative_asymmetry(&a) > <N as Float>::epsilon() {
+        return None;
+    }
+    let (values, vectors) = a.symmetric_eigensystem().ok()?;
+    if values.at.iter().any(|lambda| *lambda <= N::zero()) {
+        return None;
+    }
+    let sqrt: Vec<N> = values.at.iter().map(|lambda| Float::sqrt(*lambda)).collect();
+    let inv_sqrt: Vec<N> = sqrt.iter().map(|x| N::one() / *x).collect();
+    Some((reassemble(&sqrt, &vectors), reassemble(&inv_sqrt, &vectors)))
+}
+
+/// A real symmetric matrix for which the matrix cosine and sine can be computed.
+pub trait SymmetricTrigonometric {
+    type N;
+
+    /// compute the matrix cosine `cos(A)` of a real symmetric matrix.
+    ///
+    /// With the eigendecomposition `A = V diag(lambda) V^T`, this is `V diag(cos(lambda)) V^T`.
+    /// Together with `matrix_sin()` it gives the solution of the second-order system
+    /// `x'' = -A^2 x`, namely `x(t) = cos(A t) x(0) + A^-1 sin(A t) x'(0)`. Only the lower
+    /// triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `cos` - The symmetric matrix `cos(A)`.
+    fn matrix_cos(self) -> NalgebraLapackResult<DMatrix<Self::N>>;
+
+    /// compute the matrix sine `sin(A)` of a real symmetric matrix.
+    ///
+    /// This is `V diag(sin(lambda)) V^T`, see `matrix_cos()`. Only the lower triangle of the
+    /// matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `sin` - The symmetric matrix `sin(A)`.
+    fn matrix_sin(self) -> NalgebraLapackResult<DMatrix<Self::N>>;
+}
+
+/// evaluate `V diag(f(lambda)) V^T` from the eigensystem of a real symmetric matrix.
+fn symmetric_function<N, F>(a: DMatrix<N>, f: F) -> NalgebraLapackResult<DMatrix<N>>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>,
+          F: Fn(N) -> N
+{
+    let n = a.nrows();
+    if a.ncols() != n {
+        return Err(Error::from(ErrorKind::MatrixNotSquare));
+    }
+    if n == 0 {
+        return Ok(a);
+    }
+    let (values, vectors) = a.symmetric_eigensystem()?;
+    let f_values: Vec<N> = values.at.iter().map(|lambda| f(*lambda)).collect();
+    Ok(DMatrix::from_fn(n, n, |i, j| {
+        (0..n).fold(N::zero(), |acc, k| acc + vectors[(i, k)] * f_values[k] * vectors[(j, k)])
+    }))
+}
+
+impl<N> SymmetricTrigonometric for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    type N = N;
+
+    fn matrix_cos(self) -> NalgebraLapackResult<DMatrix<N>> {
+        symmetric_function(self, |lambda| lambda.cos())
+    }
+
+    fn matrix_sin(self) -> NalgebraLapackResult<DMatrix<N>> {
+        symmetric_function(self, |lambda| lambda.sin())
+    }
+}
+
+/// The eigensystem returned by `RealIfSymmetric::eigensystem_real_if_symmetric()`.
+#[derive(Clone)]
+pub enum RealOrComplexEigensystem<N> {
+    /// The real eigenvalues, in ascending order, and orthonormal eigenvectors of a symmetric
+    /// matrix, as returned by `SymmetricEigensystem::symmetric_eigensystem()`.
+    Real(DVector<N>, DMatrix<N>),
+    /// The eigenvalues and right eigenvectors of a nonsymmetric matrix, as returned by
+    /// `Eigensystem::eigensystem()`.
+    Complex(DVector<Complex<N>>, DMatrix<Complex<N>>),
+}
+
+/// A real matrix whose eigensystem is computed with the symmetric solver when possible.
+pub trait RealIfSymmetric {
+    type N;
+
+    /// compute eigenvalues and eigenvectors, with the symmetric solver if the matrix is
+    /// symmetric.
+    ///
+    /// The general solver returns the eigenvalues of a symmetric matrix with rounding errors
+    /// in their imaginary parts, and eigenvectors that are not exactly orthogonal. If
+    /// `||A - A^T||_F <= tol ||A||_F`, as for `symmetric_eigensystem_checked()`, this uses
+    /// `symmetric_eigensystem()` instead, for exactly real output; otherwise it uses
+    /// `Eigensystem::eigensystem()`.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `tol` - The relative tolerance on the asymmetry of `A`.
+    ///
+    /// # Returns
+    ///
+    /// * `eigensystem` - The real eigensystem of a symmetric matrix, or the complex one of a
+    ///   nonsymmetric matrix.
+    fn eigensystem_real_if_symmetric(self, tol: Self::N)
+        -> NalgebraLapackResult<RealOrComplexEigensystem<Self::N>>;
+}
+
+impl<N> RealIfSymmetric for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N> + Eigensystem<N = N>
+{
+    type N = N;
+
+    fn eigensystem_real_if_symmetric(self, tol: N)
+        -> NalgebraLapackResult<RealOrComplexEigensystem<N>> {
+        if self.ncols() != self.nrows() {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        if relative_asymmetry(&self) <= tol {
+            let (values, vectors) = self.symmetric_eigensystem()?;
+            Ok(RealOrComplexEigensystem::Real(values, vectors))
+        } else {
+            let (values, vectors) = self.eigensystem()?;
+            Ok(RealOrComplexEigensystem::Complex(values, vectors))
+        }
+    }
+}
+
+/// A real skew-symmetric matrix for which eigenvalues can be computed.
+pub trait SkewSymmetricEigenvalues {
+    type N;
+
+    /// compute the eigenvalues of a real skew-symmetric matrix.
+    ///
+    /// The eigenvalues of a skew-symmetric `A = -A^T` are purely imaginary and come in
+    /// conjugate pairs. `A` is reduced by Householder reflections to a skew-symmetric
+    /// tridiagonal `T` with subdiagonal `e`, and with `D = diag(1, i, i^2, ...)`,
+    /// `D^-1 T D = -i S` where `S` is the real symmetric tridiagonal matrix with zero diagonal
+    /// and off-diagonal `e`. The eigenvalues are therefore `i mu` for the eigenvalues `mu` of
+    /// `S`, computed with the symmetric eigensolver. Their real parts are exactly zero, which
+    /// is not the case when `A` is passed to the general eigensolver.
+    ///
+    /// Only the strictly lower triangle of the matrix is read.
+    ///
+    /// Because the input matrix may be overwritten or destroyed, it is consumed.
+    ///
+    /// # Returns
+    ///
+    /// * `eigen_values` - The eigenvalues `i mu_1, -i mu_1, i mu_2, -i mu_2, ...` with
+    ///   `mu_1 >= mu_2 >= ... >= 0`, followed by a zero eigenvalue if `n` is odd.
+    fn skew_symmetric_eigenvalues(self) -> NalgebraLapackResult<DVector<Complex<Self::N>>>;
+}
+
+impl<N> SkewSymmetricEigenvalues for DMatrix<N>
+    where N: BaseFloat,
+          DMatrix<N>: SymmetricEigensystem<N = N>
+{
+    type N = N;
+
+    fn skew_symmetric_eigenvalues(self) -> NalgebraLapackResult<DVector<Complex<N>>> {
+        let n = self.nrows();
+        if self.ncols() != n {
+            return Err(Error::from(ErrorKind::MatrixNotSquare));
+        }
+        let zero = N::zero();
+        if n <= 1 {
+            return Ok(DVector::from_element(n, Complex::new(zero, zero)));
+        }
+        let mut a = DMatrix::from_fn(n, n, |i, j| {
+            if i > j { self[(i, j)] } else if i < j { -self[(j, i)] } else { zero }
+        });
+
+        // Householder reduction to tridiagonal form, `A <- H A H` for each column.
+        for k in 0..n.saturating_sub(2) {
+            let norm = ((k + 1)..n).fold(zero, |acc, i| acc + a[(i, k)] * a[(i, k)]).sqrt();
+            if norm == zero {
+                continue;
+            }
+            let alpha = if a[(k + 1, k)] > zero { -norm } else { norm };
+            let mut v = vec![zero; n];
+            for i in (k + 1)..n {
+                v[i] = a[(i, k)];
+            }
+            v[k + 1] -= alpha;
+            let vv = v.iter().fold(zero, |acc, x| acc + *x * *x);
+            let beta = cast::<N>(2.0) / vv;
+            for j in 0..n {
+                let d = ((k + 1)..n).fold(zero, |acc, i| acc + v[i] * a[(i, j)]) * beta;
+                for i in (k + 1)..n {
+                    a[(i, j)] -= d * v[i];
+                }
+            }
+            for i in 0..n {
+                let d = ((k + 1)..n).fold(zero, |acc, j| acc + a[(i, j)] * v[j]) * beta;
+                for j in (k + 1)..n {
+                    a[(i, j)] -= d * v[j];
+                }
+            }
+        }
+
+        let s = DMatrix::from_fn(n, n, |i, j| {
+            if i == j + 1 { a[(i, j)] } else if j == i + 1 { a[(j, i)] } else { zero }
+        });
+        let (mu, _) = s.symmetric_eigensystem()?;
+        // The spectrum of `S` is symmetric about zero; averaging each pair keeps it so.
+        let mut eigen_values = Vec::with_capacity(n);
+        for k in 0..(n / 2) {
+            let m = ((mu[n - 1 - k] - mu[k]) / cast(2.0)).abs();
+            eigen_values.push(Complex::new(zero, m));
+            eigen_values.push(Complex::new(zero, -m));
+        }
+        if n % 2 == 1 {
+            eigen_values.push(Complex::new(zero, zero));
+        }
+        Ok(DVector { at: eigen_values })
+    }
+}
+
+fn cast<N: Cast<f64>>(x: f64) -> N {
+    <N as Cast<f64>>::from(x)
+}
+
+/// Eigensystem of `[[a, b], [b, c]]`, eigenvalues ascending.
+fn closed_form_2x2<N: BaseFloat>(a: N, b: N, c: N) -> ([N; 2], [[N; 2]; 2]) {
+    let zero = N::zero();
+    let one = N::one();
+    if b == zero {
+        return if a <= c {
+            ([a, c], [[one, zero], [zero, one]])
+        } else {
+            ([c, a], [[zero, one], [one, zero]])
+        };
+    }
+
+    let two: N = cast(2.0);
+    let mean = (a + c) / two;
+    let r = Float::hypot((a - c) / two, b);
+
+    // The eigenvector of the larger eigenvalue is at angle `theta` from the first axis.
+    let theta = Float::atan2(two * b, a - c) / two;
+    let (sin, cos) = (Float::sin(theta), Float::cos(theta));
+    ([mean - r, mean + r], [[-sin, cos], [cos, sin]])
+}
+
+fn cross<N: BaseFloat>(a: &[N; 3], b: &[N; 3]) -> [N; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot3<N: BaseFloat>(a: &[N; 3], b: &[N; 3]) -> N {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn scale3<N: BaseFloat>(a: &[N; 3], s: N) -> [N; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+/// Eigensystem of a 3x3 symmetric matrix given by its lower triangle, eigenvalues ascending.
+///
+/// The eigenvalues are first found from the characteristic polynomial with the trigonometric
+/// solution of the cubic (Smith, 1961). The eigenvector of the eigenvalue farthest from the
+/// other two is found as a cross product of rows of `A - lambda I`; the remaining two are found
+/// by solving the 2x2 problem projected onto its orthogonal complement, which keeps the result
+/// orthonormal even for repeated eigenvalues.
+fn closed_form_3x3<N: BaseFloat>(m: &DMatrix<N>) -> ([N; 3], [[N; 3]; 3]) {
+    let zero = N::zero();
+    let one = N::one();
+    let a = [[m[(0, 0)], m[(1, 0)], m[(2, 0)]],
+             [m[(1, 0)], m[(1, 1)], m[(2, 1)]],
+             [m[(2, 0)], m[(2, 1)], m[(2, 2)]]];
+
+    let p1 = a[1][0] * a[1][0] + a[2][0] * a[2][0] + a[2][1] * a[2][1];
+    if p1 == zero {
+        // Diagonal matrix.
+        let mut pairs = [(a[0][0], 0), (a[1][1], 1), (a[2][2], 2)];
+        pairs.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(::std::cmp::Ordering::Equal));
+        let mut vectors = [[zero; 3]; 3];
+        for (k, &(_, i)) in pairs.iter().enumerate() {
+            vectors[k][i] = one;
+        }
+        return ([pairs[0].0, pairs[1].0, pairs[2].0], vectors);
+    }
+
+    let three: N = cast(3.0);
+    let q = (a[0][0] + a[1][1] + a[2][2]) / three;
+    let p2 = (a[0][0] - q) * (a[0][0] - q) + (a[1][1] - q) * (a[1][1] - q) +
+             (a[2][2] - q) * (a[2][2] - q) + cast::<N>(2.0) * p1;
+    let p = Float::sqrt(p2 / cast(6.0));
+
+    // b = (A - q I) / p, with det(b) / 2 in [-1, 1] up to rounding.
+    let mut b = a;
+    for (i, row) in b.iter_mut().enumerate() {
+        row[i] -= q;
+        for x in row.iter_mut() {
+            *x /= p;
+        }
+    }
+    let det_b = b[0][0] * (b[1][1] * b[2][2] - b[1][2] * b[2][1]) -
+                b[0][1] * (b[1][0] * b[2][2] - b[1][2] * b[2][0]) +
+                b[0][2] * (b[1][0] * b[2][1] - b[1][1] * b[2][0]);
+    let r = Float::max(-one, Float::min(one, det_b / cast(2.0)));
+    let phi = Float::acos(r) / three;
+    let largest = q + cast::<N>(2.0) * p * Float::cos(phi);
+    let smallest = q + cast::<N>(2.0) * p * Float::cos(phi + cast::<N>(2.0) * N::frac_pi_3());
+    let middle = three * q - largest - smallest;
+
+    let isolated = if largest - middle >= middle - smallest {
+        largest
+    } else {
+        smallest
+    };
+
+    // Eigenvector of the isolated eigenvalue: the rows of A - lambda I span its orthogonal
+    // complement, so the largest of their pairwise cross products is parallel to it.
+    let mut rows = a;
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[i] -= isolated;
+    }
+    let candidates = [cross(&rows[0], &rows[1]),
+                      cross(&rows[0], &rows[2]),
+                      cross(&rows[1], &rows[2])];
+    let mut v = candidates[0];
+    let mut v_norm2 = dot3(&v, &v);
+    for c in candidates.iter().skip(1) {
+        let c_norm2 = dot3(c, c);
+        if c_norm2 > v_norm2 {
+            v = *c;
+            v_norm2 = c_norm2;
+        }
+    }
+    let v = scale3(&v, one / Float::sqrt(v_norm2));
+
+    // Orthonormal basis (u, w) of the complement of v.
+    let u = if Float::abs(v[0]) > Float::abs(v[1]) {
+        let u = [-v[2], zero, v[0]];
+        scale3(&u, one / Float::sqrt(dot3(&u, &u)))
+    } else {
+        let u = [zero, v[2], -v[1]];
+        scale3(&u, one / Float::sqrt(dot3(&u, &u)))
+    };
+    let w = cross(&v, &u);
+
+    let apply = |x: &[N; 3]| [dot3(&a[0], x), dot3(&a[1], x), dot3(&a[2], x)];
+    let au = apply(&u);
+    let aw = apply(&w);
+    let (mu, s) = closed_form_2x2(dot3(&u, &au), dot3(&u, &aw), dot3(&w, &aw));
+    let x0 = [u[0] * s[0][0] + w[0] * s[0][1],
+              u[1] * s[0][0] + w[1] * s[0][1],
+              u[2] * s[0][0] + w[2] * s[0][1]];
+    let x1 = [u[0] * s[1][0] + w[0] * s[1][1],
+              u[1] * s[1][0] + w[1] * s[1][1],
+              u[2] * s[1][0] + w[2] * s[1][1]];
+
+    let mut pairs = [(mu[0], x0), (mu[1], x1), (isolated, v)];
+    pairs.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(::std::cmp::Ordering::Equal));
+    ([pairs[0].0, pairs[1].0, pairs[2].0], [pairs[0].1, pairs[1].1, pairs[2].1])
+}
+
+/// Eigensystem of a 2x2 or 3x3 symmetric matrix given by its lower triangle, or `None` for
+/// other sizes, non-finite entries or a non-finite result.
+///
+/// The formulas square the entries, so the matrix is first divided by its largest entry in
+/// magnitude to avoid overflow and underflow, and the eigenvalues are scaled back afterwards.
+fn closed_form<N: BaseFloat>(m: &DMatrix<N>) -> Option<(DVector<N>, DMatrix<N>)> {
+    let n = m.nrows();
+    if m.ncols() != n || (n != 2 && n != 3) {
+        return None;
+    }
+    let mut scale = N::zero();
+    for j in 0..n {
+        for i in j..n {
+            scale = Float::max(scale, Float::abs(m[(i, j)]));
+        }
+    }
+    if !Float::is_finite(scale) {
+        return None;
+    }
+    let factor = if scale == N::zero() { N::one() } else { scale };
+    let scaled = DMatrix::from_fn(n, n, |i, j| m[(i, j)] / factor);
+
+    let (values, vectors) = if n == 2 {
+        let (values, vectors) = closed_form_2x2(scaled[(0, 0)], scaled[(1, 0)], scaled[(1, 1)]);
+        (values.iter().map(|x| *x * factor).collect::<Vec<N>>(),
+         DMatrix::from_fn(2, 2, |i, j| vectors[j][i]))
+    } else {
+        let (values, vectors) = closed_form_3x3(&scaled);
+        (values.iter().map(|x| *x * factor).collect::<Vec<N>>(),
+         DMatrix::from_fn(3, 3, |i, j| vectors[j][i]))
+    };
+    let finite = values.iter().chain(vectors.as_vector().iter()).all(|x| Float::is_finite(*x));
+    if !finite {
+        return None;
+    }
+    Some((DVector { at: values }, vectors))
+}
+
+/// Return `||a - a^T|| / ||a||` in the Frobenius norm, or zero for a zero matrix.
+fn relative_asymmetry<N: Float>(a: &DMatrix<N>) -> N {
+    let n = a.nrows();
+    let mut norm = N::zero();
+    let mut asymmetry = N::zero();
+    for j in 0..n {
+        for i in 0..n {
+            let d = a[(i, j)] - a[(j, i)];
+            norm = norm + a[(i, j)] * a[(i, j)];
+            asymmetry = asymmetry + d * d;
+        }
+    }
+    if norm == N::zero() { N::zero() } else { (asymmetry / norm).sqrt() }
+}
+
+/// Compute `V diag(d) V^T`.
+fn reassemble<N: BaseFloat>(d: &[N], v: &DMatrix<N>) -> DMatrix<N> {
+    let n = v.nrows();
+    // Only the lower triangle is summed and then mirrored, so the result is exactly symmetric.
+    let mut a = DMatrix::new_zeros(n, n);
+    for j in 0..n {
+        for i in j..n {
+            let sum = (0..d.len()).fold(N::zero(), |acc, k| acc + v[(i, k)] * d[k] * v[(j, k)]);
+            a[(i, j)] = sum;
+            a[(j, i)] = sum;
+        }
+    }
+    a
+}
+
+macro_rules! whitening_transform_impl(
+    ($t: ty) => (
+        impl WhiteningTransform for DMatrix<$t> {
+            type N = $t;
+
+            fn whitening_transform(self) -> Option<DMatrix<$t>> {
+                let (values, vectors) = self.symmetric_eigensystem().ok()?;
+                if values.at.iter().any(|x| *x <= 0.0) {
+                    return None;
+                }
+                let d: Vec<$t> = values.at.iter().map(|x| 1.0 / Float::sqrt(*x)).collect();
+                Some(reassemble(&d, &vectors))
+            }
+        }
+    );
+);
+
+macro_rules! symmetric_pseudoinverse_impl(
+    ($t: ty) => (
+        impl SymmetricPseudoinverse for DMatrix<$t> {
+            type N = $t;
+
+            fn symmetric_pseudoinverse(self, tol: $t) -> NalgebraLapackResult<DMatrix<$t>> {
+                let (values, vectors) = self.symmetric_eigensystem()?;
+                let d: Vec<$t> = values.at
+                    .iter()
+                    .map(|x| if Float::abs(*x) > tol { 1.0 / *x } else { 0.0 })
+                    .collect();
+                Ok(reassemble(&d, &vectors))
+            }
+        }
+    );
+);
+
+macro_rules! symmetric_tridiagonalize_impl(
+    ($t: ty, $sytrd: path, $orgtr: path) => (
+        impl SymmetricTridiagonalize for DMatrix<$t> {
+            type N = $t;
+
+            fn symmetric_tridiagonalize(self)
+                -> NalgebraLapackResult<(DVector<$t>, DVector<$t>, DMatrix<$t>)> {
+                let uplo = b'L';
+
+                let mut a = self;
+                let n = a.nrows();
+                if a.ncols() != n {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                if n == 0 {
+                    return Ok((DVector { at: vec![] }, DVector { at: vec![] },
+                               DMatrix::new_zeros(0, 0)));
+                }
+                let lda = n as i32;
+
+                let mut d: Vec<$t> = vec![0.0; n];
+                let mut e: Vec<$t> = vec![0.0; ::std::cmp::max(1, n - 1)];
+                let mut tau: Vec<$t> = vec![0.0; ::std::cmp::max(1, n - 1)];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $sytrd(uplo, n as i32, a.as_mut_vector(), lda, &mut d, &mut e, &mut tau,
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                ::diagnostics::record(stringify!($sytrd), n, n, &[uplo]);
+                $sytrd(uplo, n as i32, a.as_mut_vector(), lda, &mut d, &mut e, &mut tau,
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                // `Q` is formed in place of the reflectors stored in `a`.
+                let mut lwork = -1 as i32;
+                let mut work = vec![0.0];
+
+                $orgtr(uplo, n as i32, a.as_mut_vector(), lda, &tau, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+
+                $orgtr(uplo, n as i32, a.as_mut_vector(), lda, &tau, &mut work, lwork, &mut info);
+                check_info!(info);
+
+                e.truncate(n - 1);
+                Ok((DVector { at: d }, DVector { at: e }, a))
+            }
+        }
+    );
+);
+
+macro_rules! symmetric_eigensystem_impl(
+    ($t: ty, $lapack_func: path, $syevr: path) => (
+        impl SymmetricEigensystem for DMatrix<$t> {
+            type N = $t;
+
+            fn symmetric_eigensystem(self) -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                match closed_form(&self) {
+                    Some(result) => Ok(result),
+                    None => self.symmetric_eigensystem_lapack(),
+                }
+            }
+
+            fn symmetric_eigensystem_lapack(self)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                self.syev(b'V', &mut LapackScratch::new())
+            }
+
+            fn symmetric_eigensystem_with_workspace(self, scratch: &mut LapackScratch)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                match closed_form(&self) {
+                    Some(result) => Ok(result),
+                    None => self.syev(b'V', scratch),
+                }
+            }
+
+            fn symmetric_eigensystem_checked(self, tol: $t)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                if self.ncols() != self.nrows() {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                // A NaN element makes the asymmetry NaN, which is rejected as well.
+                let asymmetry = relative_asymmetry(&self);
+                if asymmetry.is_nan() || asymmetry > tol {
+                    return Err(Error::from(ErrorKind::MatrixNotSymmetric));
+                }
+                self.symmetric_eigensystem()
+            }
+
+            fn symmetric_eigensystem_closed_form(&self) -> Option<(DVector<$t>, DMatrix<$t>)> {
+                closed_form(self)
+            }
+
+            fn symmetric_eigenvalues(self) -> Option<DVector<$t>> {
+                match closed_form(&self) {
+                    Some((values, _)) => Some(values),
+                    None => Some(self.syev(b'N', &mut LapackScratch::new()).ok()?.0),
+                }
+            }
+
+            fn symmetric_eigensystem_with_triangle(self, triangle: Triangle)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                lower_from_triangle(self, triangle).symmetric_eigensystem()
+            }
+
+            fn symmetric_eigenvalues_with_triangle(self, triangle: Triangle)
+                -> Option<DVector<$t>> {
+                lower_from_triangle(self, triangle).symmetric_eigenvalues()
+            }
+
+            fn smallest_eigenvalue(self) -> Option<$t> {
+                self.syevr_smallest().ok()
+            }
+
+            fn symmetric_eigensystem_range(mut self, il: usize, iu: usize)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                let jobz = b'V';
+                let range = b'I';
+                let uplo = b'L';
+
+                if self.ncols() != self.nrows() {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let n = self.ncols();
+                if il > iu || iu >= n {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let lda = n as i32;
+                let (il, iu) = ((il + 1) as i32, (iu + 1) as i32);
+
+                let mut found = 0;
+                let mut w: Vec<$t> = vec![0.0; n];
+                let mut z: DMatrix<$t> = DMatrix::new_zeros(n, (iu - il + 1) as usize);
+                let mut isuppz = vec![0; 2 * n];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut iwork = vec![0];
+                let mut liwork = -1 as i32;
+                let mut info = 0;
+
+                $syevr(jobz, range, uplo, n as i32, self.as_mut_vector(), lda, 0.0, 0.0, il, iu,
+                    0.0, &mut found, &mut w, z.as_mut_vector(), lda, &mut isuppz, &mut work,
+                    lwork, &mut iwork, liwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+                liwork = iwork[0];
+                let mut iwork = vec![0; liwork as usize];
+
+                ::diagnostics::record(stringify!($syevr), n, n, &[jobz, range, uplo]);
+                $syevr(jobz, range, uplo, n as i32, self.as_mut_vector(), lda, 0.0, 0.0, il, iu,
+                    0.0, &mut found, &mut w, z.as_mut_vector(), lda, &mut isuppz, &mut work,
+                    lwork, &mut iwork, liwork, &mut info);
+                check_info!(info);
+
+                let m = found as usize;
+                w.truncate(m);
+                Ok((DVector { at: w }, DMatrix::from_fn(n, m, |i, j| z[(i, j)])))
+            }
+        }
+
+        impl Syev for DMatrix<$t> {
+            type N = $t;
+
+            fn syev(mut self, jobz: u8, scratch: &mut LapackScratch)
+                -> NalgebraLapackResult<(DVector<$t>, DMatrix<$t>)> {
+                let uplo = b'L';
+
+                if self.ncols() != self.nrows() {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let n = self.ncols();
+                let lda = n as i32;
+
+                let mut w: DVector<$t> = DVector::from_element(n, 0.0);
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut info = 0;
+
+                $lapack_func(jobz, uplo, n as i32, self.as_mut_vector(), lda, w.as_mut(),
+                    &mut work, lwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let work = workspace::<$t>(scratch, lwork as usize);
+
+                ::diagnostics::record(stringify!($lapack_func), n, n, &[jobz, uplo]);
+                $lapack_func(jobz, uplo, n as i32, self.as_mut_vector(), lda, w.as_mut(),
+                    work, lwork, &mut info);
+                check_info!(info);
+
+                Ok((w, self))
+            }
+
+            fn syevr_smallest(mut self) -> NalgebraLapackResult<$t> {
+                let jobz = b'N';
+                let range = b'I';
+                let uplo = b'L';
+
+                if self.ncols() != self.nrows() {
+                    return Err(Error::from(ErrorKind::MatrixNotSquare));
+                }
+                let n = self.ncols();
+                if n == 0 {
+                    return Err(Error::from(ErrorKind::DimensionMismatch));
+                }
+                let lda = n as i32;
+
+                let mut found = 0;
+                let mut w: Vec<$t> = vec![0.0; n];
+                // The eigenvectors are not referenced with `jobz = 'N'`.
+                let mut z: Vec<$t> = vec![0.0];
+                let mut isuppz = vec![0; 2];
+                let mut work = vec![0.0];
+                let mut lwork = -1 as i32;
+                let mut iwork = vec![0];
+                let mut liwork = -1 as i32;
+                let mut info = 0;
+
+                $syevr(jobz, range, uplo, n as i32, self.as_mut_vector(), lda, 0.0, 0.0, 1, 1,
+                    0.0, &mut found, &mut w, &mut z, 1, &mut isuppz, &mut work, lwork,
+                    &mut iwork, liwork, &mut info);
+                check_info!(info);
+
+                lwork = work[0] as i32;
+                let mut work = vec![0.0; lwork as usize];
+                liwork = iwork[0];
+                let mut iwork = vec![0; liwork as usize];
+
+                ::diagnostics::record(stringify!($syevr), n, n, &[jobz, range, uplo]);
+                $syevr(jobz, range, uplo, n as i32, self.as_mut_vector(), lda, 0.0, 0.0, 1, 1,
+                    0.0, &mut found, &mut w, &mut z, 1, &mut isuppz, &mut work, lwork,
+                    &mut iwork, liwork, &mut info);
+                check_info!(info);
+
+                Ok(w[0])
+            }
+        }
+    );
+);
+
+use lapack::fortran as interface;
+
+symmetric_eigensystem_impl!(f32, interface::ssyev, interface::ssyevr);
+symmetric_eigensystem_impl!(f64, interface::dsyev, interface::dsyevr);
+
+whitening_transform_impl!(f32);
+whitening_transform_impl!(f64);
+
+symmetric_pseudoinverse_impl!(f32);
+symmetric_pseudoinverse_impl!(f64);
+
+symmetric_tridiagonalize_impl!(f32, interface::ssytrd, interface::sorgtr);
+symmetric_tridiagonalize_impl!(f64, interface::dsytrd, interface::dorgtr);
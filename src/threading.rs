@@ -0,0 +1,52 @@
+#[cfg(feature = "openblas")]
+use std::os::raw::c_int;
+
+#[cfg(feature = "openblas")]
+extern "C" {
+    fn openblas_set_num_threads(num_threads: c_int);
+    fn openblas_get_num_threads() -> c_int;
+}
+
+/// set the number of threads used by the BLAS and LAPACK backend.
+///
+/// A threaded BLAS speeds up large factorizations, but oversubscribes the machine when the
+/// caller already runs many decompositions in parallel, e.g. with `eigensystem_batch()`;
+/// setting one thread avoids that. This forwards to `openblas_set_num_threads()` with the
+/// `openblas` feature. It is a no-op for the other backends, which have no such control or
+/// are configured through their own environment variables.
+///
+/// # Arguments
+///
+/// * `n` - The number of threads. Zero is treated as one.
+pub fn set_num_threads(n: usize) {
+    let n = if n == 0 { 1 } else { n };
+    set_backend_threads(n);
+}
+
+/// return the number of threads used by the BLAS and LAPACK backend.
+///
+/// # Returns
+///
+/// The number of threads with the `openblas` feature, and `None` for the backends without a
+/// threading control.
+pub fn get_num_threads() -> Option<usize> {
+    backend_threads()
+}
+
+#[cfg(feature = "openblas")]
+fn set_backend_threads(n: usize) {
+    unsafe { openblas_set_num_threads(n as c_int) }
+}
+
+#[cfg(not(feature = "openblas"))]
+fn set_backend_threads(_n: usize) {}
+
+#[cfg(feature = "openblas")]
+fn backend_threads() -> Option<usize> {
+    Some(unsafe { openblas_get_num_threads() } as usize)
+}
+
+#[cfg(not(feature = "openblas"))]
+fn backend_threads() -> Option<usize> {
+    None
+}
@@ -2,9 +2,36 @@ extern crate nalgebra_lapack;
 extern crate nalgebra as na;
 extern crate num;
 
-use nalgebra_lapack::{SVD, Eigensystem, Inverse, Cholesky};
+use nalgebra_lapack::{SVD, Eigensystem, Inverse, Solve, Cholesky, EigenResult,
+                      SymmetricEigensystem, LapackScalar, ColumnSpace, DeterminantSign,
+                      GeneralizedEigensystem, LU, LeastSquares, WhiteningTransform,
+                      SymmetricPseudoinverse, SvdWith, SvdAlgorithm, SvdResult, MatrixSign,
+                      solve_care, Orthonormalize, PartialSvd, InverseIteration,
+                      BlockDiagonalEigensystem, TruncatedSvd,
+                      eigensystem_batch, StructuredSolve, SolveMethod, real_schur_to_complex,
+                      GeneralizedEigenvectors, polynomial_roots, last_routine, BalancePair,
+                      GeneralizedEigenvalue, orthogonal_procrustes, kabsch, ToRealChecked,
+                      SymmetricTridiagonalize, DistanceToSingularity, MatrixFunction,
+                      canonical_correlations, ShiftInvert, NumericalRange, CholeskyFactorize,
+                      PivotedCholesky, SkewSymmetricEigenvalues, RankRevealingQr, IterOptions,
+                      TraceOfFunction, BlockSvd, principal_angles, LapackScratch,
+                      DeterminantAndInverse, nearest_rotation, BandedMatrix, InverseSqrt,
+                      MixedPrecisionSvd, EigenvalueRange, set_num_threads, get_num_threads,
+                      ScaledSolve, log_determinant_ratio, OrthogonalEigenvalues,
+                      rotation_from_skew, GroupInverse, StableSubspace,
+                      EigensystemRowVectors, SvdHandle, routine_call_count, HermitianSolve,
+                      QrFactorize, MatrixBlock, ScaledDeterminant,
+                      SymmetricTrigonometric, PseudoDeterminant,
+                      SymmetricGeneralizedEigensystem, CholeskyOfInverse, Triangle,
+                      InverseOperatorNorm, laplacian_eigenmaps, DiagonalFastPath,
+                      quadratic_eigensystem, DepartureFromNormality, solve_block_tridiagonal,
+                      RealIfSymmetric, RealOrComplexEigensystem, PolarNewton,
+                      MutualCoherence, SvdCutoff, pca_dimension_mle,
+                      apply_permutation, TraceOfInverse, ComplexLeastSquares,
+                      FactorizeWithDiagnostics, spd_geometric_mean, DeterministicEigensystem,
+                      NumericalAbscissa, MatrixAbs, ReducedRowEchelon, SchurComplement};
 
-use na::{DMatrix, DVector, Norm, ColumnSlice, Iterable, Eye, Transpose};
+use na::{DMatrix, DVector, Norm, Column, ColumnSlice, Iterable, Eye, Transpose};
 use num::complex::Complex;
 
 #[test]
@@ -286,3 +313,3904 @@ fn test_cholesky_complex() {
     let a_recomposed = real_only_mat(a_recomposed_complex);
     assert!(na::approx_eq(&ar, &a_recomposed));
 }
+
+#[test]
+fn test_bauer_fike_condition_normal() {
+    // A symmetric matrix is normal, so its eigenvectors are orthonormal.
+    let m: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[2.0, 1.0,
+          1.0, 2.0]);
+    let result = EigenResult::from(m.eigensystem().unwrap());
+    let kappa = result.bauer_fike_condition().unwrap();
+    assert!((kappa - 1.0).abs() < 1e-10);
+}
+
+#[test]
+fn test_bauer_fike_condition_nearly_defective() {
+    // Nearly a Jordan block: the two eigenvectors are almost parallel.
+    let m: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 1.0,
+          0.0, 1.0 + 1e-6]);
+    let result = EigenResult::from(m.eigensystem().unwrap());
+    let kappa = result.bauer_fike_condition().unwrap();
+    assert!(kappa > 1e5);
+}
+
+fn assert_symmetric_eigensystem(a: &DMatrix<f64>, vals: &DVector<f64>, vecs: &DMatrix<f64>) {
+    let n = a.nrows();
+    for i in 1..n {
+        assert!(vals[i - 1] <= vals[i]);
+    }
+    for i in 0..n {
+        let v = vecs.column(i);
+        let residual = (a * &v) - (v.clone() * vals[i]);
+        assert!(residual.norm() < 1e-10);
+    }
+    let eye: DMatrix<f64> = DMatrix::new_identity(n);
+    assert!(na::approx_eq(&(vecs.transpose() * vecs), &eye));
+}
+
+#[test]
+fn test_symmetric_eigensystem_closed_form_2x2() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 0.5,
+          0.5, 3.0]);
+
+    let closed = a.symmetric_eigensystem_closed_form().unwrap();
+    let (vals, vecs) = a.clone().symmetric_eigensystem().unwrap();
+    // The closed-form path was taken.
+    assert_eq!(vals, closed.0);
+    assert_eq!(vecs, closed.1);
+    assert_symmetric_eigensystem(&a, &vals, &vecs);
+
+    let (lapack_vals, _) = a.clone().symmetric_eigensystem_lapack().unwrap();
+    assert!(na::approx_eq_eps(&vals, &lapack_vals, &1e-12));
+}
+
+#[test]
+fn test_symmetric_eigensystem_closed_form_3x3() {
+    // A diffusion-tensor-like SPD matrix.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, 0.2,
+          0.5, 0.2, 1.0]);
+
+    let closed = a.symmetric_eigensystem_closed_form().unwrap();
+    let (vals, vecs) = a.clone().symmetric_eigensystem().unwrap();
+    assert_eq!(vals, closed.0);
+    assert_eq!(vecs, closed.1);
+    assert_symmetric_eigensystem(&a, &vals, &vecs);
+
+    let (lapack_vals, _) = a.clone().symmetric_eigensystem_lapack().unwrap();
+    assert!(na::approx_eq_eps(&vals, &lapack_vals, &1e-12));
+}
+
+#[test]
+fn test_symmetric_eigensystem_closed_form_repeated() {
+    // Eigenvalues 1, 1 and 4.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 1.0,
+          1.0, 2.0, 1.0,
+          1.0, 1.0, 2.0]);
+    let (vals, vecs) = a.clone().symmetric_eigensystem().unwrap();
+    assert_symmetric_eigensystem(&a, &vals, &vecs);
+    assert!(na::approx_eq(&vals, &DVector { at: vec![1.0, 1.0, 4.0] }));
+
+    let d: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[3.0, 0.0, 0.0,
+          0.0, -1.0, 0.0,
+          0.0, 0.0, 2.0]);
+    let (vals, vecs) = d.clone().symmetric_eigensystem().unwrap();
+    assert_symmetric_eigensystem(&d, &vals, &vecs);
+    assert_eq!(vals, DVector { at: vec![-1.0, 2.0, 3.0] });
+}
+
+#[test]
+fn test_symmetric_eigensystem_closed_form_extreme_scales() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, 0.2,
+          0.5, 0.2, 1.0]);
+    let (unit_vals, unit_vecs) = a.symmetric_eigensystem_closed_form().unwrap();
+    for &scale in [1e-80, 1e80, 1e-300, 1e300].iter() {
+        let scaled = DMatrix::from_fn(3, 3, |i, j| a[(i, j)] * scale);
+        let (vals, vecs) = scaled.clone().symmetric_eigensystem().unwrap();
+        for k in 0..3 {
+            assert!((vals[k] / scale - unit_vals[k]).abs() < 1e-12);
+        }
+        assert!(na::approx_eq(&vecs, &unit_vecs));
+        assert_eq!(scaled.symmetric_eigenvalues().unwrap(), vals);
+    }
+
+    let b: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1e155, 3e155,
+          3e155, -2e155]);
+    let (vals, vecs) = b.clone().symmetric_eigensystem().unwrap();
+    let expected = [(-1.0 - 45.0f64.sqrt()) / 2.0, (-1.0 + 45.0f64.sqrt()) / 2.0];
+    for k in 0..2 {
+        assert!((vals[k] / 1e155 - expected[k]).abs() < 1e-12);
+    }
+    assert!(vecs.as_vector().iter().all(|x| x.is_finite()));
+    assert_eq!(b.symmetric_eigenvalues().unwrap(), vals);
+
+    let mut not_finite = a.clone();
+    not_finite[(1, 0)] = f64::NAN;
+    assert!(not_finite.symmetric_eigensystem_closed_form().is_none());
+}
+
+#[test]
+fn test_symmetric_eigensystem_lapack_path() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[4.0, 1.0, 0.0, 0.0,
+          1.0, 4.0, 1.0, 0.0,
+          0.0, 1.0, 4.0, 1.0,
+          0.0, 0.0, 1.0, 4.0]);
+    assert!(a.symmetric_eigensystem_closed_form().is_none());
+    let (vals, vecs) = a.clone().symmetric_eigensystem().unwrap();
+    assert_symmetric_eigensystem(&a, &vals, &vecs);
+}
+
+fn singular_values_and_eigenvalues<N: LapackScalar>(m: DMatrix<N>)
+    -> (DVector<N::Real>, DVector<Complex<N::Real>>) {
+    let (_, s, _) = N::svd(m.clone()).unwrap();
+    let (vals, _) = N::eigensystem(m).unwrap();
+    (s, vals)
+}
+
+#[test]
+fn test_lapack_scalar_generic() {
+    let mr: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[2.0, 0.0,
+          0.0, 3.0]);
+    let (s, vals) = singular_values_and_eigenvalues(mr.clone());
+    assert!(na::approx_eq(&s, &DVector { at: vec![3.0, 2.0] }));
+    assert_eq!(vals.len(), 2);
+
+    let mc: DMatrix<Complex<f64>> =
+        DMatrix::from_column_vector(2,
+                                    2,
+                                    &mr.as_vector()
+                                        .iter()
+                                        .map(|re| Complex { re: *re, im: 0.0 })
+                                        .collect::<Vec<_>>());
+    let (s, vals) = singular_values_and_eigenvalues(mc);
+    assert!(na::approx_eq(&s, &DVector { at: vec![3.0, 2.0] }));
+    assert_eq!(vals.len(), 2);
+}
+
+#[test]
+fn test_column_space_split() {
+    // The third column is the sum of the first two, so the rank is 2.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.0, 1.0,
+          2.0, 1.0, 3.0,
+          0.0, 1.0, 1.0]);
+    let (range, complement) = a.clone().column_space_split(1e-10).unwrap();
+    assert_eq!((range.nrows(), range.ncols()), (3, 2));
+    assert_eq!((complement.nrows(), complement.ncols()), (3, 1));
+
+    // Together the bases are an orthonormal basis of R^3.
+    let mut q: DMatrix<f64> = DMatrix::new_zeros(3, 3);
+    for i in 0..3 {
+        q[(i, 0)] = range[(i, 0)];
+        q[(i, 1)] = range[(i, 1)];
+        q[(i, 2)] = complement[(i, 0)];
+    }
+    let eye: DMatrix<f64> = DMatrix::new_identity(3);
+    assert!(na::approx_eq(&(q.transpose() * &q), &eye));
+
+    // The columns of `a` lie in the range and are orthogonal to the complement.
+    assert!(na::approx_eq(&(&range * (range.transpose() * &a)), &a));
+    assert!(na::approx_eq(&(complement.transpose() * &a), &DMatrix::new_zeros(1, 3)));
+}
+
+#[test]
+fn test_determinant_sign() {
+    let reflection: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 0.0,
+          0.0, -1.0]);
+    assert_eq!(reflection.determinant_sign().unwrap(), -1);
+
+    let (s, c) = 0.3_f64.sin_cos();
+    let rotation: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[c, -s,
+          s, c]);
+    assert_eq!(rotation.determinant_sign().unwrap(), 1);
+
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          2.0, 4.0]);
+    assert_eq!(singular.determinant_sign().unwrap(), 0);
+
+    let rectangular: DMatrix<f64> = DMatrix::new_zeros(2, 3);
+    assert!(rectangular.determinant_sign().is_none());
+}
+
+#[test]
+fn test_generalized_eigensystem_infinite() {
+    // B is singular, so one of the two eigenvalues is infinite.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 0.0,
+          0.0, 2.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 0.0,
+          0.0, 0.0]);
+    let (values, _) = a.generalized_eigensystem(b, 1e-12).unwrap();
+    assert_eq!(values.len(), 2);
+
+    let infinite = values.iter().filter(|x| x.is_infinite()).count();
+    assert_eq!(infinite, 1);
+
+    let finite: Vec<_> = values.iter().filter_map(|x| x.finite()).collect();
+    assert_eq!(finite.len(), 1);
+    assert!(finite[0].re.is_finite() && finite[0].im.is_finite());
+    assert!(na::approx_eq(&finite[0].re, &1.0));
+    assert!(na::approx_eq(&finite[0].im, &0.0));
+}
+
+#[test]
+fn test_generalized_eigenvalues_only() {
+    // A complex pair, a real eigenvalue and, as B is singular, an infinite one.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[0.0, -2.0, 1.0, 0.0,
+          1.0, 0.0, 0.0, 1.0,
+          0.0, 0.0, 3.0, 1.0,
+          0.0, 0.0, 0.0, 1.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[1.0, 0.0, 0.0, 0.0,
+          0.0, 1.0, 0.0, 0.0,
+          0.0, 0.0, 1.0, 0.0,
+          0.0, 0.0, 0.0, 0.0]);
+    let (full, _) = a.clone().generalized_eigensystem(b.clone(), 1e-12).unwrap();
+    let values = a.generalized_eigenvalues(b, 1e-12).unwrap();
+    assert_eq!(last_routine().unwrap().jobs, vec![b'N', b'N']);
+
+    assert_eq!(values.len(), 4);
+    assert_eq!(values.iter().filter(|x| x.is_infinite()).count(), 1);
+    for value in values.iter().filter_map(|x| x.finite()) {
+        assert!(full.iter().filter_map(|x| x.finite()).any(|y| (value - y).norm() < 1e-10));
+    }
+}
+
+#[test]
+fn test_lu_solve_and_inverse() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 2.0,
+          1.0, 5.0, 3.0,
+          2.0, 3.0, 6.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          2.0, -1.0,
+          3.0, 4.0]);
+
+    let lu = a.clone().lu().unwrap();
+    let x_solve = lu.solve(b.clone()).unwrap();
+    let (x, inverse) = lu.solve_and_inverse(b.clone()).unwrap();
+
+    assert!(na::approx_eq(&x, &x_solve));
+    assert!(na::approx_eq(&(&inverse * &b), &x));
+    assert!(na::approx_eq(&(&a * &inverse), &DMatrix::new_identity(3)));
+}
+
+#[test]
+fn test_eigen_residuals() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, -1.0, 0.0,
+          -1.0, 2.0, -1.0,
+          0.0, -1.0, 2.0]);
+    let result = EigenResult::from(a.clone().eigensystem().unwrap());
+    let residuals = result.residuals(&a);
+    assert_eq!(residuals.len(), 3);
+    for r in residuals.iter() {
+        assert!(*r < 1e-12);
+    }
+}
+
+#[test]
+fn test_solve_qr_overdetermined() {
+    // Fit a line y = c0 + c1 t to four points.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(4, 1, &[1.0, 2.9, 5.1, 7.0]);
+
+    let x_qr = a.clone().solve_qr(b.clone()).unwrap();
+    let x_svd = a.least_squares(b).unwrap();
+    assert_eq!((x_qr.nrows(), x_qr.ncols()), (2, 1));
+    assert!(na::approx_eq(&x_qr, &x_svd));
+}
+
+#[test]
+fn test_solve_qr_underdetermined() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(1, 2, &[3.0, 4.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(1, 1, &[5.0]);
+
+    // The minimum-norm solution is parallel to the row of `a`.
+    let x = a.solve_qr(b).unwrap();
+    assert!(na::approx_eq(&x, &DMatrix::from_row_vector(2, 1, &[0.6, 0.8])));
+}
+
+#[test]
+fn test_solve_qr_rank_deficient() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          2.0, 0.0,
+          3.0, 0.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 1, &[1.0, 2.0, 3.0]);
+
+    assert!(a.clone().solve_qr(b.clone()).is_err());
+
+    // The SVD-based path returns the minimum-norm solution instead.
+    let x = a.least_squares(b).unwrap();
+    assert!(na::approx_eq(&x, &DMatrix::from_row_vector(2, 1, &[1.0, 0.0])));
+}
+
+#[test]
+fn test_svd_empty() {
+    let m: DMatrix<f64> = DMatrix::new_zeros(0, 0);
+    let (u, s, vt) = m.svd().unwrap();
+    assert_eq!((u.nrows(), u.ncols()), (0, 0));
+    assert_eq!(s.len(), 0);
+    assert_eq!((vt.nrows(), vt.ncols()), (0, 0));
+
+    let m: DMatrix<f64> = DMatrix::new_zeros(3, 0);
+    let (u, s, vt) = m.svd().unwrap();
+    assert_eq!(u, DMatrix::new_identity(3));
+    assert_eq!(s.len(), 0);
+    assert_eq!((vt.nrows(), vt.ncols()), (0, 0));
+}
+
+#[test]
+fn test_svd_zero() {
+    let m: DMatrix<f64> = DMatrix::new_zeros(3, 2);
+    let (u, s, vt) = m.svd().unwrap();
+    assert_eq!(s, DVector::from_element(2, 0.0));
+    assert_eq!(u, DMatrix::new_identity(3));
+    assert_eq!(vt, DMatrix::new_identity(2));
+
+    let m: DMatrix<Complex<f64>> = DMatrix::new_zeros(2, 3);
+    let (u, s, vt) = m.svd().unwrap();
+    assert_eq!(s, DVector::from_element(2, 0.0));
+    assert_eq!(u, DMatrix::new_identity(2));
+    assert_eq!(vt, DMatrix::new_identity(3));
+}
+
+#[test]
+fn test_eigensystem_empty_and_zero() {
+    let m: DMatrix<f64> = DMatrix::new_zeros(0, 0);
+    let (vals, vecs) = m.eigensystem().unwrap();
+    assert_eq!(vals.len(), 0);
+    assert_eq!((vecs.nrows(), vecs.ncols()), (0, 0));
+
+    let m: DMatrix<f64> = DMatrix::new_zeros(3, 3);
+    let (vals, vecs) = m.eigensystem().unwrap();
+    assert_eq!(vals, DVector::from_element(3, Complex::new(0.0, 0.0)));
+    assert_eq!(vecs, DMatrix::new_identity(3));
+
+    let m: DMatrix<f64> = DMatrix::new_zeros(2, 3);
+    assert!(m.eigensystem().is_err());
+}
+
+#[test]
+fn test_whitening_transform() {
+    let c: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, 0.2,
+          0.5, 0.2, 2.0]);
+    let w = c.clone().whitening_transform().unwrap();
+    let whitened = &(&w * &c) * &w.transpose();
+    assert!(na::approx_eq(&whitened, &DMatrix::new_identity(3)));
+
+    let indefinite: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          2.0, 1.0]);
+    assert!(indefinite.whitening_transform().is_none());
+}
+
+#[test]
+fn test_symmetric_pseudoinverse() {
+    // Rank 2: the third row is the sum of the first two.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 3.0,
+          1.0, 2.0, 3.0,
+          3.0, 3.0, 6.0]);
+    let pinv = a.clone().symmetric_pseudoinverse(1e-10).unwrap();
+
+    assert!(na::approx_eq(&(&(&a * &pinv) * &a), &a));
+    assert!(na::approx_eq(&(&(&pinv * &a) * &pinv), &pinv));
+    assert_eq!(pinv, pinv.transpose());
+}
+
+#[test]
+fn test_svd_builder() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 2.0,
+          3.0, 4.0,
+          5.0, 6.0,
+          7.0, 9.0]);
+    let (u, s, vt) = m.clone().svd().unwrap();
+
+    // The default configuration matches `svd()`.
+    let full = m.clone().svd_builder().run().unwrap();
+    assert!(na::approx_eq(&full.s, &s));
+    assert!(na::approx_eq(full.u.as_ref().unwrap(), &u));
+    assert!(na::approx_eq(full.vt.as_ref().unwrap(), &vt));
+
+    for &algorithm in &[SvdAlgorithm::Standard, SvdAlgorithm::DivideAndConquer] {
+        let thin = m.clone().svd_builder().economy().algorithm(algorithm).run().unwrap();
+        assert!(na::approx_eq(&thin.s, &s));
+        let thin_u = thin.u.unwrap();
+        let thin_vt = thin.vt.unwrap();
+        assert_eq!((thin_u.nrows(), thin_u.ncols()), (4, 2));
+        assert_eq!((thin_vt.nrows(), thin_vt.ncols()), (2, 2));
+        let s_diag = DMatrix::from_fn(2, 2, |i, j| if i == j { s[i] } else { 0.0 });
+        assert!(na::approx_eq(&(&thin_u * &(&s_diag * &thin_vt)), &m));
+
+        let values_only = m.clone()
+            .svd_builder()
+            .algorithm(algorithm)
+            .compute_u(false)
+            .compute_vt(false)
+            .run()
+            .unwrap();
+        assert!(values_only.u.is_none() && values_only.vt.is_none());
+        assert!(na::approx_eq(&values_only.s, &s));
+
+        let u_only = m.clone().svd_builder().algorithm(algorithm).compute_vt(false).run().unwrap();
+        assert_eq!(u_only.u.unwrap().ncols(), 4);
+        assert!(u_only.vt.is_none());
+    }
+}
+
+#[test]
+fn test_svd_reconstruct_wide_spectrum() {
+    // Factors in f32 with singular values spanning 1e12. The exact reconstruction of these
+    // factors is computed in f64 as the reference.
+    let rotation = |a: f32, b: f32| {
+        let (sa, ca) = a.sin_cos();
+        let (sb, cb) = b.sin_cos();
+        DMatrix::from_row_vector(3, 3,
+            &[ca, -sa * cb, sa * sb,
+              sa, ca * cb, -ca * sb,
+              0.0, sb, cb])
+    };
+    let u: DMatrix<f32> = rotation(0.3, 1.1);
+    let vt: DMatrix<f32> = rotation(2.0, 0.7);
+    let s: DVector<f32> = DVector { at: vec![1.0e6, 1.0, 1.0e-6] };
+
+    let reference = DMatrix::from_fn(3, 3, |i, j| {
+        (0..3).map(|k| u[(i, k)] as f64 * s[k] as f64 * vt[(k, j)] as f64).sum::<f64>()
+    });
+
+    let naive = DMatrix::from_fn(3, 3, |i, j| {
+        let mut sum = 0.0_f32;
+        for k in 0..3 {
+            sum += u[(i, k)] * s[k] * vt[(k, j)];
+        }
+        sum
+    });
+
+    let svd = SvdResult { u: Some(u), s, vt: Some(vt) };
+    let careful = svd.reconstruct().unwrap();
+
+    let error = |m: &DMatrix<f32>| {
+        DMatrix::from_fn(3, 3, |i, j| m[(i, j)] as f64 - reference[(i, j)]).as_vector()
+            .iter()
+            .map(|x| x * x)
+            .sum::<f64>()
+            .sqrt()
+    };
+    assert!(error(&careful) < error(&naive));
+    for i in 0..3 {
+        for j in 0..3 {
+            // Correctly rounded to f32.
+            let r = reference[(i, j)];
+            assert!((careful[(i, j)] as f64 - r).abs() <= 0.5 * f32::EPSILON as f64 * r.abs());
+        }
+    }
+
+    let values_only = SvdResult::<f64> { u: None, s: DVector { at: vec![1.0] }, vt: None };
+    assert!(values_only.reconstruct().is_none());
+}
+
+#[test]
+fn test_svd_overwrite() {
+    let tall: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 2.0,
+          3.0, 4.0,
+          5.0, 6.0,
+          7.0, 9.0]);
+    let wide = tall.transpose();
+
+    for m in &[tall, wide] {
+        let k = if m.nrows() <= m.ncols() { m.nrows() } else { m.ncols() };
+        let all = m.clone().svd_builder().algorithm(SvdAlgorithm::DivideAndConquer).run().unwrap();
+        let overwritten = m.clone()
+            .svd_builder()
+            .algorithm(SvdAlgorithm::DivideAndConquer)
+            .overwrite()
+            .run()
+            .unwrap();
+        assert!(na::approx_eq(&overwritten.s, &all.s));
+
+        // The singular vectors agree up to sign.
+        let (u_all, vt_all) = (all.u.unwrap(), all.vt.unwrap());
+        let (u_o, vt_o) = (overwritten.u.clone().unwrap(), overwritten.vt.clone().unwrap());
+        assert_eq!((u_o.nrows(), u_o.ncols()), (m.nrows(), k));
+        assert_eq!((vt_o.nrows(), vt_o.ncols()), (k, m.ncols()));
+        for j in 0..k {
+            let du: f64 = (0..m.nrows()).map(|i| u_o[(i, j)] * u_all[(i, j)]).sum();
+            let dv: f64 = (0..m.ncols()).map(|i| vt_o[(j, i)] * vt_all[(j, i)]).sum();
+            assert!(na::approx_eq(&du.abs(), &1.0));
+            assert!(na::approx_eq(&dv.abs(), &1.0));
+        }
+        assert!(na::approx_eq(&overwritten.reconstruct().unwrap(), m));
+    }
+}
+
+#[test]
+fn test_matrix_sign() {
+    // Eigenvalues -2 (stable) and 3 (unstable).
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[-2.0, 1.0,
+          0.0, 3.0]);
+    let sign = a.clone().matrix_sign().unwrap();
+
+    assert!(na::approx_eq(&(&sign * &sign), &DMatrix::new_identity(2)));
+    assert!(na::approx_eq(&(&sign * &a), &(&a * &sign)));
+
+    let (values, _) = sign.eigensystem().unwrap();
+    let mut re: Vec<f64> = values.iter().map(|x| x.re).collect();
+    re.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    assert!(na::approx_eq(&re[0], &-1.0));
+    assert!(na::approx_eq(&re[1], &1.0));
+
+    // Eigenvalues +-i lie on the imaginary axis.
+    let rotation: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[0.0, 1.0,
+          -1.0, 0.0]);
+    assert!(rotation.matrix_sign().is_none());
+}
+
+#[test]
+fn test_solve_care_double_integrator() {
+    // LQR for the double integrator, with the known solution [[sqrt(3), 1], [1, sqrt(3)]].
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[0.0, 1.0,
+          0.0, 0.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(2, 1, &[0.0, 1.0]);
+    let q: DMatrix<f64> = DMatrix::new_identity(2);
+    let r: DMatrix<f64> = DMatrix::new_identity(1);
+
+    let x = solve_care(a.clone(), b.clone(), q.clone(), r).unwrap();
+
+    let sqrt3 = 3.0_f64.sqrt();
+    assert!(na::approx_eq(&x, &DMatrix::from_row_vector(2, 2, &[sqrt3, 1.0, 1.0, sqrt3])));
+
+    let residual = (&a.transpose() * &x) + (&x * &a) + q -
+                   &(&x * &b) * &(&b.transpose() * &x);
+    assert!(na::approx_eq(&residual, &DMatrix::new_zeros(2, 2)));
+
+    let (values, _) = x.symmetric_eigensystem().unwrap();
+    assert!(values.iter().all(|v| *v > 0.0));
+}
+
+#[test]
+fn test_orthonormalize_dependent() {
+    // The third column is the sum of the first two, and the fourth is twice the first.
+    let vectors: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[1.0, 0.0, 1.0, 2.0,
+          1.0, 1.0, 2.0, 2.0,
+          0.0, 1.0, 1.0, 0.0,
+          2.0, -1.0, 1.0, 4.0]);
+    let q = vectors.clone().orthonormalize().unwrap();
+    assert_eq!((q.nrows(), q.ncols()), (4, 2));
+    assert!(na::approx_eq(&(&q.transpose() * &q), &DMatrix::new_identity(2)));
+
+    // Every input vector lies in the span of the basis.
+    let projected = &q * &(&q.transpose() * &vectors);
+    assert!(na::approx_eq(&projected, &vectors));
+}
+
+#[test]
+fn test_has_clustered_eigenvalues() {
+    let near_repeated: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 1.0, 0.0,
+          0.0, 1.0 + 1e-9, 0.0,
+          0.0, 0.0, 4.0]);
+    let result = EigenResult::from(near_repeated.eigensystem().unwrap());
+    assert!(result.has_clustered_eigenvalues(1e-6));
+
+    let separated: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 1.0, 0.0,
+          0.0, 2.0, 0.0,
+          0.0, 0.0, 4.0]);
+    let result = EigenResult::from(separated.eigensystem().unwrap());
+    assert!(!result.has_clustered_eigenvalues(1e-6));
+}
+
+#[test]
+fn test_svd_result_raw_round_trip() {
+    let u: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          0.0, 0.6,
+          0.0, 0.8]);
+    let vt: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[0.0, 1.0,
+          1.0, 0.0]);
+    let svd = SvdResult {
+        u: Some(u.clone()),
+        s: DVector { at: vec![5.0, 2.0] },
+        vt: Some(vt.clone()),
+    };
+
+    let (raw_u, raw_s, raw_vt, m, n) = svd.into_raw();
+    assert_eq!((m, n), (3, 2));
+    assert_eq!(raw_u, vec![1.0, 0.0, 0.0, 0.0, 0.6, 0.8]);
+
+    let svd = SvdResult::from_raw(raw_u, raw_s, raw_vt, m, n).unwrap();
+    assert_eq!(svd.u, Some(u));
+    assert_eq!(svd.s, DVector { at: vec![5.0, 2.0] });
+    assert_eq!(svd.vt, Some(vt));
+
+    let values_only = SvdResult::<f64> { u: None, s: DVector { at: vec![1.0] }, vt: None };
+    let (raw_u, raw_s, raw_vt, m, n) = values_only.into_raw();
+    let values_only = SvdResult::<f64>::from_raw(raw_u, raw_s, raw_vt, m, n).unwrap();
+    assert!(values_only.u.is_none() && values_only.vt.is_none());
+
+    assert!(SvdResult::<f64>::from_raw(vec![1.0; 5], vec![1.0], vec![], 3, 1).is_err());
+}
+
+#[test]
+fn test_partial_svd_top_3() {
+    let m: DMatrix<f64> = DMatrix::from_fn(30, 20, |i, j| 1.0 / (i + 2 * j + 1) as f64);
+    let (_, s, _) = m.clone().svd().unwrap();
+
+    let partial = m.clone().partial_svd(3, 1e-12, 200).unwrap();
+    assert_eq!(partial.s.len(), 3);
+    for i in 0..3 {
+        assert!((partial.s[i] - s[i]).abs() <= 1e-9 * s[0]);
+    }
+
+    let u = partial.u.unwrap();
+    let vt = partial.vt.unwrap();
+    assert_eq!((u.nrows(), u.ncols()), (30, 3));
+    assert_eq!((vt.nrows(), vt.ncols()), (3, 20));
+    assert!(na::approx_eq(&(&u.transpose() * &u), &DMatrix::new_identity(3)));
+    assert!(na::approx_eq(&(&vt * &vt.transpose()), &DMatrix::new_identity(3)));
+}
+
+#[test]
+fn test_solve_reporting_rank() {
+    // The first two rows are equal, so the rank is 2 and (1, -1, 0) spans the null space.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 1.0, 0.0,
+          1.0, 1.0, 0.0,
+          0.0, 0.0, 1.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 1, &[1.0, 3.0, 2.0]);
+
+    let (x, rank) = a.solve_reporting_rank(b).unwrap();
+    assert_eq!(rank, 2);
+    // Least squares requires x0 + x1 = 2 and x2 = 2; the minimum norm has x0 = x1.
+    assert!(na::approx_eq(&x, &DMatrix::from_row_vector(3, 1, &[1.0, 1.0, 2.0])));
+}
+
+#[test]
+fn test_eigenvector_for() {
+    // The triangular matrix of `test_eigenvalues_wikipedia_triangular`.
+    let mat: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.0, 0.0,
+          1.0, 2.0, 0.0,
+          2.0, 3.0, 3.0]);
+    let x = mat.eigenvector_for(Complex::new(2.0, 0.0), 1e-12).unwrap();
+
+    let expected = DVector { at: vec![0.0, 1.0, -3.0] }.normalize();
+    assert!(x.iter().all(|c| c.im.abs() < 1e-12));
+    let actual = DVector { at: x.iter().map(|c| c.re).collect() };
+    // The largest component is made positive.
+    assert!(na::approx_eq(&actual, &(-expected)));
+}
+
+#[test]
+fn test_block_diagonal_eigensystem() {
+    let first: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[2.0, 1.0,
+          0.0, 3.0]);
+    let second: DMatrix<f64> = DMatrix::from_row_vector(1, 1, &[-1.0]);
+    let blocks = [first.clone(), second.clone()];
+
+    let (values, vectors) = blocks.block_diagonal_eigensystem().unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!((vectors.nrows(), vectors.ncols()), (3, 3));
+
+    // The spectrum is the union of the block spectra.
+    let mut expected: Vec<f64> = first.eigensystem().unwrap().0.iter().map(|x| x.re).collect();
+    expected.extend(second.eigensystem().unwrap().0.iter().map(|x| x.re));
+    let actual: Vec<f64> = real_only(&values).at;
+    assert_eq!(actual, expected);
+
+    // Each column is an eigenvector of the assembled matrix.
+    let assembled: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 0.0,
+          0.0, 3.0, 0.0,
+          0.0, 0.0, -1.0]);
+    let result = EigenResult { eigen_values: values, eigen_vectors: vectors };
+    assert!(result.residuals(&assembled).iter().all(|r| *r < 1e-12));
+}
+
+#[test]
+fn test_truncated_svd_energy() {
+    // Singular values 4, 2, 1 with energies 16, 4, 1 out of 21.
+    let m: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, 4.0, 0.0,
+          0.0, 0.0, 2.0,
+          1.0, 0.0, 0.0]);
+    let total = 21.0;
+
+    for &(fraction, expected_k) in &[(0.5, 1), (0.75, 1), (0.9, 2), (0.99, 3)] {
+        let svd = m.clone().truncated_svd_energy(fraction).unwrap();
+        let k = svd.s.len();
+        assert_eq!(k, expected_k);
+        let energy: f64 = svd.s.iter().map(|x| x * x).sum();
+        assert!(energy >= fraction * total - 1e-12);
+        assert_eq!(svd.u.unwrap().ncols(), k);
+        assert_eq!(svd.vt.unwrap().nrows(), k);
+    }
+}
+
+#[test]
+fn test_weighted_least_squares() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(4, 1, &[1.0, 2.9, 5.1, 7.0]);
+    let weights = DVector { at: vec![1.0, 4.0, 0.25, 9.0] };
+
+    let x = a.clone().weighted_least_squares(b.clone(), weights).unwrap();
+
+    let scale = [1.0, 2.0, 0.5, 3.0];
+    let scaled_a = DMatrix::from_fn(4, 2, |i, j| scale[i] * a[(i, j)]);
+    let scaled_b = DMatrix::from_fn(4, 1, |i, j| scale[i] * b[(i, j)]);
+    let expected = scaled_a.least_squares(scaled_b).unwrap();
+    assert!(na::approx_eq(&x, &expected));
+
+    assert!(a.weighted_least_squares(b, DVector { at: vec![1.0; 3] }).is_err());
+}
+
+#[test]
+fn test_svd_canonicalize_degenerate() {
+    // diag(2, 1, 1), whose singular value 1 is repeated. Two decompositions that differ by a
+    // rotation within the degenerate subspace.
+    let (sin, cos) = 0.7_f64.sin_cos();
+    let rotation: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.0, 0.0,
+          0.0, cos, -sin,
+          0.0, sin, cos]);
+    let s: DVector<f64> = DVector { at: vec![2.0, 1.0, 1.0] };
+    let mut first: SvdResult<f64> = SvdResult {
+        u: Some(DMatrix::new_identity(3)),
+        s: s.clone(),
+        vt: Some(DMatrix::new_identity(3)),
+    };
+    let mut second = SvdResult {
+        u: Some(rotation.clone()),
+        s,
+        vt: Some(rotation.transpose()),
+    };
+    let expected = first.reconstruct().unwrap();
+    assert!(na::approx_eq(&second.reconstruct().unwrap(), &expected));
+
+    first.canonicalize_degenerate(1e-12);
+    second.canonicalize_degenerate(1e-12);
+    assert!(na::approx_eq(first.u.as_ref().unwrap(), second.u.as_ref().unwrap()));
+    assert!(na::approx_eq(first.vt.as_ref().unwrap(), second.vt.as_ref().unwrap()));
+    assert!(na::approx_eq(&second.reconstruct().unwrap(), &expected));
+
+    // The simple singular value keeps its vectors.
+    assert_eq!(second.u.as_ref().unwrap()[(0, 0)], 1.0);
+}
+
+#[test]
+fn test_is_diagonalizable() {
+    // A Jordan block has a single eigenvector, so LAPACK returns two parallel ones.
+    let jordan: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[3.0, 1.0,
+          0.0, 3.0]);
+    let result = EigenResult::from(jordan.eigensystem().unwrap());
+    assert!(!result.is_diagonalizable(1e-8).unwrap());
+
+    let m: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          0.0, 3.0]);
+    let result = EigenResult::from(m.eigensystem().unwrap());
+    assert!(result.is_diagonalizable(1e-8).unwrap());
+}
+
+#[test]
+fn test_eigensystem_batch() {
+    let mats: Vec<DMatrix<f64>> = (0..8)
+        .map(|k| DMatrix::from_fn(3, 3, |i, j| ((i * 3 + j + k) % 5) as f64 - 2.0))
+        .collect();
+    let results = eigensystem_batch(mats.clone());
+    assert_eq!(results.len(), mats.len());
+    for (m, result) in mats.into_iter().zip(results) {
+        let batch = result.unwrap();
+        let (values, vectors) = m.eigensystem().unwrap();
+        // Each solve is independent of the others, so the results are identical.
+        assert_eq!(batch.eigen_values.at, values.at);
+        assert_eq!(batch.eigen_vectors.as_vector(), vectors.as_vector());
+    }
+}
+
+#[test]
+fn test_solve_vector() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.0,
+          1.0, 3.0, 1.0,
+          0.0, 1.0, 2.0]);
+    let b: DVector<f64> = DVector { at: vec![1.0, 2.0, 3.0] };
+
+    let x = a.clone().solve_vector(b.clone()).unwrap();
+    let x_matrix = a.clone().solve(DMatrix::from_column_vector(3, 1, &b.at)).unwrap();
+    assert!(na::approx_eq(&DMatrix::from_column_vector(3, 1, &x.at), &x_matrix));
+
+    let x_lstsq = a.least_squares_vector(b).unwrap();
+    assert!(na::approx_eq(&x_lstsq, &x));
+}
+
+fn check_solve_auto(a: DMatrix<f64>, expected: SolveMethod) {
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          2.0, 1.0,
+          3.0, -1.0]);
+    let (x, method) = a.clone().solve_auto_reporting_method(b.clone()).unwrap();
+    assert_eq!(method, expected);
+    assert!(na::approx_eq(&(&a * &x), &b));
+}
+
+#[test]
+fn test_solve_auto_cholesky() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.0,
+          1.0, 3.0, 1.0,
+          0.0, 1.0, 2.0]);
+    check_solve_auto(a, SolveMethod::Cholesky);
+}
+
+#[test]
+fn test_solve_auto_symmetric_indefinite() {
+    // A positive diagonal, but eigenvalues of both signs.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 3.0, 0.0,
+          3.0, 1.0, 2.0,
+          0.0, 2.0, 1.0]);
+    check_solve_auto(a, SolveMethod::SymmetricIndefinite);
+}
+
+#[test]
+fn test_solve_auto_general() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.0,
+          2.0, 3.0, 1.0,
+          0.0, 1.0, 2.0]);
+    check_solve_auto(a, SolveMethod::General);
+}
+
+#[test]
+fn test_solve_auto_diagonal() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 0.0, 0.0,
+          0.0, -2.0, 0.0,
+          0.0, 0.0, 0.5]);
+    check_solve_auto(a, SolveMethod::Diagonal);
+}
+
+#[test]
+fn test_solve_auto_triangular() {
+    let upper: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, -1.0,
+          0.0, 3.0, 2.0,
+          0.0, 0.0, 2.0]);
+    check_solve_auto(upper.clone(), SolveMethod::Triangular);
+    check_solve_auto(upper.transpose(), SolveMethod::Triangular);
+}
+
+#[test]
+fn test_solve_symmetric_kkt() {
+    // The KKT matrix [H A^T; A 0] of an equality-constrained quadratic program.
+    let kkt: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[2.0, 0.0, 1.0, 1.0,
+          0.0, 2.0, 1.0, -1.0,
+          1.0, 1.0, 0.0, 0.0,
+          1.0, -1.0, 0.0, 0.0]);
+    let b: DMatrix<f64> = DMatrix::from_column_vector(4, 1, &[1.0, 2.0, 3.0, 4.0]);
+
+    let x = kkt.clone().solve_symmetric(b.clone()).unwrap();
+    let x_general = kkt.solve(b).unwrap();
+    assert!(na::approx_eq(&x, &x_general));
+}
+
+#[test]
+fn test_svd_apply_sign_convention() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[2.0, 0.0,
+          0.0, -3.0]);
+    let (u, s, vt) = m.clone().svd().unwrap();
+    let mut result = SvdResult { u: Some(u), s, vt: Some(vt) };
+    result.apply_sign_convention();
+
+    // As returned by `numpy.linalg.svd`.
+    let u_numpy: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[0.0, 1.0,
+          1.0, 0.0]);
+    let vt_numpy: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[0.0, -1.0,
+          1.0, 0.0]);
+    assert!(na::approx_eq(result.u.as_ref().unwrap(), &u_numpy));
+    assert!(na::approx_eq(result.vt.as_ref().unwrap(), &vt_numpy));
+    assert!(na::approx_eq(&result.reconstruct().unwrap(), &m));
+}
+
+#[test]
+fn test_real_schur_to_complex() {
+    // A real Schur form with the eigenvalues 1 +- 2i in a 2x2 block and 5.
+    let t: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 2.0, 3.0,
+          -2.0, 1.0, 4.0,
+          0.0, 0.0, 5.0]);
+    let (sin, cos) = 0.3_f64.sin_cos();
+    let z: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[cos, -sin, 0.0,
+          sin, cos, 0.0,
+          0.0, 0.0, 1.0]);
+    let (tc, zc) = real_schur_to_complex(t.clone(), z.clone()).unwrap();
+
+    for j in 0..3 {
+        for i in (j + 1)..3 {
+            assert_eq!(tc[(i, j)].norm(), 0.0);
+        }
+    }
+    let expected = [Complex::new(1.0, 2.0), Complex::new(1.0, -2.0), Complex::new(5.0, 0.0)];
+    for i in 0..3 {
+        assert!((tc[(i, i)] - expected[i]).norm() < 1e-12);
+    }
+
+    // Both factorizations give the same matrix.
+    let a = &(&z * &t) * &z.transpose();
+    let zc_h = DMatrix::from_fn(3, 3, |i, j| zc[(j, i)].conj());
+    let ac = &(&zc * &tc) * &zc_h;
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((ac[(i, j)] - Complex::new(a[(i, j)], 0.0)).norm() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_eigen_trace_and_det() {
+    // The triangular matrix of `test_eigenvalues_wikipedia_triangular`.
+    let m: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.0, 0.0,
+          1.0, 2.0, 0.0,
+          2.0, 3.0, 3.0]);
+    let result = EigenResult::from(m.clone().eigensystem().unwrap());
+    let (trace, det) = result.trace_and_det();
+    assert!((trace - Complex::new(6.0, 0.0)).norm() < 1e-12);
+    assert!((det - Complex::new(6.0, 0.0)).norm() < 1e-12);
+    assert!(result.verify_against(&m, 1e-12).unwrap());
+
+    let other: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.0, 0.0,
+          1.0, 2.0, 0.0,
+          2.0, 3.0, 4.0]);
+    assert!(!result.verify_against(&other, 1e-12).unwrap());
+}
+
+#[test]
+fn test_generalized_eigenvectors_jordan_block() {
+    let jordan: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[3.0, 1.0,
+          0.0, 3.0]);
+    let chains = jordan.clone().generalized_eigenvectors(1e-8).unwrap();
+    assert_eq!(chains.len(), 1);
+    let chain = &chains[0];
+    assert!((chain.eigen_value - Complex::new(3.0, 0.0)).norm() < 1e-8);
+    assert_eq!(chain.vectors.len(), 2);
+
+    // (A - 3 I) v_1 = 0 and (A - 3 I) v_2 = v_1.
+    let shifted = DMatrix::from_fn(2, 2, |i, j| {
+        Complex::new(jordan[(i, j)] - if i == j { 3.0 } else { 0.0 }, 0.0)
+    });
+    let zero = DVector { at: vec![Complex::new(0.0, 0.0); 2] };
+    for k in 0..2 {
+        let image = &shifted * &chain.vectors[k];
+        let expected = if k == 0 { &zero } else { &chain.vectors[k - 1] };
+        for i in 0..2 {
+            assert!((image[i] - expected[i]).norm() < 1e-8);
+        }
+    }
+}
+
+#[test]
+fn test_svd_truncation_error() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 2.0,
+          1.0, 3.0, 0.0,
+          2.0, 0.0, 1.0]);
+    let (u, s, vt) = m.clone().svd().unwrap();
+    let result = SvdResult { u: Some(u.clone()), s: s.clone(), vt: Some(vt.clone()) };
+
+    for k in 0..4 {
+        let approximation = DMatrix::from_fn(3, 3, |i, j| {
+            (0..k).fold(0.0, |acc, p| acc + u[(i, p)] * s[p] * vt[(p, j)])
+        });
+        let difference = m.clone() - approximation;
+        let direct = difference.as_vector().iter().fold(0.0, |acc, x| acc + x * x).sqrt();
+        assert!((result.truncation_error(k) - direct).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_lu_as_transpose() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.0,
+          2.0, 5.0, 3.0,
+          1.0, 0.0, 6.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          2.0, -1.0,
+          3.0, 4.0]);
+
+    let direct = a.clone().lu().unwrap();
+    assert!(!direct.is_transposed());
+    let from_transpose = a.transpose().lu_as_transpose().unwrap();
+    assert!(from_transpose.is_transposed());
+
+    let x = direct.solve(b.clone()).unwrap();
+    assert!(na::approx_eq(&from_transpose.solve(b.clone()).unwrap(), &x));
+    assert!(na::approx_eq(&(&a * &x), &b));
+
+    let y = direct.solve_transpose(b.clone()).unwrap();
+    assert!(na::approx_eq(&from_transpose.solve_transpose(b.clone()).unwrap(), &y));
+    assert!(na::approx_eq(&(&a.transpose() * &y), &b));
+
+    let (_, inverse) = from_transpose.solve_and_inverse(b).unwrap();
+    assert!(na::approx_eq(&(&a * &inverse), &DMatrix::new_identity(3)));
+}
+
+fn sorted_roots(mut roots: Vec<Complex<f64>>) -> Vec<Complex<f64>> {
+    roots.sort_by(|a, b| (a.re, a.im).partial_cmp(&(b.re, b.im)).unwrap());
+    roots
+}
+
+#[test]
+fn test_polynomial_roots_quadratic() {
+    // x^2 - 3x + 2 = (x - 1)(x - 2), with a leading zero coefficient.
+    let roots = sorted_roots(polynomial_roots(&[0.0, 1.0, -3.0, 2.0]).unwrap());
+    assert_eq!(roots.len(), 2);
+    assert!((roots[0] - Complex::new(1.0, 0.0)).norm() < 1e-12);
+    assert!((roots[1] - Complex::new(2.0, 0.0)).norm() < 1e-12);
+
+    // x^2 + 1
+    let roots = sorted_roots(polynomial_roots(&[1.0, 0.0, 1.0]).unwrap());
+    assert!((roots[0] - Complex::new(0.0, -1.0)).norm() < 1e-12);
+    assert!((roots[1] - Complex::new(0.0, 1.0)).norm() < 1e-12);
+
+    // 2x - 1
+    let roots = polynomial_roots(&[2.0, -1.0]).unwrap();
+    assert_eq!(roots, vec![Complex::new(0.5, 0.0)]);
+
+    let empty: &[f64] = &[];
+    assert!(polynomial_roots(&[0.0, 3.0]).unwrap().is_empty());
+    assert!(polynomial_roots(empty).unwrap().is_empty());
+}
+
+#[test]
+fn test_polynomial_roots_cubic() {
+    // 2 (x + 1)(x - 2)(x - 3) = 2x^3 - 8x^2 + 2x + 12
+    let roots = sorted_roots(polynomial_roots(&[2.0, -8.0, 2.0, 12.0]).unwrap());
+    assert_eq!(roots.len(), 3);
+    let expected = [-1.0, 2.0, 3.0];
+    for i in 0..3 {
+        assert!((roots[i] - Complex::new(expected[i], 0.0)).norm() < 1e-10);
+    }
+}
+
+#[test]
+fn test_ridge_effective_dof() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    assert!((a.clone().ridge_effective_dof(1e-12).unwrap() - 2.0).abs() < 1e-8);
+    assert!(a.clone().ridge_effective_dof(1e12).unwrap() < 1e-8);
+
+    // Rank one.
+    let rank_deficient: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 2.0,
+          2.0, 4.0,
+          3.0, 6.0]);
+    assert!((rank_deficient.ridge_effective_dof(1e-12).unwrap() - 1.0).abs() < 1e-8);
+}
+
+#[test]
+fn test_ridge_least_squares() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    let b: DMatrix<f64> = DMatrix::from_column_vector(4, 1, &[1.0, 2.5, 2.9, 4.2]);
+    let lambdas = [0.0, 0.5, 2.0];
+
+    let xs = a.clone().ridge_least_squares_path(b.clone(), &lambdas).unwrap();
+    assert_eq!(xs.len(), lambdas.len());
+    for (x, &lambda) in xs.iter().zip(lambdas.iter()) {
+        // The ordinary least-squares solution of the augmented system [a; sqrt(lambda) I].
+        let augmented = DMatrix::from_fn(6, 2, |i, j| {
+            if i < 4 { a[(i, j)] } else if i - 4 == j { lambda.sqrt() } else { 0.0 }
+        });
+        let rhs = DMatrix::from_fn(6, 1, |i, j| if i < 4 { b[(i, j)] } else { 0.0 });
+        let expected = augmented.least_squares(rhs).unwrap();
+        assert!(na::approx_eq(x, &expected));
+        assert!(na::approx_eq(&a.clone().ridge_least_squares(b.clone(), lambda).unwrap(), x));
+    }
+}
+
+#[test]
+fn test_last_routine_economy_svd() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 2.0,
+          3.0, 4.0,
+          5.0, 6.0,
+          7.0, 8.0]);
+    m.clone().svd_builder().economy().run().unwrap();
+    let call = last_routine().unwrap();
+    assert_eq!(call.name, "dgesvd");
+    assert_eq!((call.nrows, call.ncols), (4, 2));
+    assert_eq!(call.jobs, vec![b'S', b'S']);
+
+    m.clone().svd_builder().economy().algorithm(SvdAlgorithm::DivideAndConquer).run().unwrap();
+    let call = last_routine().unwrap();
+    assert_eq!(call.name, "dgesdd");
+    assert_eq!(call.jobs, vec![b'S']);
+
+    let b: DMatrix<f64> = DMatrix::from_column_vector(4, 1, &[1.0, 2.0, 3.0, 4.0]);
+    m.least_squares(b).unwrap();
+    let call = last_routine().unwrap();
+    assert_eq!(call.name, "dgelsd");
+    assert_eq!((call.nrows, call.ncols), (4, 2));
+}
+
+fn sorted_finite_real_parts(values: &DVector<GeneralizedEigenvalue<f64>>) -> Vec<f64> {
+    let mut parts: Vec<f64> = values.at.iter().map(|x| x.finite().unwrap().re).collect();
+    parts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    parts
+}
+
+#[test]
+fn test_balance_pair() {
+    // (D_1 A_0 D_2, D_1 D_2) has the eigenvalues 2 - sqrt(2), 2 and 2 + sqrt(2) of A_0, but
+    // elements ranging over many orders of magnitude.
+    let a0: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, -1.0, 0.0,
+          -1.0, 2.0, -1.0,
+          0.0, -1.0, 2.0]);
+    let d1 = [1e-6, 1.0, 1e6];
+    let d2 = [1e5, 1.0, 1e-3];
+    let a = DMatrix::from_fn(3, 3, |i, j| d1[i] * a0[(i, j)] * d2[j]);
+    let b = DMatrix::from_fn(3, 3, |i, j| if i == j { d1[i] * d2[j] } else { 0.0 });
+    let expected = [2.0 - 2.0_f64.sqrt(), 2.0, 2.0 + 2.0_f64.sqrt()];
+    let error = |values: &[f64]| {
+        (0..3).fold(0.0_f64, |acc, i| acc.max((values[i] - expected[i]).abs()))
+    };
+
+    let (unbalanced, _) = a.clone().generalized_eigensystem(b.clone(), 0.0).unwrap();
+    let unbalanced_error = error(&sorted_finite_real_parts(&unbalanced));
+
+    let balanced = a.clone().balance_pair(b.clone()).unwrap();
+    assert!(1 <= balanced.ilo && balanced.ilo <= balanced.ihi && balanced.ihi <= 3);
+    let (values, vectors) = balanced.a.clone()
+        .generalized_eigensystem(balanced.b.clone(), 0.0)
+        .unwrap();
+    let balanced_error = error(&sorted_finite_real_parts(&values));
+    assert!(balanced_error < 1e-10);
+    assert!(balanced_error <= unbalanced_error);
+
+    // The transformed eigenvectors are those of the original pair.
+    let vectors = balanced.back_transform(vectors);
+    for j in 0..3 {
+        let lambda = values[j].finite().unwrap();
+        for i in 0..3 {
+            let mut r = Complex::new(0.0, 0.0);
+            let mut scale = 0.0;
+            for k in 0..3 {
+                r += (Complex::new(a[(i, k)], 0.0) - lambda * b[(i, k)]) * vectors[(k, j)];
+                scale += (a[(i, k)].abs() + lambda.norm() * b[(i, k)].abs()) * vectors[(k, j)].norm();
+            }
+            assert!(r.norm() <= 1e-10 * scale.max(1.0));
+        }
+    }
+}
+
+#[test]
+fn test_least_squares_with_condition() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    let b: DMatrix<f64> = DMatrix::from_column_vector(4, 1, &[1.0, 2.5, 2.9, 4.2]);
+
+    let (x, condition_number, residual_norms) =
+        a.clone().least_squares_with_condition(b.clone()).unwrap();
+    assert!(na::approx_eq(&x, &a.clone().least_squares(b.clone()).unwrap()));
+
+    let (_, s, _) = a.clone().svd().unwrap();
+    assert!((condition_number - s[0] / s[1]).abs() < 1e-10 * condition_number);
+
+    let residual = b - &a * &x;
+    let expected = residual.as_vector().iter().fold(0.0, |acc, r| acc + r * r).sqrt();
+    assert_eq!(residual_norms.len(), 1);
+    assert!((residual_norms[0] - expected).abs() < 1e-12);
+}
+
+#[test]
+fn test_least_squares_full_rank_deficient() {
+    // Rank one: a = u v^T with u = (1, 2, 3) and v = (1, 2).
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 2.0,
+          2.0, 4.0,
+          3.0, 6.0]);
+    let b: DMatrix<f64> = DMatrix::from_column_vector(3, 1, &[1.0, 2.0, 3.0]);
+
+    let (x, s, rank) = a.least_squares_full(b, 1e-10).unwrap();
+    assert_eq!(rank, 1);
+    assert_eq!(s.len(), 2);
+    assert!((s[0] - 70.0_f64.sqrt()).abs() < 1e-10);
+    assert!(s[1] < 1e-10);
+    // The minimum-norm solution is along v.
+    let expected: DMatrix<f64> = DMatrix::from_column_vector(2, 1, &[0.2, 0.4]);
+    assert!(na::approx_eq(&x, &expected));
+}
+
+/// The rotation by `angle` about the unit axis `(x, y, z)`, by Rodrigues' formula.
+fn rotation_matrix(x: f64, y: f64, z: f64, angle: f64) -> DMatrix<f64> {
+    let (s, c) = angle.sin_cos();
+    let t = 1.0 - c;
+    DMatrix::from_row_vector(3, 3,
+        &[t * x * x + c, t * x * y - s * z, t * x * z + s * y,
+          t * x * y + s * z, t * y * y + c, t * y * z - s * x,
+          t * x * z - s * y, t * y * z + s * x, t * z * z + c])
+}
+
+fn point_set() -> DMatrix<f64> {
+    DMatrix::from_row_vector(5, 3,
+        &[1.0, 0.0, 0.0,
+          0.0, 2.0, 0.0,
+          0.0, 0.0, 3.0,
+          1.0, 1.0, 1.0,
+          -1.0, 2.0, 0.5])
+}
+
+#[test]
+fn test_orthogonal_procrustes() {
+    let (x, y, z) = (1.0 / 3.0_f64.sqrt(), 1.0 / 3.0_f64.sqrt(), 1.0 / 3.0_f64.sqrt());
+    let r = rotation_matrix(x, y, z, 0.8);
+    let a = point_set();
+    let b = &a * &r;
+
+    let recovered = orthogonal_procrustes(a, b).unwrap();
+    assert!(na::approx_eq(&recovered, &r));
+}
+
+#[test]
+fn test_kabsch() {
+    let r = rotation_matrix(0.0, 0.6, 0.8, 2.0);
+    let t = [1.0, -2.0, 0.5];
+    let p = point_set();
+    // q_i = R p_i + t, with the points as rows.
+    let rotated = &p * &r.transpose();
+    let q = DMatrix::from_fn(5, 3, |i, j| rotated[(i, j)] + t[j]);
+
+    let (r_recovered, t_recovered) = kabsch(p.clone(), q).unwrap();
+    assert!(na::approx_eq(&r_recovered, &r));
+    assert!(na::approx_eq(&t_recovered, &DVector { at: t.to_vec() }));
+
+    // A mirrored copy is still aligned by a proper rotation.
+    let mirrored = DMatrix::from_fn(5, 3, |i, j| if j == 2 { -p[(i, j)] } else { p[(i, j)] });
+    let (r_mirror, _) = kabsch(p, mirrored).unwrap();
+    assert_eq!(r_mirror.determinant_sign().unwrap(), 1);
+}
+
+#[test]
+fn test_to_real_checked() {
+    let real: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[Complex::new(1.0, 0.0), Complex::new(2.0, 1e-14),
+          Complex::new(-3.0, -1e-14), Complex::new(4.0, 0.0)]);
+    let expected: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          -3.0, 4.0]);
+    assert_eq!(real.to_real_checked(1e-12).unwrap(), expected);
+
+    let complex: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[Complex::new(1.0, 0.0), Complex::new(2.0, 0.5),
+          Complex::new(-3.0, 0.0), Complex::new(4.0, 0.0)]);
+    assert!(complex.to_real_checked(1e-12).is_none());
+
+    let vector = DVector { at: vec![Complex::new(1.0, 0.0), Complex::new(2.0, 1e-3)] };
+    assert!(vector.clone().to_real_checked(1e-12).is_none());
+    assert_eq!(vector.to_real_checked(1e-2).unwrap().at, vec![1.0, 2.0]);
+}
+
+#[test]
+fn test_eigen_result_reconstruct() {
+    // The triangular matrix of `test_eigenvalues_wikipedia_triangular`.
+    let m: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.0, 0.0,
+          1.0, 2.0, 0.0,
+          2.0, 3.0, 3.0]);
+    let result = EigenResult::from(m.clone().eigensystem().unwrap());
+    let reconstructed = result.reconstruct().unwrap().to_real_checked(1e-10).unwrap();
+    assert!(na::approx_eq(&reconstructed, &m));
+
+    // A Jordan block is defective.
+    let jordan: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[3.0, 1.0,
+          0.0, 3.0]);
+    let result = EigenResult::from(jordan.eigensystem().unwrap());
+    assert!(result.reconstruct().is_none());
+}
+
+#[test]
+fn test_symmetric_tridiagonalize() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[4.0, 1.0, -2.0, 2.0,
+          1.0, 2.0, 0.0, 1.0,
+          -2.0, 0.0, 3.0, -2.0,
+          2.0, 1.0, -2.0, -1.0]);
+    let (d, e, q) = a.clone().symmetric_tridiagonalize().unwrap();
+    assert_eq!(d.len(), 4);
+    assert_eq!(e.len(), 3);
+
+    assert!(na::approx_eq(&(&q.transpose() * &q), &DMatrix::new_identity(4)));
+    let t = &(&q.transpose() * &a) * &q;
+    for i in 0..4 {
+        for j in 0..4 {
+            let expected = if i == j {
+                d[i]
+            } else if i == j + 1 {
+                e[j]
+            } else if j == i + 1 {
+                e[i]
+            } else {
+                0.0
+            };
+            assert!((t[(i, j)] - expected).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_svd_smart() {
+    let tall: DMatrix<f64> = DMatrix::from_fn(1000, 10, |i, j| ((i * 7 + j * 13) % 11) as f64);
+    let svd = tall.svd_smart().unwrap();
+    assert_eq!(last_routine().unwrap().jobs, vec![b'S', b'S']);
+    let u = svd.u.unwrap();
+    let vt = svd.vt.unwrap();
+    assert_eq!((u.nrows(), u.ncols()), (1000, 10));
+    assert_eq!(svd.s.len(), 10);
+    assert_eq!((vt.nrows(), vt.ncols()), (10, 10));
+
+    // A nearly square matrix gets the full decomposition.
+    let square: DMatrix<f64> = DMatrix::from_fn(6, 4, |i, j| ((i * 7 + j * 13) % 11) as f64);
+    let svd = square.svd_smart().unwrap();
+    assert_eq!(last_routine().unwrap().jobs, vec![b'A', b'A']);
+    assert_eq!(svd.u.unwrap().ncols(), 6);
+}
+
+#[test]
+fn test_svd_smart_wide() {
+    let wide: DMatrix<f64> = DMatrix::from_fn(5, 50, |i, j| ((i * 7 + j * 13) % 11) as f64);
+    let direct = wide.clone().svd_builder().economy().run().unwrap();
+    let svd = wide.clone().svd_smart().unwrap();
+    // The transpose was decomposed.
+    let call = last_routine().unwrap();
+    assert_eq!((call.nrows, call.ncols), (50, 5));
+
+    let u = svd.u.unwrap();
+    let vt = svd.vt.unwrap();
+    assert_eq!((u.nrows(), u.ncols()), (5, 5));
+    assert_eq!((vt.nrows(), vt.ncols()), (5, 50));
+    let (direct_u, direct_vt) = (direct.u.unwrap(), direct.vt.unwrap());
+    for k in 0..5 {
+        assert!((svd.s[k] - direct.s[k]).abs() < 1e-10 * direct.s[0]);
+        // Each pair of singular vectors is only determined up to a common sign.
+        let sign = if u[(0, k)] * direct_u[(0, k)] < 0.0 { -1.0 } else { 1.0 };
+        for i in 0..5 {
+            assert!((u[(i, k)] - sign * direct_u[(i, k)]).abs() < 1e-8);
+        }
+        for j in 0..50 {
+            assert!((vt[(k, j)] - sign * direct_vt[(k, j)]).abs() < 1e-8);
+        }
+    }
+    let singular_values = svd.s;
+    let s = DMatrix::from_fn(5, 5, |i, j| if i == j { singular_values[i] } else { 0.0 });
+    let reconstructed = &(&u * &s) * &vt;
+    for (x, y) in reconstructed.as_vector().iter().zip(wide.as_vector().iter()) {
+        assert!((x - y).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_distance_to_singularity() {
+    let nearly_singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          1.0, 2.0 + 1e-10]);
+    assert!(nearly_singular.distance_to_singularity().unwrap() < 1e-9);
+
+    let orthogonal = rotation_matrix(0.0, 0.6, 0.8, 1.2);
+    assert!((orthogonal.distance_to_singularity().unwrap() - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_lu_solve_iter() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.0,
+          2.0, 5.0, 3.0,
+          1.0, 0.0, 6.0]);
+    let b: DMatrix<f64> = DMatrix::from_fn(3, 4, |i, j| (i * 4 + j) as f64 - 5.0);
+    let lu = a.lu().unwrap();
+    let batch = lu.solve(b.clone()).unwrap();
+
+    let columns = (0..b.ncols()).map(|j| DVector { at: (0..3).map(|i| b[(i, j)]).collect() });
+    let mut count = 0;
+    for (j, x) in lu.solve_iter(columns).enumerate() {
+        let x = x.unwrap();
+        for i in 0..3 {
+            assert!((x[i] - batch[(i, j)]).abs() < 1e-12);
+        }
+        count += 1;
+    }
+    assert_eq!(count, 4);
+
+    let wrong_size = vec![DVector { at: vec![1.0, 2.0] }];
+    assert!(lu.solve_iter(wrong_size).next().unwrap().is_err());
+}
+
+#[test]
+fn test_matrix_function_exp() {
+    // `a = s diag(1, -2, 0.5) s^-1`, so `exp(a) = s diag(e, e^-2, e^0.5) s^-1`.
+    let s: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 2.0, 0.0,
+          0.0, 1.0, 1.0,
+          1.0, 0.0, 3.0]);
+    let s_inv = s.clone().inv().unwrap();
+    let lambda = [1.0f64, -2.0, 0.5];
+    let a = &(&s * &DMatrix::from_fn(3, 3, |i, j| if i == j { lambda[i] } else { 0.0 })) * &s_inv;
+    let expected = &(&s * &DMatrix::from_fn(3, 3, |i, j| {
+        if i == j { lambda[i].exp() } else { 0.0 }
+    })) * &s_inv;
+
+    let exp_a = a.matrix_function(|z: Complex<f64>| z.exp()).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((exp_a[(i, j)] - Complex::new(expected[(i, j)], 0.0)).norm() < 1e-10);
+        }
+    }
+
+    // The generator of a rotation has eigenvalues `+-i theta`, and its exponential is the
+    // rotation.
+    let theta = 0.7f64;
+    let g: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[0.0, -theta, theta, 0.0]);
+    let r = g.matrix_function(|z: Complex<f64>| z.exp()).unwrap();
+    let expected = [theta.cos(), -theta.sin(), theta.sin(), theta.cos()];
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!((r[(i, j)] - Complex::new(expected[i * 2 + j], 0.0)).norm() < 1e-12);
+        }
+    }
+
+    // A Jordan block: exp([[1, 1], [0, 1]]) = e [[1, 1], [0, 1]].
+    let e = 1.0f64.exp();
+    let repeated: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 1.0, 0.0, 1.0]);
+    let exp_j = repeated.matrix_function(|z: Complex<f64>| z.exp()).unwrap();
+    let expected = [e, e, 0.0, e];
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!((exp_j[(i, j)] - Complex::new(expected[i * 2 + j], 0.0)).norm() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_matrix_function_close_eigenvalues() {
+    let e = 1.0f64.exp();
+
+    let identity: DMatrix<f64> = DMatrix::from_fn(3, 3, |i, j| if i == j { 1.0 } else { 0.0 });
+    let exp_i = identity.matrix_function(|z: Complex<f64>| z.exp()).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { e } else { 0.0 };
+            assert!((exp_i[(i, j)] - Complex::new(expected, 0.0)).norm() < 1e-12);
+        }
+    }
+
+    // I + J, with J the matrix of ones, has the eigenvalue 1 twice and 4 once, and
+    // exp(I + J) = e (I + (e^3 - 1) / 3 J).
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 1.0,
+          1.0, 2.0, 1.0,
+          1.0, 1.0, 2.0]);
+    let exp_a = a.matrix_function(|z: Complex<f64>| z.exp()).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = e * ((e.powi(3) - 1.0) / 3.0 + if i == j { 1.0 } else { 0.0 });
+            assert!((exp_a[(i, j)] - Complex::new(expected, 0.0)).norm() < 1e-10);
+        }
+    }
+
+    let jordan: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[4.0, 1.0, 0.0, 4.0]);
+    let sqrt_j = jordan.matrix_function(|z: Complex<f64>| z.sqrt()).unwrap();
+    let expected = [2.0, 0.25, 0.0, 2.0];
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!((sqrt_j[(i, j)] - Complex::new(expected[i * 2 + j], 0.0)).norm() < 1e-12);
+        }
+    }
+
+    // exp([[a, 1], [0, b]]) has the off-diagonal entry (e^b - e^a) / (b - a), which the
+    // plain recurrence would evaluate with cancellation for b this close to a.
+    let b = 1.0 + 1e-9;
+    let near: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 1.0, 0.0, b]);
+    let exp_n = near.matrix_function(|z: Complex<f64>| z.exp()).unwrap();
+    let expected = [e, e * (1.0 + 0.5e-9), 0.0, b.exp()];
+    for i in 0..2 {
+        for j in 0..2 {
+            assert!((exp_n[(i, j)] - Complex::new(expected[i * 2 + j], 0.0)).norm() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_matrix_function_singularity_in_contour() {
+    // The circle around the cluster {0.001, 0.05} encloses zero, where `ln` is singular.
+    let near_zero: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[0.001, 1.0, 0.0, 0.05]);
+    assert!(near_zero.matrix_function(|z: Complex<f64>| z.ln()).is_none());
+
+    // Away from zero the same cluster shape is fine.
+    let away: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[2.0, 1.0, 0.0, 2.05]);
+    let ln_a = away.matrix_function(|z: Complex<f64>| z.ln()).unwrap();
+    let expected = (2.05f64.ln() - 2.0f64.ln()) / 0.05;
+    assert!((ln_a[(0, 1)] - Complex::new(expected, 0.0)).norm() < 1e-12);
+}
+
+#[test]
+fn test_symmetric_eigensystem_checked() {
+    let nonsymmetric: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 0.0,
+          0.0, 3.0, 1.0,
+          0.0, 0.0, 4.0]);
+    match nonsymmetric.symmetric_eigensystem_checked(1e-8) {
+        Err(nalgebra_lapack::NalgebraLapackError(
+            nalgebra_lapack::errors::ErrorKind::MatrixNotSymmetric, _)) => {}
+        other => panic!("expected a MatrixNotSymmetric error, got {:?}", other),
+    }
+
+    let symmetric: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 0.0,
+          1.0, 3.0, 1.0,
+          0.0, 1.0, 4.0]);
+    let (checked, _) = symmetric.clone().symmetric_eigensystem_checked(1e-8).unwrap();
+    let (unchecked, _) = symmetric.symmetric_eigensystem().unwrap();
+    assert_eq!(checked.at, unchecked.at);
+}
+
+#[test]
+fn test_canonical_correlations() {
+    // Orthogonal zero-mean columns of the 8x8 Hadamard matrix.
+    let h = |k: usize, i: usize| if (i & k).count_ones() % 2 == 0 { 1.0 } else { -1.0 };
+    // `x` spans `{h1, h2}` and `y` spans `{h1 + h3, h4}`; the only correlated pair is `h1`
+    // with `h1 + h3`, at correlation `1 / sqrt(2)`. A constant offset is added to check the
+    // centering.
+    let x: DMatrix<f64> = DMatrix::from_fn(8, 2, |i, j| h(j + 1, i) + 3.0);
+    let y: DMatrix<f64> = DMatrix::from_fn(8, 2, |i, j| {
+        if j == 0 { h(1, i) + h(3, i) - 1.0 } else { 2.0 * h(4, i) }
+    });
+    let (rho, w_x, w_y) = canonical_correlations(x.clone(), y.clone()).unwrap();
+    assert_eq!(rho.len(), 2);
+    assert!((rho[0] - 0.5f64.sqrt()).abs() < 1e-12);
+    assert!(rho[1].abs() < 1e-12);
+
+    // The leading canonical variates have unit norm and the reported correlation.
+    let u: Vec<f64> = (0..8).map(|i| {
+        (0..2).fold(0.0, |acc, j| acc + (x[(i, j)] - 3.0) * w_x[(j, 0)])
+    }).collect();
+    let v: Vec<f64> = (0..8).map(|i| (0..2).fold(0.0, |acc, j| {
+        let centered = if j == 0 { y[(i, j)] + 1.0 } else { y[(i, j)] };
+        acc + centered * w_y[(j, 0)]
+    })).collect();
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b.iter()).fold(0.0, |acc, (p, q)| acc + p * q);
+    assert!((dot(&u, &u) - 1.0).abs() < 1e-12);
+    assert!((dot(&v, &v) - 1.0).abs() < 1e-12);
+    assert!((dot(&u, &v).abs() - rho[0]).abs() < 1e-12);
+
+    assert!(canonical_correlations(x, DMatrix::new_zeros(3, 1)).is_err());
+}
+
+#[test]
+fn test_eigenvalues_near() {
+    // A symmetric matrix with eigenvalues 1, 2, 4 and 7.
+    let q = rotation_matrix(0.0, 0.6, 0.8, 0.4);
+    let d = [1.0, 2.0, 4.0, 7.0];
+    let q4: DMatrix<f64> = DMatrix::from_fn(4, 4, |i, j| {
+        if i < 3 && j < 3 { q[(i, j)] } else if i == j { 1.0 } else { 0.0 }
+    });
+    let a: DMatrix<f64> = DMatrix::from_fn(4, 4, |i, j| {
+        (0..4).fold(0.0, |acc, k| acc + q4[(i, k)] * d[k] * q4[(j, k)])
+    });
+
+    let nearest = a.clone().eigenvalues_near(3.2, 1).unwrap();
+    assert_eq!(nearest.len(), 1);
+    assert!((nearest[0] - Complex::new(4.0, 0.0)).norm() < 1e-10);
+
+    let two = a.clone().eigenvalues_near(1.8, 2).unwrap();
+    assert!((two[0] - Complex::new(2.0, 0.0)).norm() < 1e-10);
+    assert!((two[1] - Complex::new(1.0, 0.0)).norm() < 1e-10);
+
+    assert_eq!(a.eigenvalues_near(0.0, 10).unwrap().len(), 4);
+}
+
+#[test]
+fn test_numerical_range_boundary_normal() {
+    // A normal matrix with eigenvalues `1 +- 2i` and `-3`, whose numerical range is the
+    // triangle with these vertices.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, -2.0, 0.0,
+          2.0, 1.0, 0.0,
+          0.0, 0.0, -3.0]);
+    let eigen_values = [Complex::new(1.0, 2.0), Complex::new(1.0, -2.0), Complex::new(-3.0, 0.0)];
+    let n_points = 16;
+    let boundary = a.numerical_range_boundary(n_points).unwrap();
+    assert_eq!(boundary.len(), n_points);
+    for (k, p) in boundary.iter().enumerate() {
+        // The boundary point is extreme in the direction `e^(i theta)`, the same as the
+        // extreme vertex of the triangle.
+        let theta = 2.0 * std::f64::consts::PI * k as f64 / n_points as f64;
+        let direction = Complex::new(theta.cos(), theta.sin());
+        let support = |z: &Complex<f64>| (z * direction.conj()).re;
+        let max_vertex = eigen_values.iter().map(support).fold(f64::MIN, f64::max);
+        assert!((support(p) - max_vertex).abs() < 1e-10);
+
+        // And it lies in the triangle: its barycentric coordinates are non-negative.
+        let (v0, v1, v2) = (eigen_values[0], eigen_values[1], eigen_values[2]);
+        let cross = |u: Complex<f64>, v: Complex<f64>| u.re * v.im - u.im * v.re;
+        let area = cross(v1 - v0, v2 - v0);
+        let l1 = cross(*p - v0, v2 - v0) / area;
+        let l2 = cross(v1 - v0, *p - v0) / area;
+        assert!(l1 >= -1e-10 && l2 >= -1e-10 && l1 + l2 <= 1.0 + 1e-10);
+    }
+}
+
+/// Check the closed-form `svd()` of a small square matrix against the LAPACK path of
+/// `svd_with()`.
+fn check_small_svd(m: DMatrix<f64>) {
+    let n = m.nrows();
+    let (u, s, vt) = m.clone().svd().unwrap();
+    let lapack = m.clone().svd_with(Default::default()).unwrap();
+    let (lapack_u, lapack_vt) = (lapack.u.unwrap(), lapack.vt.unwrap());
+
+    for i in 0..n {
+        assert!((s[i] - lapack.s[i]).abs() < 1e-12);
+    }
+    let reconstructed = DMatrix::from_fn(n, n, |i, j| {
+        (0..n).fold(0.0, |acc, k| acc + u[(i, k)] * s[k] * vt[(k, j)])
+    });
+    assert!(na::approx_eq(&reconstructed, &m));
+    assert!(na::approx_eq(&(&u.transpose() * &u), &DMatrix::new_identity(n)));
+    assert!(na::approx_eq(&(&vt * &vt.transpose()), &DMatrix::new_identity(n)));
+    // The singular values are distinct, so the vectors agree up to sign.
+    for k in 0..n {
+        let du = (0..n).fold(0.0, |acc, i| acc + u[(i, k)] * lapack_u[(i, k)]);
+        let dv = (0..n).fold(0.0, |acc, j| acc + vt[(k, j)] * lapack_vt[(k, j)]);
+        assert!((du.abs() - 1.0).abs() < 1e-10);
+        assert!((dv.abs() - 1.0).abs() < 1e-10);
+    }
+}
+
+#[test]
+fn test_svd_closed_form() {
+    check_small_svd(DMatrix::from_row_vector(2, 2, &[3.0, 1.0, -1.0, 2.0]));
+    // A reflection, with negative determinant.
+    check_small_svd(DMatrix::from_row_vector(2, 2, &[1.0, 4.0, 2.0, 1.0]));
+
+    check_small_svd(DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          -1.0, 3.0, 2.0,
+          0.0, 1.0, 5.0]));
+    // A deformation gradient with a reflection.
+    check_small_svd(DMatrix::from_row_vector(3, 3,
+        &[1.2, 0.3, 0.0,
+          0.1, -0.9, 0.2,
+          0.0, 0.4, 1.5]));
+
+    // A rank-deficient matrix, with `s_2` too small for `A^T A`, is left to LAPACK.
+    let rank_one: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 2.0, 3.0,
+          2.0, 4.0, 6.0,
+          -1.0, -2.0, -3.0]);
+    let (u, s, vt) = rank_one.clone().svd().unwrap();
+    assert!((s[0] - 84.0f64.sqrt()).abs() < 1e-12);
+    assert!(s[1].abs() < 1e-7 && s[2].abs() < 1e-7);
+    assert!(na::approx_eq(&(&u.transpose() * &u), &DMatrix::new_identity(3)));
+    let reconstructed = DMatrix::from_fn(3, 3, |i, j| {
+        (0..3).fold(0.0, |acc, k| acc + u[(i, k)] * s[k] * vt[(k, j)])
+    });
+    assert!(na::approx_eq(&reconstructed, &rank_one));
+}
+
+#[test]
+fn test_svd_closed_form_extreme_scales() {
+    let small: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[3.0, 1.0, -1.0, 2.0]);
+    let large: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          -1.0, 3.0, 2.0,
+          0.0, 1.0, 5.0]);
+    for m in [small, large].iter() {
+        let n = m.nrows();
+        let (_, unit_s, _) = m.clone().svd().unwrap();
+        for &scale in [1e155, 1e-160, 1e300, 1e-300].iter() {
+            let scaled = DMatrix::from_fn(n, n, |i, j| m[(i, j)] * scale);
+            let (u, s, vt) = scaled.svd().unwrap();
+            for k in 0..n {
+                assert!((s[k] / scale - unit_s[k]).abs() < 1e-12 * unit_s[0]);
+            }
+            assert!(na::approx_eq(&(&u.transpose() * &u), &DMatrix::new_identity(n)));
+            assert!(na::approx_eq(&(&vt * &vt.transpose()), &DMatrix::new_identity(n)));
+        }
+    }
+}
+
+#[test]
+fn test_svd_closed_form_tiny_singular_value() {
+    // Products of plane rotations, so that `A = Q1 diag(s) Q2^T` has no zero entries.
+    let rotation = |c: f64, s: f64| -> DMatrix<f64> {
+        let r1 = DMatrix::from_row_vector(3, 3, &[c, -s, 0.0, s, c, 0.0, 0.0, 0.0, 1.0]);
+        let r2 = DMatrix::from_row_vector(3, 3, &[1.0, 0.0, 0.0, 0.0, c, -s, 0.0, s, c]);
+        &r1 * &r2
+    };
+    let q1 = rotation(0.6, 0.8);
+    let q2 = rotation(0.28, 0.96);
+    for sigma in [[1.0, 1e-9, 0.0], [1.0, 1e-9, 1e-12]].iter() {
+        let a = DMatrix::from_fn(3, 3, |i, j| {
+            (0..3).fold(0.0, |acc, k| acc + q1[(i, k)] * sigma[k] * q2[(j, k)])
+        });
+        let (u, s, vt) = a.clone().svd().unwrap();
+        for k in 0..3 {
+            assert!((s[k] - sigma[k]).abs() < 1e-14);
+        }
+        for i in 0..3 {
+            for j in 0..3 {
+                let r = (0..3).fold(0.0, |acc, k| acc + u[(i, k)] * s[k] * vt[(k, j)]);
+                assert!((r - a[(i, j)]).abs() < 1e-14);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cholesky_quadratic_form() {
+    let c: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 2.0, 0.6,
+          2.0, 5.0, 1.0,
+          0.6, 1.0, 3.0]);
+    let x = DVector { at: vec![1.0, -2.0, 0.5] };
+    let factorization = c.clone().cholesky_factorization().unwrap();
+    let q = factorization.quadratic_form(&x).unwrap();
+    let call = last_routine().unwrap();
+    assert_eq!(call.name, "dtrtrs");
+    assert_eq!(call.jobs, vec![b'L', b'N', b'N']);
+
+    let c_inv = c.inv().unwrap();
+    let expected = (0..3).fold(0.0, |acc, i| {
+        acc + x[i] * (0..3).fold(0.0, |acc, j| acc + c_inv[(i, j)] * x[j])
+    });
+    assert!((q - expected).abs() < 1e-12);
+
+    assert!(factorization.quadratic_form(&DVector { at: vec![1.0] }).is_err());
+}
+
+#[test]
+fn test_eigensystem_conjugate_pair_order() {
+    // Two rotation-scaling blocks with eigenvalues `1 +- 2i` and `-0.5 +- 3i`, mixed by a
+    // similarity transform.
+    let block: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[1.0, -2.0, 0.0, 0.0,
+          2.0, 1.0, 0.0, 0.0,
+          0.0, 0.0, -0.5, 3.0,
+          0.0, 0.0, -3.0, -0.5]);
+    let s: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[1.0, 0.5, 0.0, 0.2,
+          0.0, 1.0, 0.3, 0.0,
+          0.1, 0.0, 1.0, 0.4,
+          0.0, 0.2, 0.0, 1.0]);
+    let a = &(&s * &block) * &s.clone().inv().unwrap();
+    let (values, vectors) = a.clone().eigensystem().unwrap();
+
+    for p in 0..2 {
+        let (first, second) = (values[2 * p], values[2 * p + 1]);
+        assert!(first.im > 0.0);
+        assert_eq!(second, first.conj());
+        for i in 0..4 {
+            assert_eq!(vectors[(i, 2 * p + 1)], vectors[(i, 2 * p)].conj());
+        }
+    }
+    // Each column is the eigenvector of its eigenvalue.
+    for j in 0..4 {
+        for i in 0..4 {
+            let av = (0..4)
+                .fold(Complex::new(0.0, 0.0), |acc, k| acc + vectors[(k, j)] * a[(i, k)]);
+            assert!((av - values[j] * vectors[(i, j)]).norm() < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn test_lu_null_space() {
+    // The third column is the sum of the first two, so `(1, 1, -1)` spans the null space.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 2.0, 3.0,
+          4.0, 5.0, 9.0,
+          7.0, 8.0, 15.0]);
+    let null = a.clone().lu().unwrap().null_space(1e-12);
+    assert_eq!((null.nrows(), null.ncols()), (3, 1));
+    let x = [null[(0, 0)], null[(1, 0)], null[(2, 0)]];
+    for i in 0..3 {
+        let ax = (0..3).fold(0.0, |acc, j| acc + a[(i, j)] * x[j]);
+        assert!(ax.abs() < 1e-12);
+    }
+    let expected = 1.0 / 3.0f64.sqrt();
+    assert!((x[0].abs() - expected).abs() < 1e-12);
+    assert!((x[0] - x[1]).abs() < 1e-12 && (x[0] + x[2]).abs() < 1e-12);
+
+    let regular: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[2.0, 1.0, 1.0, 3.0]);
+    assert_eq!(regular.lu().unwrap().null_space(1e-12).ncols(), 0);
+}
+
+#[test]
+fn test_cholesky_pivoted_semidefinite() {
+    // `A = B B^T` with `B` of rank 2.
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 2.0,
+          0.5, -1.0,
+          2.0, 1.5]);
+    let a = &b * &b.transpose();
+    let (permutation, lower, rank) = a.clone().cholesky_pivoted(-1.0).unwrap();
+    assert_eq!(rank, 2);
+    assert_eq!((lower.nrows(), lower.ncols()), (3, 2));
+
+    let reconstructed = &lower * &lower.transpose();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((reconstructed[(i, j)] - a[(permutation[i], permutation[j])]).abs() < 1e-12);
+        }
+    }
+    // The first pivot is the largest diagonal element.
+    assert_eq!(permutation[0], 2);
+}
+
+#[test]
+fn test_eigen_backward_error() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, 0.2,
+          0.5, 0.2, 2.0]);
+    let result = EigenResult::from(a.clone().eigensystem().unwrap());
+    assert!(result.backward_error(&a) < 1e-14);
+
+    let mut perturbed = result.clone();
+    perturbed.eigen_values[0] += Complex::new(1e-3, 0.0);
+    let error = perturbed.backward_error(&a);
+    assert!(error > 1e-5 && error < 1e-3);
+}
+
+#[test]
+fn test_skew_symmetric_eigenvalues() {
+    let g: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[0.0, -1.5, 1.5, 0.0]);
+    let values = g.skew_symmetric_eigenvalues().unwrap();
+    assert_eq!(values.at, vec![Complex::new(0.0, 1.5), Complex::new(0.0, -1.5)]);
+
+    // The cross-product matrix of `w` has eigenvalues `+-i |w|` and zero.
+    let w = [1.0, 2.0, 2.0];
+    let k: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, -w[2], w[1],
+          w[2], 0.0, -w[0],
+          -w[1], w[0], 0.0]);
+    let values = k.skew_symmetric_eigenvalues().unwrap();
+    assert_eq!(values.len(), 3);
+    assert!((values[0] - Complex::new(0.0, 3.0)).norm() < 1e-12);
+    assert_eq!(values[1], values[0].conj());
+    assert_eq!(values[2], Complex::new(0.0, 0.0));
+    assert!(values.at.iter().all(|v| v.re == 0.0));
+}
+
+#[test]
+fn test_svd_reconstruct_by_reference() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 3,
+        &[1.0, 2.0, 0.5,
+          -1.0, 0.0, 3.0,
+          2.0, 1.0, 1.0,
+          0.0, -2.0, 4.0]);
+    let result = m.clone().svd_with(Default::default()).unwrap();
+    let first = result.reconstruct().unwrap();
+    let second = result.reconstruct().unwrap();
+    assert_eq!(first, second);
+    assert!(na::approx_eq(&first, &m));
+
+    // The factors are still available after reconstructing.
+    let u = result.u.as_ref().unwrap();
+    let vt = result.vt.as_ref().unwrap();
+    assert_eq!((u.nrows(), vt.ncols()), (4, 3));
+}
+
+#[test]
+fn test_min_norm_solve() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 4,
+        &[1.0, 2.0, 0.0, -1.0,
+          0.0, 1.0, 3.0, 1.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(2, 1, &[2.0, 5.0]);
+    let x = a.clone().min_norm_solve(b.clone()).unwrap();
+    assert!(na::approx_eq(&(&a * &x), &b));
+
+    // The SVD also gives the minimum-norm solution.
+    let expected = a.clone().least_squares(b.clone()).unwrap();
+    assert!(na::approx_eq(&x, &expected));
+
+    // Adding a null vector of `a` to the solution only increases its norm.
+    let null = [6.0, -3.0, 1.0, 0.0];
+    let norm2 = |v: &[f64]| v.iter().fold(0.0, |acc, x| acc + x * x);
+    let xs: Vec<f64> = (0..4).map(|i| x[(i, 0)]).collect();
+    let shifted: Vec<f64> = (0..4).map(|i| xs[i] + 0.1 * null[i]).collect();
+    assert!(norm2(&shifted) > norm2(&xs));
+
+    let tall: DMatrix<f64> = a.transpose();
+    assert!(tall.min_norm_solve(DMatrix::new_zeros(4, 1)).is_err());
+}
+
+#[test]
+fn test_rank_revealing_qr() {
+    // The third and fourth columns are combinations of the first two.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(5, 4,
+        &[1.0, 0.0, 1.0, 2.0,
+          2.0, 1.0, 3.0, 5.0,
+          0.0, 3.0, 3.0, 3.0,
+          1.0, -1.0, 0.0, 1.0,
+          4.0, 2.0, 6.0, 10.0]);
+    let rank = a.clone().rank_revealing_qr(1e-10).unwrap();
+    assert_eq!(rank, 2);
+
+    let (_, s, _) = a.svd().unwrap();
+    let svd_rank = s.at.iter().filter(|x| **x > 1e-10 * s[0]).count();
+    assert_eq!(rank, svd_rank);
+
+    let zero: DMatrix<f64> = DMatrix::new_zeros(3, 3);
+    assert_eq!(zero.rank_revealing_qr(1e-10).unwrap(), 0);
+}
+
+#[test]
+fn test_matrix_sign_iteration_limit() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[3.0, 1.0, 0.0, -2.0]);
+    let options = IterOptions { max_iters: 1, ..IterOptions::default() };
+    match a.clone().matrix_sign_with(options) {
+        Err(nalgebra_lapack::NalgebraLapackError(
+            nalgebra_lapack::errors::ErrorKind::ConvergenceFailure, _)) => {}
+        other => panic!("expected a ConvergenceFailure error, got {:?}", other),
+    }
+
+    let sign = a.clone().matrix_sign_with(IterOptions::default()).unwrap();
+    assert!(na::approx_eq(&sign, &a.matrix_sign().unwrap()));
+}
+
+#[test]
+fn test_svd_vector_phases() {
+    let m: DMatrix<Complex<f64>> = DMatrix::from_row_vector(3, 2,
+        &[Complex::new(1.0, 2.0), Complex::new(0.0, -1.0),
+          Complex::new(-0.5, 0.0), Complex::new(2.0, 1.0),
+          Complex::new(0.0, 1.5), Complex::new(-1.0, -1.0)]);
+    let result = m.svd_with(Default::default()).unwrap();
+    let (magnitudes, phases) = result.vector_phases().unwrap();
+    let u = result.u.unwrap();
+    assert_eq!((magnitudes.nrows(), magnitudes.ncols()), (3, 3));
+    for i in 0..3 {
+        for j in 0..3 {
+            let rebuilt = Complex::from_polar(&magnitudes[(i, j)], &phases[(i, j)]);
+            assert!((rebuilt - u[(i, j)]).norm() < 1e-12);
+        }
+    }
+
+    let values_only: SvdResult<Complex<f64>> =
+        SvdResult { u: None, s: DVector { at: vec![1.0] }, vt: None };
+    assert!(values_only.vector_phases().is_none());
+}
+
+#[test]
+fn test_trace_of_function_log_determinant() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[5.0, 1.0, 0.5, 0.0,
+          1.0, 4.0, 1.0, 0.2,
+          0.5, 1.0, 3.0, 0.4,
+          0.0, 0.2, 0.4, 2.0]);
+    let log_det = a.clone().trace_of_function(|x: f64| x.ln()).unwrap();
+
+    // The log-determinant of an SPD matrix from its Cholesky factor.
+    let l = a.clone().cholesky().unwrap();
+    let expected = (0..4).fold(0.0, |acc, i| acc + 2.0 * l[(i, i)].ln());
+    assert!((log_det - expected).abs() < 1e-12);
+
+    let trace = a.clone().trace_of_function(|x: f64| x).unwrap();
+    assert!((trace - 14.0).abs() < 1e-12);
+}
+
+#[test]
+fn test_svd_of_block() {
+    let m: DMatrix<f64> = DMatrix::from_fn(4, 4, |i, j| {
+        (i * 4 + j) as f64 + if i == j { 3.0 } else { 0.0 }
+    });
+    let (u, s, vt) = m.svd_of_block(1..3, 2..4).unwrap();
+
+    let block: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[m[(1, 2)], m[(1, 3)],
+          m[(2, 2)], m[(2, 3)]]);
+    let (expected_u, expected_s, expected_vt) = block.svd().unwrap();
+    assert_eq!(u, expected_u);
+    assert_eq!(s, expected_s);
+    assert_eq!(vt, expected_vt);
+
+    assert!(m.svd_of_block(2..5, 0..1).is_err());
+}
+
+#[test]
+fn test_principal_angles() {
+    // The x-y plane and the plane spanned by x and a vector at 0.3 rad from y towards z share
+    // the x axis, and are 0.3 rad apart otherwise.
+    let theta = 0.3f64;
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 1.0,
+          0.0, 2.0,
+          0.0, 0.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[2.0, 0.0,
+          0.0, theta.cos(),
+          0.0, theta.sin()]);
+    let angles = principal_angles(a.clone(), b).unwrap();
+    assert_eq!(angles.len(), 2);
+    assert!(angles[0].abs() < 1e-7);
+    assert!((angles[1] - theta).abs() < 1e-12);
+
+    // A subspace is at zero angle from itself.
+    let same = principal_angles(a.clone(), a).unwrap();
+    assert!(same.at.iter().all(|x| x.abs() < 1e-7));
+}
+
+#[test]
+fn test_symmetric_eigenvalues() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[4.0, 1.0, -2.0, 2.0,
+          1.0, 2.0, 0.0, 1.0,
+          -2.0, 0.0, 3.0, -2.0,
+          2.0, 1.0, -2.0, -1.0]);
+    let (full, _) = a.clone().symmetric_eigensystem().unwrap();
+    let values = a.symmetric_eigenvalues().unwrap();
+    assert_eq!(values.len(), 4);
+    for i in 0..4 {
+        assert!((values[i] - full[i]).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_smallest_eigenvalue() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[4.0, 1.0, -2.0, 2.0,
+          1.0, 2.0, 0.0, 1.0,
+          -2.0, 0.0, 3.0, -2.0,
+          2.0, 1.0, -2.0, -1.0]);
+    let (full, _) = a.clone().symmetric_eigensystem().unwrap();
+    let min = full.at.iter().cloned().fold(f64::INFINITY, f64::min);
+    let smallest = a.smallest_eigenvalue().unwrap();
+    assert!((smallest - min).abs() < 1e-12);
+
+    let empty: DMatrix<f64> = DMatrix::new_zeros(0, 0);
+    assert!(empty.smallest_eigenvalue().is_none());
+}
+
+#[cfg(feature = "matrixcompare")]
+#[test]
+fn test_svd_reconstruction_matrixcompare() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 4,
+        &[1.0, -2.0, 0.5, 3.0,
+          0.0, 4.0, -1.0, 2.0,
+          2.5, 1.0, 1.0, -3.0]);
+    let (u, s, vt) = a.clone().svd().unwrap();
+    let svd = SvdResult { u: Some(u.clone()), s: s.clone(), vt: Some(vt.clone()) };
+    nalgebra_lapack::assert_reconstructs!(svd, a, 1e-12);
+
+    let sigma = DMatrix::from_fn(3, 4, |i, j| if i == j { s[i] } else { 0.0 });
+    nalgebra_lapack::assert_matrix_approx_eq!(&(&u * &sigma) * &vt, a, 1e-12);
+}
+
+#[test]
+fn test_lapack_scratch_reserve() {
+    let mut scratch = LapackScratch::new();
+    assert_eq!(scratch.capacity(), 0);
+    scratch.reserve(20);
+    assert!(scratch.capacity() >= 20);
+    let capacity = scratch.capacity();
+    // Reserving less never shrinks the buffer.
+    scratch.reserve(4);
+    assert_eq!(scratch.capacity(), capacity);
+}
+
+#[test]
+fn test_lapack_scratch_pipeline() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(5, 3,
+        &[1.0, 2.0, 0.5,
+          -1.0, 0.0, 3.0,
+          2.0, 1.0, -1.0,
+          0.5, -2.0, 1.0,
+          3.0, 1.0, 2.0]);
+    let s: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[4.0, 1.0, -2.0, 2.0,
+          1.0, 2.0, 0.0, 1.0,
+          -2.0, 0.0, 3.0, -2.0,
+          2.0, 1.0, -2.0, -1.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(5, 1, &[1.0, 2.0, 3.0, 4.0, 5.0]);
+
+    let mut scratch = LapackScratch::new();
+    let q = a.clone().orthonormalize_with_workspace(&mut scratch).unwrap();
+    let (values, vectors) = s.clone().symmetric_eigensystem_with_workspace(&mut scratch).unwrap();
+    let x = a.clone().solve_qr_with_workspace(b.clone(), &mut scratch).unwrap();
+    assert!(scratch.capacity() > 0);
+
+    assert!(na::approx_eq(&q, &a.clone().orthonormalize().unwrap()));
+    let (values_alloc, vectors_alloc) = s.symmetric_eigensystem().unwrap();
+    assert!(na::approx_eq(&values, &values_alloc));
+    assert!(na::approx_eq(&vectors, &vectors_alloc));
+    assert!(na::approx_eq(&x, &a.clone().solve_qr(b).unwrap()));
+
+    // Once large enough, the buffer is reused without growing.
+    let capacity = scratch.capacity();
+    let mut reserved = LapackScratch::new();
+    reserved.reserve(capacity);
+    let q = a.clone().orthonormalize_with_workspace(&mut reserved).unwrap();
+    assert_eq!(reserved.capacity(), capacity);
+    assert!(na::approx_eq(&q, &a.orthonormalize().unwrap()));
+}
+
+#[test]
+fn test_det_and_inverse() {
+    // A homogeneous transform: a rotation scaled by 2, then a translation.
+    let r = rotation_matrix(0.0, 0.6, 0.8, 0.7);
+    let t: DMatrix<f64> = DMatrix::from_fn(4, 4, |i, j| {
+        match (i, j) {
+            (3, 3) => 1.0,
+            (3, _) => 0.0,
+            (_, 3) => [1.0, -2.0, 0.5][i],
+            _ => 2.0 * r[(i, j)],
+        }
+    });
+    let (det, inverse) = t.clone().det_and_inverse().unwrap();
+    assert!((det - 8.0).abs() < 1e-12);
+    assert!((det - t.clone().lu().unwrap().determinant()).abs() < 1e-12);
+    assert!(na::approx_eq(&inverse, &t.clone().inv().unwrap()));
+    assert!(na::approx_eq(&(&t * &inverse), &DMatrix::new_identity(4)));
+
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+    assert!(singular.det_and_inverse().is_none());
+}
+
+#[test]
+fn test_nearest_rotation() {
+    // With a negative determinant, the polar factor of `R0 diag(2, 1, -0.5)` is the reflection
+    // `R0 diag(1, 1, -1)`, and the nearest rotation is `R0` itself.
+    let r0 = rotation_matrix(0.0, 0.6, 0.8, 0.9);
+    let d: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 0.0, 0.0,
+          0.0, 1.0, 0.0,
+          0.0, 0.0, -0.5]);
+    let a = &r0 * &d;
+    let (u, _, vt) = a.clone().svd().unwrap();
+    assert_eq!((&u * &vt).determinant_sign().unwrap(), -1);
+
+    let r = nearest_rotation(a).unwrap();
+    assert!(na::approx_eq(&r, &r0));
+    assert!(na::approx_eq(&(&r.transpose() * &r), &DMatrix::new_identity(3)));
+    assert!((r.clone().lu().unwrap().determinant() - 1.0).abs() < 1e-12);
+
+    // A proper rotation is its own nearest rotation.
+    assert!(na::approx_eq(&nearest_rotation(r0.clone()).unwrap(), &r0));
+}
+
+#[test]
+fn test_svd_rank_gap() {
+    // A rank-2 matrix plus small deterministic noise.
+    let a: DMatrix<f64> = DMatrix::from_fn(6, 5, |i, j| {
+        let (x, y) = (i as f64, j as f64);
+        (x + 1.0) * (y - 2.0) + 0.5 * (x * x - 3.0) * (2.0 * y + 1.0) +
+            1e-6 * ((7 * i + 3 * j) % 5) as f64
+    });
+    let (u, s, vt) = a.svd().unwrap();
+    let svd = SvdResult { u: Some(u), s, vt: Some(vt) };
+    let (rank, ratio) = svd.rank_gap();
+    assert_eq!(rank, 2);
+    assert!(ratio > 1e3);
+
+    let single = SvdResult::<f64> { u: None, s: DVector { at: vec![3.0] }, vt: None };
+    assert_eq!(single.rank_gap(), (1, 1.0));
+    let zeros = SvdResult::<f64> { u: None, s: DVector { at: vec![0.0, 0.0] }, vt: None };
+    assert_eq!(zeros.rank_gap(), (0, 1.0));
+}
+
+#[test]
+fn test_banded_generalized_eigensystem() {
+    let n = 5;
+    // A stiffness-like tridiagonal `A` and a pentadiagonal positive-definite `B`, with more
+    // subdiagonals than `A`.
+    let a: DMatrix<f64> = DMatrix::from_fn(n, n, |i, j| {
+        match i.abs_diff(j) {
+            0 => 2.0,
+            1 => -1.0,
+            _ => 0.0,
+        }
+    });
+    let b: DMatrix<f64> = DMatrix::from_fn(n, n, |i, j| {
+        match i.abs_diff(j) {
+            0 => 4.0,
+            1 => 1.0,
+            2 => 0.5,
+            _ => 0.0,
+        }
+    });
+    let a_band = BandedMatrix::from_dense(&a, 1).unwrap();
+    let b_band = BandedMatrix::from_dense(&b, 2).unwrap();
+    assert_eq!(a_band.bandwidth(), 1);
+    assert_eq!(b_band.dim(), n);
+    assert!(na::approx_eq(&a_band.to_dense(), &a));
+    assert!(na::approx_eq(&b_band.to_dense(), &b));
+
+    let (values, vectors) = a_band.clone().generalized_eigensystem(b_band.clone(), true).unwrap();
+    let z = vectors.unwrap();
+
+    // The dense reference: with `B = L L^T`, these are the eigenvalues of `L^-1 A L^-T`.
+    let l_inv = b.clone().cholesky().unwrap().inv().unwrap();
+    let c = &(&l_inv * &a) * &l_inv.transpose();
+    let (expected, _) = c.symmetric_eigensystem().unwrap();
+    for i in 0..n {
+        assert!((values[i] - expected[i]).abs() < 1e-12);
+    }
+    let lambda = DMatrix::from_fn(n, n, |i, j| if i == j { values[i] } else { 0.0 });
+    assert!(na::approx_eq(&(&a * &z), &(&(&b * &z) * &lambda)));
+    assert!(na::approx_eq(&(&(&z.transpose() * &b) * &z), &DMatrix::new_identity(n)));
+
+    let (values_only, none) = a_band.generalized_eigensystem(b_band, false).unwrap();
+    assert!(none.is_none());
+    assert!(na::approx_eq(&values_only, &values));
+}
+
+#[test]
+fn test_banded_from_band_storage() {
+    let ab: DMatrix<f64> = DMatrix::from_row_vector(2, 3, &[2.0, 2.0, 2.0, -1.0, -1.0, 0.0]);
+    let banded = BandedMatrix::from_band_storage(ab.clone()).unwrap();
+    assert_eq!((banded.dim(), banded.bandwidth()), (3, 1));
+    assert_eq!(banded.band_storage(), &ab);
+
+    // The storage needs a row for the diagonal.
+    assert!(BandedMatrix::<f64>::from_band_storage(DMatrix::new_zeros(0, 3)).is_err());
+}
+
+#[test]
+fn test_inverse_sqrt() {
+    let spd: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, -0.5,
+          0.5, -0.5, 2.0]);
+    let x = spd.clone().inverse_sqrt().unwrap();
+    let w = spd.clone().whitening_transform().unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((x[(i, j)].re - w[(i, j)]).abs() < 1e-12);
+            assert!(x[(i, j)].im.abs() < 1e-12);
+        }
+    }
+
+    // A rotation-like block with eigenvalues `1 +- 2i`, and an eigenvalue 3.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, -2.0, 0.0,
+          2.0, 1.0, 0.5,
+          0.0, 0.0, 3.0]);
+    let x = a.clone().inverse_sqrt().unwrap();
+    let a_c = DMatrix::from_fn(3, 3, |i, j| Complex::new(a[(i, j)], 0.0));
+    let product = &(&x * &x) * &a_c;
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((product[(i, j)] - Complex::new(expected, 0.0)).norm() < 1e-12);
+        }
+    }
+
+    // A negative real eigenvalue is on the branch cut.
+    let negative: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[-1.0, 0.0,
+          0.0, 2.0]);
+    assert!(negative.inverse_sqrt().is_none());
+}
+
+#[test]
+fn test_svd_mixed_precision() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 3,
+        &[1.0, -2.0, 0.5,
+          3.0, 0.25, -1.0,
+          -0.75, 4.0, 2.0,
+          2.0, 1.0, 1.5]);
+    let (_, s, _) = a.clone().svd().unwrap();
+    let (u, s_low, vt) = a.clone().svd_mixed_precision().unwrap();
+    assert_eq!(s_low.len(), s.len());
+    let tol = 10.0 * f32::EPSILON as f64 * s[0];
+    for i in 0..s.len() {
+        assert!((s_low[i] - s[i]).abs() < tol);
+    }
+    let sigma = DMatrix::from_fn(4, 3, |i, j| if i == j { s_low[i] } else { 0.0 });
+    let reconstructed = &(&u * &sigma) * &vt;
+    for i in 0..4 {
+        for j in 0..3 {
+            assert!((reconstructed[(i, j)] - a[(i, j)]).abs() < tol);
+        }
+    }
+}
+
+#[test]
+fn test_tridiagonal_selected_eigensystem() {
+    // The 1D Laplacian, with eigenvalues `2 - 2 cos(k pi / (n + 1))`.
+    let n = 50;
+    let d: DVector<f64> = DVector::from_element(n, 2.0);
+    let e: DVector<f64> = DVector::from_element(n - 1, -1.0);
+    let t = BandedMatrix::from_tridiagonal(&d, &e).unwrap();
+    let expected = |k: usize| {
+        2.0 - 2.0 * ((k + 1) as f64 * std::f64::consts::PI / (n + 1) as f64).cos()
+    };
+
+    let (values, vectors) = t.selected_eigensystem(EigenvalueRange::Index(0..3), true).unwrap();
+    let z = vectors.unwrap();
+    assert_eq!(values.len(), 3);
+    assert_eq!((z.nrows(), z.ncols()), (n, 3));
+    let dense = t.to_dense();
+    for k in 0..3 {
+        assert!((values[k] - expected(k)).abs() < 1e-12);
+        let residual = (0..n).fold(0.0f64, |acc, i| {
+            let tz = (0..n).fold(0.0, |acc, j| acc + dense[(i, j)] * z[(j, k)]);
+            acc.max((tz - values[k] * z[(i, k)]).abs())
+        });
+        assert!(residual < 1e-10);
+    }
+    assert!(na::approx_eq(&(&z.transpose() * &z), &DMatrix::new_identity(3)));
+
+    let upper = 0.5 * (expected(1) + expected(2));
+    let (in_interval, none) = t.selected_eigensystem(EigenvalueRange::Value(0.0, upper), false)
+        .unwrap();
+    assert!(none.is_none());
+    assert_eq!(in_interval.len(), 2);
+}
+
+#[test]
+fn test_factor_lu_with_condition() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, -2.0, 1.0,
+          -2.0, 4.0, -2.0,
+          1.0, -2.0, 3.0]);
+    let (lu, rcond) = a.clone().factor_lu_with_condition().unwrap();
+
+    // The exact reciprocal condition number in the 1-norm, from the explicit inverse.
+    let norm1 = |m: &DMatrix<f64>| {
+        (0..m.ncols())
+            .map(|j| (0..m.nrows()).fold(0.0, |acc, i| acc + m[(i, j)].abs()))
+            .fold(0.0, f64::max)
+    };
+    let exact = 1.0 / (norm1(&a) * norm1(&a.clone().inv().unwrap()));
+    // The estimate of `||A^-1||_1` is a lower bound, and usually exact for small matrices.
+    assert!(rcond >= exact * (1.0 - 1e-12));
+    assert!(rcond <= 3.0 * exact);
+
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 1, &[1.0, 2.0, 3.0]);
+    let x = lu.solve(b.clone()).unwrap();
+    assert!(na::approx_eq(&(&a * &x), &b));
+
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          2.0, 4.0]);
+    let (_, rcond) = singular.factor_lu_with_condition().unwrap();
+    assert_eq!(rcond, 0.0);
+
+    let empty: DMatrix<f64> = DMatrix::new_zeros(0, 0);
+    let (lu, rcond) = empty.factor_lu_with_condition().unwrap();
+    assert_eq!(rcond, 1.0);
+    assert_eq!(lu.solve(DMatrix::new_zeros(0, 2)).unwrap().ncols(), 2);
+}
+
+#[test]
+fn test_set_num_threads() {
+    set_num_threads(1);
+    if let Some(n) = get_num_threads() {
+        assert_eq!(n, 1);
+    }
+
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 0.0, 1.0,
+          -1.0, 3.0, 0.5,
+          0.0, 1.0, 4.0]);
+    let (u, s, vt) = a.clone().svd().unwrap();
+    let sigma = DMatrix::from_fn(3, 3, |i, j| if i == j { s[i] } else { 0.0 });
+    assert!(na::approx_eq(&(&(&u * &sigma) * &vt), &a));
+}
+
+#[test]
+fn test_solve_equilibrated() {
+    // The first row is in much larger units. Without scaling, partial pivoting keeps it as the
+    // pivot row, and the first unknown is found by a catastrophic cancellation.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 1e10,
+          1.0, 1.0]);
+    let x_true = [0.3, 0.7];
+    let b: DMatrix<f64> = DMatrix::from_row_vector(2, 1,
+        &[x_true[0] + 1e10 * x_true[1],
+          x_true[0] + x_true[1]]);
+    let error = |x: &DMatrix<f64>| {
+        (0..2).fold(0.0f64, |acc, i| acc.max((x[(i, 0)] - x_true[i]).abs()))
+    };
+
+    let plain = a.clone().solve(b.clone()).unwrap();
+    let equilibrated = a.solve_equilibrated(b).unwrap();
+    assert!(error(&equilibrated) < 1e-12);
+    assert!(error(&equilibrated) <= error(&plain));
+}
+
+#[test]
+fn test_log_determinant_ratio() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 0.0,
+          1.0, -3.0, 1.0,
+          0.0, 1.0, 4.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.5, 0.25,
+          0.0, 2.0, 1.0,
+          1.0, 0.0, 3.0]);
+    let (sign, log_ratio) = log_determinant_ratio(a.clone(), b.clone()).unwrap();
+    let (sign_a, log_a) = a.clone().lu().unwrap().sign_and_log_determinant();
+    let (sign_b, log_b) = b.clone().lu().unwrap().sign_and_log_determinant();
+    assert_eq!(sign, sign_a * sign_b);
+    assert!((log_ratio - (log_a - log_b)).abs() < 1e-12);
+    let ratio = a.lu().unwrap().determinant() / b.lu().unwrap().determinant();
+    assert_eq!(sign, if ratio < 0.0 { -1 } else { 1 });
+    assert!((log_ratio - ratio.abs().ln()).abs() < 1e-12);
+
+    // Both determinants overflow, but their ratio is `100^n`.
+    let n = 400;
+    let big_b: DMatrix<f64> = DMatrix::from_fn(n, n, |i, j| {
+        if i == j { 4.0 } else if i + 1 == j || j + 1 == i { 1.0 } else { 0.0 }
+    });
+    let big_a = DMatrix::from_fn(n, n, |i, j| 100.0 * big_b[(i, j)]);
+    assert!(big_a.clone().lu().unwrap().determinant().is_infinite());
+    let (sign, log_ratio) = log_determinant_ratio(big_a, big_b).unwrap();
+    assert_eq!(sign, 1);
+    assert!((log_ratio - n as f64 * 100f64.ln()).abs() < 1e-9);
+
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+    assert!(log_determinant_ratio(DMatrix::new_identity(2), singular).is_none());
+}
+
+#[test]
+fn test_orthogonal_eigenvalues() {
+    let theta = 0.4f64;
+    let (s, c) = theta.sin_cos();
+    let q: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[c, -s,
+          s, c]);
+    let values = q.orthogonal_eigenvalues().unwrap();
+    assert_eq!(values.len(), 2);
+    for x in values.at.iter() {
+        assert!((x.norm() - 1.0).abs() <= 2.0 * f64::EPSILON);
+        assert!((x.re - c).abs() < 1e-12);
+        assert!((x.im.abs() - s).abs() < 1e-12);
+    }
+
+    let not_orthogonal: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 0.1,
+          0.0, 1.0]);
+    match not_orthogonal.orthogonal_eigenvalues() {
+        Err(nalgebra_lapack::NalgebraLapackError(
+            nalgebra_lapack::errors::ErrorKind::MatrixNotOrthogonal, _)) => {}
+        _ => panic!("expected a MatrixNotOrthogonal error"),
+    }
+}
+
+#[test]
+fn test_rotation_from_skew() {
+    // The rotation by 0.9 rad about the unit axis (0, 0.6, 0.8).
+    let w: DVector<f64> = DVector { at: vec![0.0, 0.54, 0.72] };
+    let r = rotation_from_skew(w.clone()).unwrap();
+    assert!(na::approx_eq(&r, &rotation_matrix(0.0, 0.6, 0.8, 0.9)));
+    assert!(na::approx_eq(&(&r.transpose() * &r), &DMatrix::new_identity(3)));
+    assert!((r.clone().lu().unwrap().determinant() - 1.0).abs() < 1e-12);
+
+    // It is the matrix exponential of the cross-product matrix.
+    let k: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, -w[2], w[1],
+          w[2], 0.0, -w[0],
+          -w[1], w[0], 0.0]);
+    let exp = k.matrix_function(|z| z.exp()).unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((exp[(i, j)].re - r[(i, j)]).abs() < 1e-12);
+        }
+    }
+
+    // For a small angle, it approximates `I + [w]_x`.
+    let small: DVector<f64> = DVector { at: vec![1e-6, -2e-6, 3e-6] };
+    let r = rotation_from_skew(small.clone()).unwrap();
+    let first_order = DMatrix::from_row_vector(3, 3,
+        &[1.0, -small[2], small[1],
+          small[2], 1.0, -small[0],
+          -small[1], small[0], 1.0]);
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((r[(i, j)] - first_order[(i, j)]).abs() < 1e-11);
+        }
+    }
+    assert!(na::approx_eq(&(&r.transpose() * &r), &DMatrix::new_identity(3)));
+
+    assert!(rotation_from_skew(DVector { at: vec![1.0, 2.0] }).is_err());
+}
+
+#[test]
+fn test_solve_column_scaled() {
+    // The second unknown is about 1e300, so its column is subnormal and loses precision in the
+    // elimination unless it is scaled.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2e-315,
+          3.0, 5e-315]);
+    let x_true = [1.0, 1e300];
+    let b: DMatrix<f64> = DMatrix::from_row_vector(2, 1,
+        &[a[(0, 0)] * x_true[0] + (a[(0, 1)] * 1e300) * (x_true[1] / 1e300),
+          a[(1, 0)] * x_true[0] + (a[(1, 1)] * 1e300) * (x_true[1] / 1e300)]);
+    let relative_error = |x: &DMatrix<f64>| {
+        (0..2).fold(0.0f64, |acc, i| acc.max(((x[(i, 0)] - x_true[i]) / x_true[i]).abs()))
+    };
+
+    let plain = a.clone().solve(b.clone()).unwrap();
+    let scales: DVector<f64> = DVector { at: vec![1.0, 1e300] };
+    let scaled = a.solve_column_scaled(b, scales).unwrap();
+    assert!(relative_error(&scaled) < 1e-12);
+    assert!(relative_error(&scaled) <= relative_error(&plain));
+}
+
+#[test]
+fn test_group_inverse() {
+    // Eigenvalues 2, 3 and a simple 0, so the matrix is singular with index one.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 0.0, 0.0,
+          1.0, 3.0, 0.0,
+          1.0, 1.0, 0.0]);
+    let x = a.clone().group_inverse().unwrap();
+    let assert_close = |p: &DMatrix<f64>, q: &DMatrix<f64>| {
+        for (pi, qi) in p.as_vector().iter().zip(q.as_vector().iter()) {
+            assert!((pi - qi).abs() < 1e-10);
+        }
+    };
+    assert_close(&(&(&a * &x) * &a), &a);
+    assert_close(&(&(&x * &a) * &x), &x);
+    assert_close(&(&a * &x), &(&x * &a));
+
+    // A nilpotent matrix has index two.
+    let n: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[0.0, 1.0, 0.0, 0.0]);
+    assert!(n.group_inverse().is_none());
+}
+
+#[test]
+fn test_stable_subspace() {
+    // The leading block has the eigenvalues -1 +- i sqrt(6), so the stable subspace is
+    // spanned by the first two unit vectors.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[-1.0, 2.0, 1.0,
+          -3.0, -1.0, 0.0,
+          0.0, 0.0, 2.0]);
+    let basis = a.clone().stable_subspace().unwrap();
+    assert_eq!(basis.ncols(), 2);
+    for j in 0..2 {
+        assert!(basis[(2, j)].abs() < 1e-12);
+    }
+
+    // `A Q` stays within the span of `Q`: `(I - Q Q^T) A Q = 0`.
+    let aq = &a * &basis;
+    let residual = aq.clone() - (&basis * &(&basis.transpose() * &aq));
+    for x in residual.as_vector().iter() {
+        assert!(x.abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_eigen_result_grouped() {
+    // The eigenvalue 2 has a two-dimensional eigenspace.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 0.0, 1.0,
+          0.0, 2.0, 1.0,
+          0.0, 0.0, 5.0]);
+    let eigen = EigenResult::from(a.clone().eigensystem().unwrap());
+    let groups = eigen.grouped(1e-8);
+    assert_eq!(groups.len(), 2);
+    let (lambda, vectors) = groups.into_iter()
+        .find(|g| (g.0 - Complex::new(2.0, 0.0)).norm() < 1e-10)
+        .unwrap();
+    assert_eq!(vectors.ncols(), 2);
+    let ca = DMatrix::from_fn(3, 3, |i, j| Complex::new(a[(i, j)], 0.0));
+    let residual = &ca * &vectors - DMatrix::from_fn(3, 2, |i, j| vectors[(i, j)] * lambda);
+    for x in residual.as_vector().iter() {
+        assert!(x.norm() < 1e-10);
+    }
+    let (_, s, _) = vectors.svd().unwrap();
+    assert!(s[1] > 1e-6);
+}
+
+#[test]
+fn test_lu_complex() {
+    let a: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[Complex::new(1.0, 1.0), Complex::new(2.0, 0.0),
+          Complex::new(3.0, 0.0), Complex::new(4.0, -1.0)]);
+    let b: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 1,
+        &[Complex::new(1.0, 0.0), Complex::new(0.0, 2.0)]);
+    let (lu, rcond) = a.clone().factor_lu_with_condition().unwrap();
+
+    let x = lu.solve(b.clone()).unwrap();
+    let residual = &a * &x - b;
+    for r in residual.as_vector().iter() {
+        assert!(r.norm() < 1e-12);
+    }
+    assert!((lu.determinant() - Complex::new(-1.0, 3.0)).norm() < 1e-12);
+
+    // The estimate of `||A^-1||_1` is a lower bound, exact for such a small matrix.
+    let one_norm = |m: &DMatrix<Complex<f64>>| {
+        (0..2).map(|j| (0..2).fold(0.0, |acc, i| acc + m[(i, j)].norm()))
+            .fold(0.0f64, |acc, x| acc.max(x))
+    };
+    let exact = 1.0 / (one_norm(&a) * one_norm(&lu.inverse().unwrap()));
+    assert!(rcond >= exact * (1.0 - 1e-12) && rcond <= 3.0 * exact);
+}
+
+#[test]
+fn test_cholesky_factorization_complex() {
+    let c: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[Complex::new(4.0, 0.0), Complex::new(1.0, 1.0),
+          Complex::new(1.0, -1.0), Complex::new(3.0, 0.0)]);
+    let x = DVector { at: vec![Complex::new(1.0, 0.0), Complex::new(0.0, 1.0)] };
+    let q = c.cholesky_factorization().unwrap().quadratic_form(&x).unwrap();
+    assert!((q - 0.9).abs() < 1e-12);
+}
+
+#[test]
+fn test_eigensystem_row_vectors() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, -1.0, 2.0,
+          1.0, 0.0, 1.0,
+          0.0, 0.0, 3.0]);
+    let (values, columns) = a.clone().eigensystem().unwrap();
+    let (row_values, rows) = a.eigensystem_row_vectors().unwrap();
+    assert_eq!(values.at, row_values.at);
+    for i in 0..3 {
+        for k in 0..3 {
+            assert_eq!(rows[(i, k)], columns[(k, i)]);
+        }
+    }
+}
+
+#[test]
+fn test_svd_handle() {
+    // Rank 2, and larger than the matrices handled by the closed-form SVD.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 5,
+        &[1.0, 2.0, 0.0, 1.0, 3.0,
+          0.0, 1.0, 1.0, 2.0, 1.0,
+          1.0, 3.0, 1.0, 3.0, 4.0,
+          2.0, 4.0, 0.0, 2.0, 6.0]);
+    let handle = SvdHandle::new(a.clone());
+    assert!(!handle.is_computed());
+    let before = routine_call_count();
+
+    assert_eq!(handle.rank().unwrap(), 2);
+    let null = handle.null_space().unwrap();
+    assert_eq!((null.nrows(), null.ncols()), (5, 3));
+    for x in (&a * &null).as_vector().iter() {
+        assert!(x.abs() < 1e-12);
+    }
+    let pinv = handle.pseudoinverse().unwrap();
+    let apa = &(&a * &pinv) * &a;
+    for (x, y) in apa.as_vector().iter().zip(a.as_vector().iter()) {
+        assert!((x - y).abs() < 1e-12);
+    }
+    assert!(handle.condition_number().unwrap() > 1e12);
+
+    assert_eq!(routine_call_count(), before + 1);
+}
+
+#[test]
+fn test_ridge_path() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    let b = DVector { at: vec![1.0, 2.5, 2.9, 4.2] };
+    let lambdas = [0.0, 0.1, 1.0, 10.0];
+
+    let path = a.clone().ridge_path(b.clone(), &lambdas).unwrap();
+    assert_eq!((path.nrows(), path.ncols()), (2, lambdas.len()));
+    let bm = DMatrix::from_column_vector(4, 1, &b.at);
+    for (j, &lambda) in lambdas.iter().enumerate() {
+        let x = a.clone().ridge_least_squares(bm.clone(), lambda).unwrap();
+        for i in 0..2 {
+            assert!((path[(i, j)] - x[(i, 0)]).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_solve_hpd() {
+    let c = |re, im| Complex::new(re, im);
+    let a: DMatrix<Complex<f64>> = DMatrix::from_row_vector(3, 3,
+        &[c(4.0, 0.0), c(1.0, 1.0), c(0.0, -0.5),
+          c(1.0, -1.0), c(3.0, 0.0), c(0.5, 0.0),
+          c(0.0, 0.5), c(0.5, 0.0), c(2.0, 0.0)]);
+    let b: DMatrix<Complex<f64>> = DMatrix::from_row_vector(3, 2,
+        &[c(1.0, 0.0), c(0.0, 1.0),
+          c(2.0, -1.0), c(1.0, 0.0),
+          c(0.0, 3.0), c(-1.0, 1.0)]);
+    let x = a.clone().solve_hpd(b.clone()).unwrap();
+    let expected = a.solve(b).unwrap();
+    for (xi, ei) in x.as_vector().iter().zip(expected.as_vector().iter()) {
+        assert!((*xi - *ei).norm() < 1e-12);
+    }
+
+    let indefinite: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[c(1.0, 0.0), c(0.0, 2.0),
+          c(0.0, -2.0), c(1.0, 0.0)]);
+    assert!(indefinite.solve_hpd(DMatrix::new_identity(2)).is_none());
+}
+
+#[test]
+fn test_qr_compact() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(5, 3,
+        &[1.0, 2.0, 0.0,
+          0.0, 1.0, 1.0,
+          1.0, 0.0, 3.0,
+          2.0, 1.0, 1.0,
+          -1.0, 4.0, 2.0]);
+    let qr = a.clone().qr_compact().unwrap();
+    let q = qr.q().unwrap();
+    assert!(na::approx_eq(&(&q * &qr.r()), &a));
+
+    // `Q [x; 0]` only involves the thin factor.
+    let x: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, -2.0,
+          0.5, 3.0,
+          2.0, 1.0]);
+    let padded = DMatrix::from_fn(5, 2, |i, j| if i < 3 { x[(i, j)] } else { 0.0 });
+    assert!(na::approx_eq(&qr.apply_q(padded).unwrap(), &(&q * &x)));
+
+    let y: DMatrix<f64> = DMatrix::from_column_vector(5, 1, &[1.0, 2.0, 3.0, 4.0, 5.0]);
+    let qty = qr.apply_q_transpose(y.clone()).unwrap();
+    let expected = &q.transpose() * &y;
+    for i in 0..3 {
+        assert!((qty[(i, 0)] - expected[(i, 0)]).abs() < 1e-12);
+    }
+    assert!(na::approx_eq(&qr.apply_q(qty).unwrap(), &y));
+}
+
+#[test]
+fn test_solve_normal_equations() {
+    // Nearly dependent columns: cond(a) is about 1e6, so cond(a^T a) is about 1e12.
+    let a: DMatrix<f64> = DMatrix::from_fn(6, 2, |i, j| {
+        if j == 0 { 1.0 } else { 1.0 + 1e-6 * i as f64 }
+    });
+    let x_true: DMatrix<f64> = DMatrix::from_column_vector(2, 1, &[1.0, 2.0]);
+    let b = &a * &x_true;
+    let error = |x: &DMatrix<f64>| {
+        (0..2).fold(0.0f64, |acc, i| acc.max((x[(i, 0)] - x_true[(i, 0)]).abs()))
+    };
+
+    let x_qr = a.clone().solve_normal_equations(b.clone()).unwrap();
+    let at = a.transpose();
+    let x_formula = (&at * &a).solve(&at * &b).unwrap();
+    assert!(error(&x_qr) < 1e-6);
+    assert!(error(&x_qr) < error(&x_formula));
+}
+
+#[test]
+fn test_matrix_block_in_place() {
+    let parent: DMatrix<f64> = DMatrix::from_fn(6, 5, |i, j| {
+        1.0 / (i + 2 * j + 1) as f64 + if i == j { 1.0 } else { 0.0 }
+    });
+    let block = DMatrix::from_fn(4, 4, |i, j| parent[(i + 1, j + 1)]);
+
+    let (_, s, _) = block.clone().svd().unwrap();
+    let mut a = parent.clone();
+    let (_, s_block, _) = MatrixBlock::new(&mut a, 1..5, 1..5).unwrap().svd().unwrap();
+    for (x, y) in s.at.iter().zip(s_block.at.iter()) {
+        assert!((x - y).abs() < 1e-12);
+    }
+    // The block was passed with the leading dimension of the parent, without a copy.
+    let call = last_routine().unwrap();
+    assert_eq!((call.name, call.nrows, call.lda), ("dgesvd", 4, 6));
+    for j in 0..5 {
+        for i in 0..6 {
+            if !((1..5).contains(&i) && 1 <= j) {
+                assert_eq!(a[(i, j)], parent[(i, j)]);
+            }
+        }
+    }
+
+    let (values, _) = block.eigensystem().unwrap();
+    let mut a = parent.clone();
+    let (values_block, _) = MatrixBlock::new(&mut a, 1..5, 1..5).unwrap().eigensystem().unwrap();
+    for (x, y) in values.at.iter().zip(values_block.at.iter()) {
+        assert!((*x - *y).norm() < 1e-12);
+    }
+    assert_eq!(last_routine().unwrap().lda, 6);
+}
+
+#[test]
+fn test_matrix_block_empty() {
+    let mut a: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 2.0, 3.0, 4.0]);
+    for (rows, cols) in [(2..2, 2..2), (0..2, 2..2), (2..2, 0..2), (1..1, 0..1)].iter() {
+        let block = MatrixBlock::new(&mut a, rows.clone(), cols.clone()).unwrap();
+        assert_eq!((block.nrows(), block.ncols()), (rows.len(), cols.len()));
+        let copy = block.to_matrix();
+        assert_eq!((copy.nrows(), copy.ncols()), (rows.len(), cols.len()));
+    }
+    assert!(MatrixBlock::new(&mut a, 2..3, 0..0).is_err());
+}
+
+#[test]
+fn test_participation_factors() {
+    // A damped oscillator coupled to a first-order state.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, 1.0, 0.0,
+          -4.0, -0.4, 0.5,
+          0.2, 0.0, -2.0]);
+    let eigen = EigenResult::from(a.eigensystem().unwrap());
+    let p = eigen.participation_factors().unwrap();
+    for i in 0..3 {
+        let sum = (0..3).fold(Complex::new(0.0, 0.0), |acc, k| acc + p[(k, i)]);
+        assert!((sum - Complex::new(1.0, 0.0)).norm() < 1e-12);
+    }
+}
+
+#[test]
+fn test_determinant_scaled() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, -1.0, 0.5,
+          1.0, 3.0, 2.0,
+          -4.0, 1.0, 1.0]);
+    let det = a.clone().lu().unwrap().determinant();
+    let (mantissa, exponent) = a.determinant_scaled().unwrap();
+    assert!(0.5 <= mantissa.abs() && mantissa.abs() < 1.0);
+    assert!((mantissa * 2.0f64.powi(exponent) - det).abs() < 1e-12 * det.abs());
+
+    // The determinant 1e350 overflows, but its scaled form does not.
+    let big: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, 1e200, 0.0,
+          1e200, 0.0, 0.0,
+          0.0, 0.0, 1e-50]);
+    let (mantissa, exponent) = big.determinant_scaled().unwrap();
+    assert!(mantissa < 0.0);
+    let log10 = (-mantissa).log10() + exponent as f64 * 2.0f64.log10();
+    assert!((log10 - 350.0).abs() < 1e-10);
+
+    // The empty product is one.
+    let empty: DMatrix<f64> = DMatrix::new_zeros(0, 0);
+    assert_eq!(empty.determinant_scaled().unwrap(), (0.5, 1));
+}
+
+#[test]
+fn test_least_squares_qr() {
+    let b: DMatrix<f64> = DMatrix::from_column_vector(4, 1, &[1.0, 2.5, 2.9, 4.2]);
+    let full: DMatrix<f64> = DMatrix::from_row_vector(4, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0]);
+    // The third column is the sum of the first two.
+    let deficient: DMatrix<f64> = DMatrix::from_row_vector(4, 3,
+        &[1.0, 0.0, 1.0,
+          1.0, 1.0, 2.0,
+          1.0, 2.0, 3.0,
+          1.0, 3.0, 4.0]);
+
+    for (a, expected_rank) in [(full, 2), (deficient, 2)] {
+        let (x, rank) = a.clone().least_squares_qr(b.clone(), 1e-10).unwrap();
+        let (x_svd, rank_svd) = a.solve_reporting_rank(b.clone()).unwrap();
+        assert_eq!(rank, expected_rank);
+        assert_eq!(rank, rank_svd);
+        for (xi, yi) in x.as_vector().iter().zip(x_svd.as_vector().iter()) {
+            assert!((xi - yi).abs() < 1e-10);
+        }
+    }
+}
+
+#[test]
+fn test_matrix_cos_sin() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, -1.0, 0.5,
+          -1.0, 1.5, 0.3,
+          0.5, 0.3, -0.7]);
+    let c = a.clone().matrix_cos().unwrap();
+    let s = a.matrix_sin().unwrap();
+    let sum = &c * &c + &s * &s;
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((sum[(i, j)] - expected).abs() < 1e-12);
+        }
+    }
+
+    let d: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.3, 0.0, 0.0,
+          0.0, -1.2, 0.0,
+          0.0, 0.0, 2.5]);
+    let c = d.clone().matrix_cos().unwrap();
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { d[(i, i)].cos() } else { 0.0 };
+            assert!((c[(i, j)] - expected).abs() < 1e-14);
+        }
+    }
+}
+
+#[test]
+fn test_svd_orthogonality_error() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 3,
+        &[2.0, -1.0, 0.5,
+          0.3, 4.0, 1.2,
+          -1.5, 0.7, 3.0,
+          1.0, 1.0, -2.0]);
+    let svd = m.svd_with(Default::default()).unwrap();
+    let (u_error, v_error) = svd.orthogonality_error();
+    assert!(u_error < 1e-14);
+    assert!(v_error < 1e-14);
+}
+
+#[test]
+fn test_pseudo_determinant() {
+    // The third row is the sum of the first two, so the rank is two.
+    let m: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, -1.0, 0.5,
+          0.3, 4.0, 1.2,
+          2.3, 3.0, 1.7]);
+    let s = m.clone().svd_with(Default::default()).unwrap().s;
+    assert!(s[2] < 1e-12 * s[0]);
+    let pdet = m.pseudo_determinant(1e-10).unwrap();
+    assert!((pdet - s[0] * s[1]).abs() < 1e-12 * pdet);
+}
+
+#[test]
+fn test_symmetric_generalized_eigensystem_range() {
+    let k: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[2.0, -1.0, 0.0, 0.0,
+          -1.0, 2.0, -1.0, 0.0,
+          0.0, -1.0, 2.0, -1.0,
+          0.0, 0.0, -1.0, 1.0]);
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[4.0, 1.0, 0.0, 0.0,
+          1.0, 4.0, 1.0, 0.0,
+          0.0, 1.0, 4.0, 1.0,
+          0.0, 0.0, 1.0, 2.0]);
+
+    let (values, vectors) = k.clone().symmetric_generalized_eigensystem_range(m.clone(), 0, 1)
+        .unwrap();
+    let k_band = BandedMatrix::from_dense(&k, 3).unwrap();
+    let m_band = BandedMatrix::from_dense(&m, 3).unwrap();
+    let (all_values, _) = k_band.generalized_eigensystem(m_band, false).unwrap();
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(vectors.ncols(), 2);
+    for j in 0..2 {
+        assert!((values[j] - all_values[j]).abs() < 1e-12);
+        let x = DMatrix::from_fn(4, 1, |i, _| vectors[(i, j)]);
+        let residual = &k * &x - (&m * &x) * values[j];
+        assert!(residual.as_vector().iter().all(|r| r.abs() < 1e-12));
+    }
+}
+
+#[test]
+fn test_cholesky_of_inverse() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, -0.7,
+          0.5, -0.7, 2.0]);
+    let m = a.clone().cholesky_of_inverse().unwrap();
+    for i in 0..3 {
+        assert!(m[(i, i)] > 0.0);
+        for j in (i + 1)..3 {
+            assert_eq!(m[(i, j)], 0.0);
+        }
+    }
+    // `M M^T` is the inverse of `A`.
+    let product = &(&m * &m.transpose()) * &a;
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if i == j { 1.0 } else { 0.0 };
+            assert!((product[(i, j)] - expected).abs() < 1e-12);
+        }
+    }
+
+    let indefinite: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 2.0, 2.0, 1.0]);
+    assert!(indefinite.cholesky_of_inverse().is_none());
+}
+
+#[test]
+fn test_upper_triangle() {
+    let full: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, -0.7,
+          0.5, -0.7, 2.0]);
+    let upper = DMatrix::from_fn(3, 3, |i, j| if i <= j { full[(i, j)] } else { 0.0 });
+
+    let (values, vectors) = upper.clone().symmetric_eigensystem_with_triangle(Triangle::Upper)
+        .unwrap();
+    let (expected_values, _) = full.clone().symmetric_eigensystem().unwrap();
+    let only_values = upper.clone().symmetric_eigenvalues_with_triangle(Triangle::Upper)
+        .unwrap();
+    for j in 0..3 {
+        assert!((values[j] - expected_values[j]).abs() < 1e-12);
+        assert!((only_values[j] - expected_values[j]).abs() < 1e-12);
+        let x = DMatrix::from_fn(3, 1, |i, _| vectors[(i, j)]);
+        let residual = &full * &x - x.clone() * values[j];
+        assert!(residual.as_vector().iter().all(|r| r.abs() < 1e-12));
+    }
+
+    let l = upper.cholesky_with_triangle(Triangle::Upper).unwrap();
+    let reconstructed = &l * &l.transpose();
+    for i in 0..3 {
+        for j in 0..3 {
+            assert!((reconstructed[(i, j)] - full[(i, j)]).abs() < 1e-12);
+        }
+    }
+
+    let a: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[Complex::new(4.0, 0.0), Complex::new(1.0, 2.0),
+          Complex::new(0.0, 0.0), Complex::new(3.0, 0.0)]);
+    let b: DMatrix<Complex<f64>> = DMatrix::from_column_vector(2, 1,
+        &[Complex::new(1.0, 0.0), Complex::new(0.0, 1.0)]);
+    let x = a.solve_hpd_with_triangle(b.clone(), Triangle::Upper).unwrap();
+    let hermitian: DMatrix<Complex<f64>> = DMatrix::from_row_vector(2, 2,
+        &[Complex::new(4.0, 0.0), Complex::new(1.0, 2.0),
+          Complex::new(1.0, -2.0), Complex::new(3.0, 0.0)]);
+    let residual = &hermitian * &x - b;
+    assert!(residual.as_vector().iter().all(|r| r.norm() < 1e-12));
+}
+
+#[test]
+fn test_inverse_operator_norm() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          -1.0, 3.0, -0.7,
+          0.5, 2.0, 2.0]);
+    let norm = a.clone().inverse_operator_norm().unwrap();
+    let (_, s, _) = a.inv().unwrap().svd().unwrap();
+    assert!((norm - s[0]).abs() < 1e-12 * s[0]);
+
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 2.0, 2.0, 4.0]);
+    let norm = singular.inverse_operator_norm();
+    assert!(norm.map_or(true, |x| x > 1e12));
+}
+
+#[test]
+fn test_laplacian_eigenmaps() {
+    // Two triangles, {0, 1, 2} and {3, 4, 5}, joined by a weak edge between 2 and 3.
+    let mut w: DMatrix<f64> = DMatrix::new_zeros(6, 6);
+    let edges = [(0, 1, 1.0), (0, 2, 1.0), (1, 2, 1.0), (3, 4, 1.0), (3, 5, 1.0), (4, 5, 1.0),
+                 (2, 3, 0.1)];
+    for &(i, j, weight) in edges.iter() {
+        w[(i, j)] = weight;
+        w[(j, i)] = weight;
+    }
+    let embedding = laplacian_eigenmaps(w, 2).unwrap();
+    assert_eq!(embedding.nrows(), 6);
+    assert_eq!(embedding.ncols(), 2);
+
+    let fiedler: Vec<f64> = (0..6).map(|i| embedding[(i, 0)]).collect();
+    let sign = fiedler[0].signum();
+    for i in 0..3 {
+        assert_eq!(fiedler[i].signum(), sign);
+        assert_eq!(fiedler[i + 3].signum(), -sign);
+    }
+}
+
+#[test]
+fn test_diagonal_fast_path() {
+    let d: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[3.0, 0.0, 0.0, 0.0,
+          0.0, -5.0, 1e-17, 0.0,
+          0.0, 0.0, 1.0, 0.0,
+          0.0, 0.0, 0.0, -2.0]);
+
+    let before = routine_call_count();
+    let (u, s, vt) = d.clone().svd_diagonal_fast(1e-15).unwrap();
+    let (values, vectors) = d.clone().eigensystem_diagonal_fast(1e-15).unwrap();
+    assert_eq!(routine_call_count(), before);
+
+    let (_, s_lapack, _) = d.clone().svd().unwrap();
+    for i in 0..4 {
+        assert!((s[i] - s_lapack[i]).abs() < 1e-12);
+    }
+    let sigma = DMatrix::from_fn(4, 4, |i, j| if i == j { s[i] } else { 0.0 });
+    let reconstructed = &(&u * &sigma) * &vt;
+    for i in 0..4 {
+        for j in 0..4 {
+            assert!((reconstructed[(i, j)] - d[(i, j)]).abs() < 1e-15);
+        }
+    }
+
+    let (values_lapack, _) = d.clone().eigensystem().unwrap();
+    for i in 0..4 {
+        assert!((values[i] - values_lapack[i]).norm() < 1e-12);
+        assert_eq!(vectors[(i, i)], Complex::new(1.0, 0.0));
+    }
+
+    // With a zero tolerance, the small off-diagonal element means LAPACK is used.
+    let before = routine_call_count();
+    d.svd_diagonal_fast(0.0).unwrap();
+    assert_eq!(routine_call_count(), before + 1);
+}
+
+#[test]
+fn test_generalized_eigensystem_sorted() {
+    // The eigenvalues are 3, -1 and 0.5, and the singular `b` adds an infinite one.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[3.0, 0.0, 0.0, 0.0,
+          0.0, -1.0, 0.0, 0.0,
+          0.0, 0.0, 0.5, 0.0,
+          0.0, 0.0, 0.0, 1.0]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[1.0, 0.0, 0.0, 0.0,
+          0.0, 1.0, 0.0, 0.0,
+          0.0, 0.0, 1.0, 0.0,
+          0.0, 0.0, 0.0, 0.0]);
+    let (values, vectors) = a.generalized_eigensystem_sorted(b, 1e-12).unwrap();
+
+    let expected = [0.5, -1.0, 3.0];
+    for j in 0..3 {
+        let lambda = values[j].finite().unwrap();
+        assert!((lambda - Complex::new(expected[j], 0.0)).norm() < 1e-12);
+    }
+    assert!(values[3].is_infinite());
+
+    // Each eigenvector is the unit vector of its eigenvalue's diagonal position.
+    let positions = [2, 1, 0, 3];
+    for j in 0..4 {
+        for i in 0..4 {
+            let magnitude = vectors[(i, j)].norm();
+            if i == positions[j] {
+                assert!((magnitude - 1.0).abs() < 1e-12);
+            } else {
+                assert!(magnitude < 1e-12);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_inverse_norm1_estimate() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          -1.0, 3.0, -0.7,
+          0.5, 2.0, 0.2]);
+    let norm1 = |m: &DMatrix<f64>| {
+        (0..m.ncols()).map(|j| (0..m.nrows()).fold(0.0, |acc, i| acc + m[(i, j)].abs()))
+            .fold(0.0, f64::max)
+    };
+    let exact = norm1(&a) * norm1(&a.clone().inv().unwrap());
+
+    let lu = a.clone().lu().unwrap();
+    let estimate = norm1(&a) * lu.inverse_norm1_estimate().unwrap();
+    assert!(estimate <= exact * (1.0 + 1e-12));
+    assert!(estimate >= exact / 3.0);
+
+    let empty: DMatrix<f64> = DMatrix::new_zeros(0, 0);
+    assert_eq!(empty.lu().unwrap().inverse_norm1_estimate().unwrap(), 0.0);
+}
+
+#[test]
+fn test_least_squares_with_residual() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(5, 2,
+        &[1.0, 0.0,
+          1.0, 1.0,
+          1.0, 2.0,
+          1.0, 3.0,
+          1.0, 4.0]);
+    let b: DMatrix<f64> = DMatrix::from_column_vector(5, 1, &[1.1, 1.9, 3.2, 3.8, 5.1]);
+    let (x, r) = a.clone().least_squares_with_residual(b.clone()).unwrap();
+
+    let expected = b - &a * &x;
+    for i in 0..5 {
+        assert!((r[(i, 0)] - expected[(i, 0)]).abs() < 1e-14);
+    }
+    assert!(r.as_vector().iter().any(|x| x.abs() > 1e-3));
+    let projection = &a.transpose() * &r;
+    assert!(projection.as_vector().iter().all(|x| x.abs() < 1e-12));
+}
+
+#[test]
+fn test_quadratic_eigensystem() {
+    // Two uncoupled damped oscillators, with eigenvalues
+    // `-c / 2 +/- i sqrt(k - c^2 / 4)` for unit mass.
+    let m: DMatrix<f64> = DMatrix::new_identity(2);
+    let c: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[0.4, 0.0, 0.0, 1.0]);
+    let k: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[4.0, 0.0, 0.0, 9.0]);
+    let (values, vectors) = quadratic_eigensystem(m.clone(), c.clone(), k.clone()).unwrap();
+    assert_eq!(values.len(), 4);
+    assert_eq!(vectors.nrows(), 2);
+    assert_eq!(vectors.ncols(), 4);
+
+    let expected = [Complex::new(-0.2, 3.96f64.sqrt()), Complex::new(-0.2, -3.96f64.sqrt()),
+                    Complex::new(-0.5, 8.75f64.sqrt()), Complex::new(-0.5, -8.75f64.sqrt())];
+    for e in expected.iter() {
+        assert!(values.at.iter().any(|v| (v.finite().unwrap() - *e).norm() < 1e-10));
+    }
+
+    let to_complex = |a: &DMatrix<f64>| {
+        DMatrix::from_fn(2, 2, |i, j| Complex::new(a[(i, j)], 0.0))
+    };
+    let (m, c, k) = (to_complex(&m), to_complex(&c), to_complex(&k));
+    for j in 0..4 {
+        let lambda = values[j].finite().unwrap();
+        let x = DMatrix::from_fn(2, 1, |i, _| vectors[(i, j)]);
+        let q = DMatrix::from_fn(2, 2, |r, s| {
+            lambda * lambda * m[(r, s)] + lambda * c[(r, s)] + k[(r, s)]
+        });
+        let residual = &q * &x;
+        let norm = x.as_vector().iter().fold(0.0, |acc, z| acc + z.norm_sqr()).sqrt();
+        assert!(residual.as_vector().iter().all(|z| z.norm() < 1e-10 * norm));
+    }
+}
+
+#[test]
+fn test_departure_from_normality() {
+    let symmetric: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, -0.7,
+          0.5, -0.7, 2.0]);
+    assert!(symmetric.departure_from_normality().unwrap() < 1e-14);
+
+    // A Jordan block is as non-normal as it gets.
+    let jordan: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[1.0, 1.0, 0.0, 1.0]);
+    assert!((jordan.departure_from_normality().unwrap() - 2.0f64.sqrt()).abs() < 1e-14);
+
+    let tall: DMatrix<f64> = DMatrix::new_zeros(3, 2);
+    assert!(tall.departure_from_normality().is_err());
+}
+
+#[test]
+fn test_svd_reconstruction_error() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 3,
+        &[2.0, -1.0, 0.5,
+          0.3, 4.0, 1.2,
+          -1.5, 0.7, 3.0,
+          1.0, 1.0, -2.0]);
+    let svd = m.clone().svd_with(Default::default()).unwrap();
+    let error = svd.reconstruction_error(&m).unwrap();
+    assert_eq!((error.nrows(), error.ncols()), (4, 3));
+    assert!(error.as_vector().iter().all(|x| x.abs() < 1e-13));
+
+    let mut perturbed = m.clone();
+    perturbed[(1, 2)] += 0.5;
+    let error = svd.reconstruction_error(&perturbed).unwrap();
+    assert!((error[(1, 2)] + 0.5).abs() < 1e-13);
+    let norm = error.as_vector().iter().fold(0.0, |acc, x| acc + x * x).sqrt();
+    assert!((svd.reconstruction_error_norm(&perturbed).unwrap() - norm).abs() < 1e-15);
+
+    assert!(svd.reconstruction_error(&DMatrix::new_zeros(3, 3)).is_none());
+}
+
+#[test]
+fn test_solve_block_tridiagonal() {
+    // Blocks of sizes 2, 1 and 2.
+    let diag: Vec<DMatrix<f64>> = vec![
+        DMatrix::from_row_vector(2, 2, &[4.0, 1.0, -1.0, 5.0]),
+        DMatrix::from_row_vector(1, 1, &[6.0]),
+        DMatrix::from_row_vector(2, 2, &[3.0, 0.5, 0.2, 4.0]),
+    ];
+    let sub: Vec<DMatrix<f64>> = vec![
+        DMatrix::from_row_vector(1, 2, &[1.0, -0.5]),
+        DMatrix::from_row_vector(2, 1, &[0.3, 1.0]),
+    ];
+    let sup: Vec<DMatrix<f64>> = vec![
+        DMatrix::from_row_vector(2, 1, &[0.7, -1.0]),
+        DMatrix::from_row_vector(1, 2, &[1.5, 0.4]),
+    ];
+    let b: DMatrix<f64> = DMatrix::from_row_vector(5, 2,
+        &[1.0, 0.0,
+          2.0, 1.0,
+          -1.0, 0.5,
+          0.5, 2.0,
+          3.0, -1.0]);
+
+    let mut dense: DMatrix<f64> = DMatrix::new_zeros(5, 5);
+    let offsets = [0, 2, 3];
+    for i in 0..3 {
+        for r in 0..diag[i].nrows() {
+            for c in 0..diag[i].ncols() {
+                dense[(offsets[i] + r, offsets[i] + c)] = diag[i][(r, c)];
+            }
+        }
+    }
+    for i in 0..2 {
+        for r in 0..sub[i].nrows() {
+            for c in 0..sub[i].ncols() {
+                dense[(offsets[i + 1] + r, offsets[i] + c)] = sub[i][(r, c)];
+            }
+        }
+        for r in 0..sup[i].nrows() {
+            for c in 0..sup[i].ncols() {
+                dense[(offsets[i] + r, offsets[i + 1] + c)] = sup[i][(r, c)];
+            }
+        }
+    }
+
+    let x = solve_block_tridiagonal(&diag, &sub, &sup, b.clone()).unwrap();
+    let expected = dense.solve(b.clone()).unwrap();
+    for (xi, ei) in x.as_vector().iter().zip(expected.as_vector().iter()) {
+        assert!((xi - ei).abs() < 1e-12);
+    }
+
+    match solve_block_tridiagonal(&diag, &sup, &sub, b) {
+        Err(nalgebra_lapack::NalgebraLapackError(
+            nalgebra_lapack::errors::ErrorKind::DimensionMismatch, _)) => {}
+        _ => panic!("expected a DimensionMismatch error"),
+    }
+}
+
+#[test]
+fn test_eigensystem_real_if_symmetric() {
+    let symmetric: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, -0.7,
+          0.5, -0.7, 2.0]);
+    match symmetric.clone().eigensystem_real_if_symmetric(1e-12).unwrap() {
+        RealOrComplexEigensystem::Real(values, _) => {
+            let (expected, _) = symmetric.symmetric_eigensystem().unwrap();
+            assert_eq!(values, expected);
+        }
+        RealOrComplexEigensystem::Complex(..) => panic!("expected a real eigensystem"),
+    }
+
+    // A rotation has the eigenvalues `+/- i`.
+    let rotation: DMatrix<f64> = DMatrix::from_row_vector(2, 2, &[0.0, -1.0, 1.0, 0.0]);
+    match rotation.eigensystem_real_if_symmetric(1e-12).unwrap() {
+        RealOrComplexEigensystem::Complex(values, _) => {
+            assert!(values.at.iter().all(|v| (v.im.abs() - 1.0).abs() < 1e-12));
+        }
+        RealOrComplexEigensystem::Real(..) => panic!("expected a complex eigensystem"),
+    }
+}
+
+#[test]
+fn test_polar_newton() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(6, 3,
+        &[2.0, -1.0, 0.5,
+          0.3, 4.0, 1.2,
+          -1.5, 0.7, 3.0,
+          1.0, 1.0, -2.0,
+          0.2, -0.3, 0.8,
+          1.7, 0.4, 0.1]);
+    let (u, h) = a.clone().polar_newton().unwrap();
+    assert_eq!((u.nrows(), u.ncols()), (6, 3));
+    assert_eq!((h.nrows(), h.ncols()), (3, 3));
+
+    // The SVD-based orthogonal polar factor is `P Q^T` from `A = P S Q^T`.
+    let options = nalgebra_lapack::SvdOptions { economy: true, ..Default::default() };
+    let svd = a.clone().svd_with(options).unwrap();
+    let expected = &svd.u.unwrap() * &svd.vt.unwrap();
+    for (x, y) in u.as_vector().iter().zip(expected.as_vector().iter()) {
+        assert!((x - y).abs() < 1e-12);
+    }
+
+    let product = &u * &h;
+    for (x, y) in product.as_vector().iter().zip(a.as_vector().iter()) {
+        assert!((x - y).abs() < 1e-12);
+    }
+    for i in 0..3 {
+        for j in 0..3 {
+            assert_eq!(h[(i, j)], h[(j, i)]);
+        }
+    }
+}
+
+#[test]
+fn test_mutual_coherence() {
+    let nearly_parallel: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 1.0, 0.0,
+          2.0, 2.001, 0.0,
+          0.0, 0.0, 1.0]);
+    let coherence = nearly_parallel.mutual_coherence();
+    assert!(coherence > 0.999999 && coherence <= 1.0);
+
+    let c = 0.6;
+    let s = 0.8;
+    let orthogonal: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[c, -s, 0.0,
+          s, c, 0.0,
+          0.0, 0.0, 1.0]);
+    assert!(orthogonal.mutual_coherence() < 1e-15);
+}
+
+#[test]
+fn test_svd_handle_cutoff() {
+    // Singular values 3, 2 and 1e-9, with a clear gap below 2.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[0.0, 2.0, 0.0,
+          3.0, 0.0, 0.0,
+          0.0, 0.0, 1e-9]);
+    let handle = SvdHandle::new(a.clone());
+    let tol = 1e-6;
+
+    let (rank, cutoff) = handle.rank_with_cutoff(tol).unwrap();
+    assert_eq!(rank, 2);
+    assert_eq!(cutoff.last_retained, Some(1));
+    let discarded = cutoff.first_discarded.unwrap();
+    assert!((discarded - 1e-9).abs() < 1e-15);
+    let s = handle.singular_values().unwrap();
+    assert!(s[1] > tol && discarded <= tol);
+
+    let (pinv, pinv_cutoff) = handle.pseudoinverse_with_cutoff(tol).unwrap();
+    assert_eq!(pinv_cutoff, cutoff);
+    // The discarded singular value is dropped from `A A^+ A`.
+    let apa = &(&a * &pinv) * &a;
+    for (x, y) in apa.as_vector().iter().zip(a.as_vector().iter()) {
+        assert!((x - y).abs() < 1e-8);
+    }
+    assert!(apa[(2, 2)].abs() < 1e-15);
+
+    let none_kept: SvdCutoff<f64> = handle.rank_with_cutoff(10.0).unwrap().1;
+    assert_eq!(none_kept.last_retained, None);
+    assert_eq!(none_kept.first_discarded, Some(s[0]));
+}
+
+#[test]
+fn test_pca_dimension_mle() {
+    // Three strong components above a nearly flat noise floor.
+    let mut s = vec![30.0, 25.0, 20.0];
+    s.extend((1..8).rev().map(|k| 1.0 + 0.01 * k as f64));
+    let spectrum: DVector<f64> = DVector { at: s };
+    assert_eq!(pca_dimension_mle(&spectrum, 1000), 3);
+
+    let single: DVector<f64> = DVector { at: vec![2.0] };
+    assert_eq!(pca_dimension_mle(&single, 10), 1);
+}
+
+#[test]
+fn test_svd_optimal_shrinkage() {
+    // A rank-two signal plus small, uniformly distributed pseudo-random noise.
+    let n = 40;
+    let clean: DMatrix<f64> = DMatrix::from_fn(n, n, |i, j| {
+        let (x, y) = (i as f64 / n as f64, j as f64 / n as f64);
+        (1.0 + x) * (2.0 - y) + (3.0 * x).sin() * (5.0 * y).cos()
+    });
+    let mut state: u64 = 12345;
+    let noise = DMatrix::from_fn(n, n, |_, _| {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        0.2 * ((state >> 11) as f64 / (1u64 << 53) as f64 - 0.5)
+    });
+    let noisy = clean.clone() + noise;
+    let svd = noisy.clone().svd_builder().run().unwrap();
+
+    let shrunk = svd.optimal_shrinkage(1.0);
+    assert_eq!(shrunk.len(), n);
+    assert_eq!(shrunk.iter().filter(|x| **x > 0.0).count(), 2);
+    for k in 0..2 {
+        assert_eq!(shrunk[k], svd.s[k]);
+    }
+
+    let distance = |x: &DMatrix<f64>| {
+        (x.clone() - clean.clone()).as_vector().iter().fold(0.0, |acc, d| acc + d * d).sqrt()
+    };
+    let denoised = svd.denoise(1.0).unwrap();
+    assert!(distance(&denoised) < 0.5 * distance(&noisy));
+}
+
+#[test]
+fn test_lu_permutation_vector() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[1.0, 2.0, 0.0, 1.0,
+          4.0, 1.0, 3.0, 0.0,
+          2.0, 5.0, 1.0, 2.0,
+          0.0, 1.0, 6.0, 1.0]);
+    let (perm, l, u) = a.clone().lu_with_permutation_vector().unwrap();
+    assert_eq!(perm.len(), 4);
+    let p = DMatrix::from_fn(4, 4, |i, j| if perm[i] == j { 1.0 } else { 0.0 });
+
+    // `P A = L U`.
+    let pa = &p * &a;
+    let product = &l * &u;
+    for (x, y) in pa.as_vector().iter().zip(product.as_vector().iter()) {
+        assert!((x - y).abs() < 1e-12);
+    }
+
+    let v: DVector<f64> = DVector { at: vec![1.0, -2.0, 3.0, 0.5] };
+    let permuted = apply_permutation(&perm, &v);
+    let expected = &p * &v;
+    assert_eq!(permuted.at, expected.at);
+}
+
+#[test]
+fn test_eigenvalue_separations() {
+    let separated: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 0.5, 0.2,
+          0.0, 5.0, 0.3,
+          0.0, 0.0, 10.0]);
+    let result = EigenResult::from(separated.clone().eigensystem().unwrap());
+    let sep = result.eigenvalue_separations(&separated).unwrap();
+    assert_eq!(sep.len(), 3);
+    for x in sep.iter() {
+        assert!(*x > 1.0);
+    }
+
+    // Two nearly coincident eigenvalues, coupled by the off-diagonal element.
+    let close: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 1.0, 0.0,
+          0.0, 1.0 + 1e-8, 0.0,
+          0.0, 0.0, 4.0]);
+    let result = EigenResult::from(close.clone().eigensystem().unwrap());
+    let sep = result.eigenvalue_separations(&close).unwrap();
+    for (lambda, x) in result.eigen_values.iter().zip(sep.iter()) {
+        if (lambda.re - 4.0).abs() < 1e-6 {
+            assert!(*x > 1.0);
+        } else {
+            assert!(*x < 1e-6);
+        }
+    }
+}
+
+#[test]
+fn test_qr_append_row() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(4, 3,
+        &[1.0, 2.0, 0.0,
+          0.0, 1.0, 1.0,
+          1.0, 0.0, 3.0,
+          2.0, 1.0, 1.0]);
+    let rows = [[-1.0, 4.0, 2.0], [3.0, -2.0, 0.5]];
+    let mut qr = a.clone().qr_compact().unwrap();
+    for row in rows.iter() {
+        qr.append_row(DVector { at: row.to_vec() }).unwrap();
+    }
+    assert_eq!(qr.nrows(), 6);
+    let augmented = DMatrix::from_fn(6, 3, |i, j| if i < 4 { a[(i, j)] } else { rows[i - 4][j] });
+
+    // The rows of `R` are unique up to their signs.
+    let fresh = augmented.clone().qr_compact().unwrap().r();
+    let updated = qr.r();
+    for i in 0..3 {
+        let sign = if updated[(i, i)] * fresh[(i, i)] < 0.0 { -1.0 } else { 1.0 };
+        for j in 0..3 {
+            assert!((updated[(i, j)] - sign * fresh[(i, j)]).abs() < 1e-12);
+        }
+    }
+
+    let q = qr.q().unwrap();
+    assert_eq!((q.nrows(), q.ncols()), (6, 3));
+    assert!(na::approx_eq(&(&q * &updated), &augmented));
+    let y: DMatrix<f64> = DMatrix::from_column_vector(6, 1, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    let qty = qr.apply_q_transpose(y.clone()).unwrap();
+    assert!(na::approx_eq(&qr.apply_q(qty).unwrap(), &y));
+
+    assert!(qr.append_row(DVector { at: vec![1.0, 2.0] }).is_err());
+}
+
+#[test]
+fn test_trace_of_inverse() {
+    let spd: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, 1.0, 0.5,
+          1.0, 3.0, 0.2,
+          0.5, 0.2, 2.0]);
+    let inverse = spd.clone().inv().unwrap();
+    let expected = (0..3).fold(0.0, |acc, i| acc + inverse[(i, i)]);
+    assert!((spd.trace_of_inverse().unwrap() - expected).abs() < 1e-12);
+
+    let general: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 1.0, 0.0,
+          -1.0, 3.0, 1.0,
+          0.5, 0.0, 1.0]);
+    let inverse = general.clone().inv().unwrap();
+    let expected = (0..3).fold(0.0, |acc, i| acc + inverse[(i, i)]);
+    assert!((general.trace_of_inverse().unwrap() - expected).abs() < 1e-12);
+
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          1.0, 2.0]);
+    assert!(singular.trace_of_inverse().is_none());
+}
+
+#[test]
+fn test_least_squares_complex() {
+    let c = |re: f64, im: f64| Complex { re, im };
+    let a: DMatrix<Complex<f64>> = DMatrix::from_row_vector(3, 2,
+        &[c(1.0, 1.0), c(2.0, 0.0),
+          c(0.0, 0.0), c(1.0, -1.0),
+          c(3.0, 0.0), c(0.0, 1.0)]);
+    let x0 = [c(1.0, -2.0), c(0.5, 1.0)];
+    // `r = conj(a_1 x a_2)` is orthogonal to both columns in the Hermitian inner product, so
+    // the least-squares solution of `a x = a x0 + r` is `x0`.
+    let (a1, a2) = ((0..3).map(|i| a[(i, 0)]).collect::<Vec<_>>(),
+                    (0..3).map(|i| a[(i, 1)]).collect::<Vec<_>>());
+    let r = [(a1[1] * a2[2] - a1[2] * a2[1]).conj(),
+             (a1[2] * a2[0] - a1[0] * a2[2]).conj(),
+             (a1[0] * a2[1] - a1[1] * a2[0]).conj()];
+    let b = DMatrix::from_fn(3, 1, |i, _| a[(i, 0)] * x0[0] + a[(i, 1)] * x0[1] + r[i]);
+
+    let x = a.clone().least_squares_complex(b.clone()).unwrap();
+    assert_eq!((x.nrows(), x.ncols()), (2, 1));
+    for k in 0..2 {
+        assert!((x[(k, 0)] - x0[k]).norm() < 1e-12);
+    }
+
+    // The normal equations with the plain transpose give a different, wrong answer.
+    let at = a.transpose();
+    let naive = (&at * &a).solve(&at * &b).unwrap();
+    assert!((0..2).any(|k| (naive[(k, 0)] - x0[k]).norm() > 1e-3));
+}
+
+#[test]
+fn test_factorization_diagnostics() {
+    let well: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, -2.0, 1.0,
+          -2.0, 4.0, -2.0,
+          1.0, -2.0, 3.0]);
+    let (lu, diagnostics) = well.clone().lu_with_diagnostics().unwrap();
+    let (_, rcond) = well.clone().factor_lu_with_condition().unwrap();
+    assert_eq!(diagnostics.rcond, rcond);
+    assert_eq!(diagnostics.rank, 3);
+    assert!(!diagnostics.near_zero_pivots);
+    assert!(diagnostics.condition_number() < 10.0);
+    assert_eq!(lu.factors().nrows(), 3);
+    for diagnostics in [well.clone().qr_with_diagnostics().unwrap().1,
+                        well.cholesky_with_diagnostics().unwrap().1] {
+        assert!(diagnostics.rcond > 0.1);
+        assert_eq!(diagnostics.rank, 3);
+        assert!(!diagnostics.near_zero_pivots);
+    }
+
+    // The second column is exactly twice the first.
+    let singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          2.0, 4.0]);
+    let (_, diagnostics) = singular.clone().lu_with_diagnostics().unwrap();
+    assert_eq!(diagnostics.rank, 1);
+    assert!(diagnostics.near_zero_pivots);
+    assert_eq!(diagnostics.condition_number(), f64::INFINITY);
+    let (_, diagnostics) = singular.qr_with_diagnostics().unwrap();
+    assert!(diagnostics.rcond < 1e-10);
+    assert_eq!(diagnostics.rank, 1);
+
+    // Positive definite, but with a condition number of about `4e12`.
+    let near_singular: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 1.0,
+          1.0, 1.0 + 1e-12]);
+    let (cholesky, diagnostics) = near_singular.cholesky_with_diagnostics().unwrap();
+    assert_eq!(cholesky.lower().nrows(), 2);
+    assert!(diagnostics.rcond < 1e-10);
+    assert!(diagnostics.condition_number() > 1e10);
+}
+
+#[test]
+fn test_spd_geometric_mean() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, -2.0, 1.0,
+          -2.0, 4.0, -2.0,
+          1.0, -2.0, 3.0]);
+    let mean = spd_geometric_mean(a.clone(), a.clone()).unwrap();
+    assert!(na::approx_eq(&mean, &a));
+
+    let diagonal = |d: &[f64]| DMatrix::from_fn(3, 3, |i, j| if i == j { d[i] } else { 0.0 });
+    let mean = spd_geometric_mean(diagonal(&[1.0, 4.0, 9.0]), diagonal(&[4.0, 1.0, 1.0]))
+        .unwrap();
+    assert!(na::approx_eq(&mean, &diagonal(&[2.0, 2.0, 3.0])));
+
+    // The mean is symmetric and satisfies the Riccati equation `X A^-1 X = B`.
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 0.5, 0.0,
+          0.5, 1.0, 0.25,
+          0.0, 0.25, 3.0]);
+    let mean = spd_geometric_mean(a.clone(), b.clone()).unwrap();
+    assert!(na::approx_eq(&mean, &mean.transpose()));
+    assert!(na::approx_eq(&(&(&mean * &a.clone().inv().unwrap()) * &mean), &b));
+
+    let indefinite = diagonal(&[1.0, -1.0, 1.0]);
+    assert!(spd_geometric_mean(a.clone(), indefinite.clone()).is_none());
+    assert!(spd_geometric_mean(indefinite, a).is_none());
+}
+
+#[test]
+fn test_eigensystem_deterministic() {
+    // The triangular matrix of `test_eigenvalues_wikipedia_triangular`.
+    let mat = DMatrix::from_row_vector(3, 3, &[1.0, 0.0, 0.0,
+                                               1.0, 2.0, 0.0,
+                                               2.0, 3.0, 3.0]);
+    let (eigen_values, eigen_vectors) = mat.eigensystem_deterministic().unwrap();
+    for (k, expected) in [1.0, 2.0, 3.0].iter().enumerate() {
+        assert!((eigen_values[k] - Complex::new(*expected, 0.0)).norm() < 1e-12);
+    }
+
+    // The largest element of each eigenvector is positive. The first eigenvector has two
+    // elements of equal magnitude, so only its direction is fixed.
+    let first = real_only(&eigen_vectors.column_slice(0, 0, 3));
+    let expected = DVector { at: vec![1.0, -1.0, 0.5] }.normalize();
+    assert!(na::approx_eq(&first, &expected) || na::approx_eq(&-first, &expected));
+    let expected = [DVector { at: vec![0.0, -1.0, 3.0] }.normalize(),
+                    DVector { at: vec![0.0, 0.0, 1.0] }];
+    for (k, expected) in expected.iter().enumerate() {
+        let actual = eigen_vectors.column_slice(k + 1, 0, 3);
+        assert!(actual.at.iter().all(|x| x.im.abs() < 1e-12));
+        assert!(na::approx_eq(&real_only(&actual), expected));
+    }
+
+    // A conjugate pair is ordered by imaginary part, with the largest elements made real.
+    let rotation = DMatrix::from_row_vector(2, 2, &[0.0, -1.0,
+                                                    1.0, 0.0]);
+    let (eigen_values, eigen_vectors) = rotation.eigensystem_deterministic().unwrap();
+    assert!((eigen_values[0] - Complex::new(0.0, -1.0)).norm() < 1e-12);
+    assert!((eigen_values[1] - Complex::new(0.0, 1.0)).norm() < 1e-12);
+    for j in 0..2 {
+        let real_and_positive = (0..2).any(|i| {
+            let x = eigen_vectors[(i, j)];
+            x.im == 0.0 && (x.re - 0.5f64.sqrt()).abs() < 1e-12
+        });
+        assert!(real_and_positive);
+    }
+}
+
+#[test]
+fn test_lu_solve_low_rank_update() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[4.0, -2.0, 1.0,
+          -2.0, 4.0, -2.0,
+          1.0, -2.0, 3.0]);
+    let u: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          2.0, -1.0,
+          0.5, 3.0]);
+    let v: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[0.5, 1.0,
+          -1.0, 0.0,
+          2.0, 0.25]);
+    let b: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 0.0,
+          2.0, 1.0,
+          3.0, -1.0]);
+    let lu = a.clone().lu().unwrap();
+    let x = lu.solve_low_rank_update(u.clone(), v.clone(), b.clone()).unwrap();
+    let updated = a.clone() + &u * &v.transpose();
+    let expected = updated.lu().unwrap().solve(b.clone()).unwrap();
+    assert!(na::approx_eq(&x, &expected));
+
+    // A rank-one update, with the factorization of the transpose.
+    let u1 = DMatrix::from_fn(3, 1, |i, _| u[(i, 0)]);
+    let v1 = DMatrix::from_fn(3, 1, |i, _| v[(i, 0)]);
+    let lu = a.clone().lu_as_transpose().unwrap();
+    let x = lu.solve_low_rank_update(u1.clone(), v1.clone(), b.clone()).unwrap();
+    assert!(na::approx_eq(&(&(a + &u1 * &v1.transpose()) * &x), &b));
+
+    // `diag(2, 4, 8) - 2 e_1 e_1^T` is singular, and so is the capacitance matrix.
+    let d: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[2.0, 0.0, 0.0,
+          0.0, 4.0, 0.0,
+          0.0, 0.0, 8.0]);
+    let e1: DMatrix<f64> = DMatrix::from_fn(3, 1, |i, _| if i == 0 { 1.0 } else { 0.0 });
+    let lu = d.lu().unwrap();
+    assert!(lu.solve_low_rank_update(e1.clone() * 2.0, e1 * -1.0, b.clone()).is_none());
+    assert!(lu.solve_low_rank_update(u, v1, b).is_none());
+}
+
+#[test]
+fn test_numerical_abscissa() {
+    // A stable but highly non-normal matrix, whose solutions grow transiently.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[-1.0, 10.0,
+          0.0, -2.0]);
+    let (eigen_values, _) = a.clone().eigensystem().unwrap();
+    let spectral = eigen_values.at.iter().fold(f64::NEG_INFINITY, |acc, x| acc.max(x.re));
+    assert!((spectral + 1.0).abs() < 1e-12);
+
+    // The eigenvalues of `[[-1, 5], [5, -2]]` are `(-3 +- sqrt(101)) / 2`.
+    let omega = a.numerical_abscissa().unwrap();
+    assert!((omega - (-3.0 + 101.0f64.sqrt()) / 2.0).abs() < 1e-12);
+    assert!(omega > 0.0 && omega > spectral);
+
+    // For a symmetric matrix, the two coincide.
+    let s: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[2.0, 1.0,
+          1.0, 2.0]);
+    assert!((s.numerical_abscissa().unwrap() - 3.0).abs() < 1e-12);
+
+    assert!(DMatrix::<f64>::new_zeros(2, 3).numerical_abscissa().is_none());
+}
+
+#[test]
+fn test_matrix_abs() {
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 2,
+        &[1.0, 2.0,
+          -3.0, 0.5,
+          0.0, 4.0]);
+    let h = a.clone().matrix_abs().unwrap();
+    assert_eq!((h.nrows(), h.ncols()), (2, 2));
+    assert!(na::approx_eq(&h, &h.transpose()));
+    assert!(na::approx_eq(&(&h * &h), &(&a.transpose() * &a)));
+    let (values, _) = h.symmetric_eigensystem().unwrap();
+    assert!(values.at.iter().all(|lambda| *lambda > 0.0));
+
+    // For a symmetric matrix, the eigenvalues `3` and `-1` become `3` and `1`.
+    let s: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 2.0,
+          2.0, 1.0]);
+    let expected: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[2.0, 1.0,
+          1.0, 2.0]);
+    assert!(na::approx_eq(&s.matrix_abs().unwrap(), &expected));
+}
+
+#[test]
+fn test_rref() {
+    // The second column is twice the first, and the fourth is the first plus the third.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(3, 4,
+        &[1.0, 2.0, 0.0, 1.0,
+          2.0, 4.0, 1.0, 3.0,
+          3.0, 6.0, 1.0, 4.0]);
+    let (r, pivots) = a.rref(1e-12);
+    assert_eq!(pivots, vec![0, 2]);
+    let expected: DMatrix<f64> = DMatrix::from_row_vector(3, 4,
+        &[1.0, 2.0, 0.0, 1.0,
+          0.0, 0.0, 1.0, 1.0,
+          0.0, 0.0, 0.0, 0.0]);
+    assert!(na::approx_eq(&r, &expected));
+    assert_eq!((r[(2, 0)], r[(2, 2)], r[(0, 2)], r[(1, 0)]), (0.0, 0.0, 0.0, 0.0));
+
+    // A perturbation below the tolerance does not make the second column independent.
+    let a: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[1.0, 1.0,
+          1.0, 1.0 + 1e-14]);
+    assert_eq!(a.clone().rref(1e-10).1, vec![0]);
+    assert_eq!(a.rref(0.0).1, vec![0, 1]);
+}
+
+#[test]
+fn test_schur_complement() {
+    let m: DMatrix<f64> = DMatrix::from_row_vector(4, 4,
+        &[2.0, 1.0, 1.0, 0.0,
+          1.0, 3.0, 0.0, 2.0,
+          4.0, 0.0, 5.0, 1.0,
+          0.0, 1.0, 2.0, 6.0]);
+    // `A^-1 = [[3, -1], [-1, 2]] / 5`, so `C A^-1 B = [[12, -8], [-1, 4]] / 5`.
+    let expected: DMatrix<f64> = DMatrix::from_row_vector(2, 2,
+        &[13.0 / 5.0, 13.0 / 5.0,
+          11.0 / 5.0, 26.0 / 5.0]);
+    let s = m.clone().schur_complement(2).unwrap();
+    assert!(na::approx_eq(&s, &expected));
+
+    assert!(na::approx_eq(&m.clone().schur_complement(0).unwrap(), &m));
+    assert_eq!(m.clone().schur_complement(4).unwrap().nrows(), 0);
+    assert!(m.schur_complement(5).is_none());
+
+    let singular_block: DMatrix<f64> = DMatrix::from_row_vector(3, 3,
+        &[1.0, 2.0, 0.0,
+          2.0, 4.0, 1.0,
+          0.0, 1.0, 1.0]);
+    assert!(singular_block.schur_complement(2).is_none());
+}